@@ -1,19 +1,101 @@
-//! This module implements basics types of the library like point on a curve
-//! and the curve trait.
+//! This module implements basics types of the library like point on a curve,
+//! the curve trait and the field trait for coordinates.
 
 use std::{fmt, mem};
 use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, inv_mod, sqrt_mod};
 
 
-/// Generic type for point on a curve that is a pair of two integers.
+/// `FieldTrait` defines the arithmetic of the coordinates of points, all the
+/// operations take the modulo `m` of the underlying prime field. It is
+/// implemented for `Bigi<N>` (the prime field itself) and extension fields
+/// like `fields::Fp2`.
+pub trait FieldTrait<const N: usize>: Copy + PartialEq + fmt::Debug {
+    /// Gets zero of the field.
+    fn zero() -> Self;
+
+    /// Embeds an element of the prime field.
+    fn from_bigi(x: &Bigi<N>) -> Self;
+
+    /// Returns true if the element is zero.
+    fn is_zero(&self) -> bool;
+
+    /// Sum of the elements.
+    fn add(&self, other: &Self, m: &Bigi<N>) -> Self;
+
+    /// Difference of the elements.
+    fn sub(&self, other: &Self, m: &Bigi<N>) -> Self;
+
+    /// Product of the elements.
+    fn mul(&self, other: &Self, m: &Bigi<N>) -> Self;
+
+    /// Inverse of a non-zero element.
+    fn inv(&self, m: &Bigi<N>) -> Self;
+
+    /// Finds both square roots of the element.
+    fn sqrt(&self, m: &Bigi<N>) -> Result<(Self, Self), &'static str>;
+
+    /// Gets the opposite element.
+    fn neg(&self, m: &Bigi<N>) -> Self {
+        Self::zero().sub(self, m)
+    }
+
+    /// Squares the element.
+    fn square(&self, m: &Bigi<N>) -> Self {
+        self.mul(self, m)
+    }
+}
+
+
+impl<const N: usize> FieldTrait<N> for Bigi<N> {
+    fn zero() -> Self {
+        Bigi::<N>::from(0)
+    }
+
+    fn from_bigi(x: &Bigi<N>) -> Self {
+        *x
+    }
+
+    fn is_zero(&self) -> bool {
+        Bigi::<N>::is_zero(self)
+    }
+
+    fn add(&self, other: &Self, m: &Bigi<N>) -> Self {
+        add_mod(self, other, m)
+    }
+
+    fn sub(&self, other: &Self, m: &Bigi<N>) -> Self {
+        sub_mod(self, other, m)
+    }
+
+    fn mul(&self, other: &Self, m: &Bigi<N>) -> Self {
+        mul_mod(self, other, m)
+    }
+
+    fn inv(&self, m: &Bigi<N>) -> Self {
+        inv_mod(self, m)
+    }
+
+    fn sqrt(&self, m: &Bigi<N>) -> Result<(Self, Self), &'static str> {
+        sqrt_mod(self, m)
+    }
+}
+
+
+/// Generic type for point on a curve that is a pair of two coordinates
+/// from a field.
 #[derive(Copy, Clone)]
-pub struct Point<const N: usize> {
-    pub x: Bigi<N>,
-    pub y: Bigi<N>,
+pub struct GenericPoint<F> {
+    pub x: F,
+    pub y: F,
     pub is_zero: bool
 }
 
 
+/// Point on a curve over integers modulo a prime.
+pub type Point<const N: usize> = GenericPoint<Bigi<N>>;
+
+
 /// A macros to define a point.
 /// ```rust
 /// use bigi::Bigi;
@@ -67,7 +149,7 @@ macro_rules! point_simple {
 }
 
 
-impl<const N: usize> PartialEq for Point<N> {
+impl<F: PartialEq> PartialEq for GenericPoint<F> {
     fn eq(&self, other: &Self) -> bool {
         if self.is_zero == other.is_zero {
             if self.is_zero {
@@ -82,7 +164,7 @@ impl<const N: usize> PartialEq for Point<N> {
 }
 
 
-impl<const N: usize> fmt::Debug for Point<N> {
+impl<F: fmt::Debug> fmt::Debug for GenericPoint<F> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         if self.is_zero {
             write!(f, "{{null}}")
@@ -93,6 +175,14 @@ impl<const N: usize> fmt::Debug for Point<N> {
 }
 
 
+impl<F> GenericPoint<F> {
+    /// Creates a point by its coordinates.
+    pub fn new(x: F, y: F) -> Self {
+        Self { x, y, is_zero: false }
+    }
+}
+
+
 impl<const N: usize> Point<N> {
     /// Converts point to a hex string.
     pub fn to_hex(&self) -> String {
@@ -125,33 +215,38 @@ impl<const N: usize> Point<N> {
 
 
 /// `CurveTrait` is a trait that defines all the necessary methods
-/// to make the algorithms in the library work.
-pub trait CurveTrait<const N: usize> {
+/// to make the algorithms in the library work. The coordinates belong to
+/// the field `F` (integers modulo `get_modulo()` by default), the scalars
+/// are always integers.
+pub trait CurveTrait<const N: usize, F: FieldTrait<N> = Bigi<N>> {
     /// Gets modulo of the curve.
     fn get_modulo(&self) -> Bigi<N>;
 
     /// Gets zero point of the curve.
-    fn zero(&self) -> Point<N>;
+    fn zero(&self) -> GenericPoint<F>;
 
     /// Returns true if the point is on the curve else false.
-    fn check(&self, p: &Point<N>) -> bool;
+    fn check(&self, p: &GenericPoint<F>) -> bool;
+
+    /// Returns true if the curve is singular (its discriminant is zero).
+    fn is_singular(&self) -> bool;
 
     /// Finds `y` coordinates of two points on the curve by given `x`.
-    fn find_y(&self, x: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str>;
+    fn find_y(&self, x: &F) -> Result<(F, F), &'static str>;
 
     /// Gets the inverse of the point.
-    fn inv(&self, p: &Point<N>) -> Point<N>;
+    fn inv(&self, p: &GenericPoint<F>) -> GenericPoint<F>;
 
     /// Sum of the points on the curve.
-    fn add(&self, p: &Point<N>, q: &Point<N>) -> Point<N>;
+    fn add(&self, p: &GenericPoint<F>, q: &GenericPoint<F>) -> GenericPoint<F>;
 
     /// Doubles the point on the curve.
-    fn double(&self, p: &Point<N>) -> Point<N> {
+    fn double(&self, p: &GenericPoint<F>) -> GenericPoint<F> {
         self.add(&p, &p)
     }
 
     /// Multiplies the point by the integer.
-    fn mul(&self, p: &Point<N>, k: &Bigi<N>) -> Point<N> {
+    fn mul(&self, p: &GenericPoint<F>, k: &Bigi<N>) -> GenericPoint<F> {
         let mut res = self.zero();
         let mut p2 = p.clone();
         for i in 0..k.bit_length() {
@@ -162,6 +257,23 @@ pub trait CurveTrait<const N: usize> {
         }
         res
     }
+
+    /// Calculates the sum `k1 P1 + k2 P2 + ...` sharing the doublings.
+    fn mul_sum(&self, points: &[GenericPoint<F>],
+               scalars: &[Bigi<N>]) -> GenericPoint<F> {
+        assert!(points.len() == scalars.len());
+        let bits = scalars.iter().map(|k| k.bit_length()).max().unwrap_or(0);
+        let mut res = self.zero();
+        for i in (0..bits).rev() {
+            res = self.double(&res);
+            for (p, k) in points.iter().zip(scalars.iter()) {
+                if k.get_bit(i) {
+                    res = self.add(&res, p);
+                }
+            }
+        }
+        res
+    }
 }
 
 
@@ -0,0 +1,520 @@
+//! This module implements [BLS signatures](https://en.wikipedia.org/wiki/BLS_digital_signature)
+//! over a pairing (`pairing::PairingTrait`, e.g. Fp254BNb or BLS12-381).
+//! The signatures are short points of G1, the public keys are points of G2,
+//! so `e(S, G2) = e(H(m), P)`. The signatures can be aggregated into one
+//! point: for distinct messages it is checked by
+//! `check_aggregate_signature`, for the same message by
+//! `check_aggregate_signature_same_message` that needs the proofs of
+//! possession of the keys to prevent rogue-key attacks.
+//!
+//! Messages are hashed to G1 by `hash_to_g1`. If the pairing has
+//! a hash-to-curve suite (BLS12381G1_XMD:SHA-256_SSWU_RO_ for BLS12-381),
+//! it is `hash_to_curve::hash_to_curve` with the tags of the proof of
+//! possession scheme from the BLS signature draft, like
+//! `BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_`. Otherwise (Fp254BNb) `x`
+//! is taken from SHA-256 with a counter until it is on the curve, then
+//! the cofactor is cleared. The proofs of possession hash the public key
+//! in the compressed encoding of ZCash (`public_key_to_bytes`).
+//!
+//! Usage example:
+//! ```rust
+//! use bigi_ecc::pairing::load_fp254bnb_pairing;
+//! use bigi_ecc::bls::{generate_pair, build_signature, check_signature,
+//!                     aggregate_signatures, check_aggregate_signature};
+//!
+//! let pairing = load_fp254bnb_pairing();
+//! let mut rng = rand::thread_rng();
+//!
+//! // Sign and check
+//! let (private_key, public_key) = generate_pair(&mut rng, &pairing);
+//! let signature = build_signature(&pairing, &private_key, b"message")
+//!     .unwrap();
+//! assert!(check_signature(&pairing, &public_key, b"message", &signature));
+//!
+//! // Aggregate signatures of distinct messages
+//! let (private_key_2, public_key_2) = generate_pair(&mut rng, &pairing);
+//! let signature_2 = build_signature(&pairing, &private_key_2, b"another")
+//!     .unwrap();
+//! let aggregate = aggregate_signatures(&pairing, &[signature, signature_2]);
+//! assert!(check_aggregate_signature(
+//!     &pairing,
+//!     &[(&public_key, &b"message"[..]), (&public_key_2, &b"another"[..])],
+//!     &aggregate
+//! ));
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use sha2::{Sha256, Digest};
+use bigi::Bigi;
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::pairing::{PairingTrait, Point2};
+use crate::hash_to_curve::hash_to_curve;
+use crate::utils::{bigi_from_be_bytes_mod, bigi_to_be_bytes};
+
+
+/// Domain separation tag to hash messages if the pairing has no
+/// hash-to-curve suite.
+pub const DST_SIGNATURE: &[u8] = b"BIGI_ECC_BLS_SIG_SHA256_";
+
+/// Domain separation tag to hash public keys in proofs of possession if
+/// the pairing has no hash-to-curve suite.
+pub const DST_POSSESSION: &[u8] = b"BIGI_ECC_BLS_POP_SHA256_";
+
+
+/// Generates a private key and the public key in G2.
+pub fn generate_pair<R: Rng + ?Sized, P: PairingTrait<N>, const N: usize>(
+            rng: &mut R, pairing: &P
+        ) -> (Bigi<N>, Point2<N>) {
+    let order = &pairing.schema().order;
+    let bits = order.bit_length();
+    let private_key = loop {
+        let x = Bigi::<N>::gen_random(rng, bits, false);
+        if !x.is_zero() && &x < order {
+            break x;
+        }
+    };
+    (private_key, get_public_key(pairing, &private_key))
+}
+
+
+/// Gets the public key `x G2` for the private key `x`.
+pub fn get_public_key<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, private_key: &Bigi<N>
+        ) -> Point2<N> {
+    pairing.get_point2(private_key)
+}
+
+
+/// Gets the domain separation tag to hash messages: `BLS_SIG_` with
+/// the suite and `POP_` or `DST_SIGNATURE`.
+pub fn signature_dst<P: PairingTrait<N>, const N: usize>(
+            pairing: &P
+        ) -> Vec<u8> {
+    match pairing.g1_suite() {
+        Some(suite) => format!("BLS_SIG_{}POP_", suite.ro_id()).into_bytes(),
+        None => DST_SIGNATURE.to_vec()
+    }
+}
+
+
+/// Gets the domain separation tag to hash public keys in proofs of
+/// possession: `BLS_POP_` with the suite and `POP_` or `DST_POSSESSION`.
+pub fn possession_dst<P: PairingTrait<N>, const N: usize>(
+            pairing: &P
+        ) -> Vec<u8> {
+    match pairing.g1_suite() {
+        Some(suite) => format!("BLS_POP_{}POP_", suite.ro_id()).into_bytes(),
+        None => DST_POSSESSION.to_vec()
+    }
+}
+
+
+/// Hashes the message to a point of G1 with the domain separation tag.
+/// With the suite of the pairing it is `hash_to_curve`, otherwise
+/// the counter is incremented until `x` from SHA-256 is on the curve,
+/// `y` is chosen even and the result is multiplied by the cofactor.
+/// It fails only if the suite requests too many bytes from
+/// `expand_message_xmd`.
+pub fn hash_to_g1<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, dst: &[u8], message: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let schema = pairing.schema();
+    if let Some(suite) = pairing.g1_suite() {
+        return hash_to_curve(schema, suite, message, dst);
+    }
+    let m = &schema.curve.m;
+    let mut counter: u32 = 0;
+    loop {
+        // Two digests make the bias modulo the prime negligible
+        let bytes: Vec<u8> = (0..2u8).flat_map(|i| {
+            let mut hasher = Sha256::new();
+            hasher.update(dst);
+            hasher.update(&counter.to_be_bytes());
+            hasher.update(&[i]);
+            hasher.update(message);
+            hasher.finalize().to_vec()
+        }).collect();
+        let x = bigi_from_be_bytes_mod(&bytes, m);
+
+        if let Ok((y1, y2)) = schema.curve.find_y(&x) {
+            let y = if y1.get_bit(0) { y2 } else { y1 };
+            let p = schema.curve.mul(&point!(x, y), &schema.cofactor);
+            if !p.is_zero {
+                return Ok(p);
+            }
+        }
+        counter += 1;
+    }
+}
+
+
+/// Builds the signature `x H(m)` of the message.
+pub fn build_signature<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, private_key: &Bigi<N>, message: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let h = hash_to_g1(pairing, &signature_dst(pairing), message)?;
+    Ok(pairing.schema().curve.mul(&h, private_key))
+}
+
+
+/// Checks the signature of the message: `e(S, G2) = e(H(m), P)`.
+pub fn check_signature<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, public_key: &Point2<N>, message: &[u8],
+            signature: &Point<N>
+        ) -> bool {
+    check_aggregate_signature(pairing, &[(public_key, message)], signature)
+}
+
+
+/// Sums the signatures into one.
+pub fn aggregate_signatures<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, signatures: &[Point<N>]
+        ) -> Point<N> {
+    let curve = &pairing.schema().curve;
+    signatures.iter().fold(curve.zero(), |acc, s| curve.add(&acc, s))
+}
+
+
+/// Sums the public keys into one, it is to be used only for the keys with
+/// checked proofs of possession.
+pub fn aggregate_public_keys<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, public_keys: &[Point2<N>]
+        ) -> Point2<N> {
+    let twist = pairing.twist();
+    public_keys.iter().fold(twist.zero(), |acc, p| twist.add(&acc, p))
+}
+
+
+/// Checks the aggregate signature of the pairs of a public key and
+/// a message: `e(S, G2) = e(H(m1), P1) e(H(m2), P2) ...`. The messages must
+/// be distinct, otherwise the check fails.
+pub fn check_aggregate_signature<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, items: &[(&Point2<N>, &[u8])], signature: &Point<N>
+        ) -> bool {
+    if items.is_empty() || !pairing.is_in_g1(signature) {
+        return false;
+    }
+    for (idx, (public_key, message)) in items.iter().enumerate() {
+        if public_key.is_zero || !pairing.is_in_g2(public_key) {
+            return false;
+        }
+        if items[..idx].iter().any(|(_, other)| other == message) {
+            return false;
+        }
+    }
+
+    let dst = signature_dst(pairing);
+    let pairs: Result<Vec<(Point<N>, Point2<N>)>, &'static str> = items
+        .iter()
+        .map(|(public_key, message)| {
+            Ok((hash_to_g1(pairing, &dst, message)?, **public_key))
+        })
+        .collect();
+    match pairs {
+        Ok(mut pairs) => check_pairs(pairing, &mut pairs, signature),
+        Err(_) => false
+    }
+}
+
+
+/// Checks the aggregate signature of one message signed by all the keys
+/// (fast aggregate verification): the keys are summed, so the check costs
+/// two pairings. The proofs of possession of the keys must be checked
+/// before (see `check_possession_proof`).
+pub fn check_aggregate_signature_same_message<P: PairingTrait<N>,
+                                             const N: usize>(
+            pairing: &P, public_keys: &[Point2<N>], message: &[u8],
+            signature: &Point<N>
+        ) -> bool {
+    if public_keys.is_empty() || !pairing.is_in_g1(signature) {
+        return false;
+    }
+    if public_keys.iter().any(|p| p.is_zero || !pairing.is_in_g2(p)) {
+        return false;
+    }
+
+    let public_key = aggregate_public_keys(pairing, public_keys);
+    match hash_to_g1(pairing, &signature_dst(pairing), message) {
+        Ok(h) => check_pairs(pairing, &mut vec![(h, public_key)], signature),
+        Err(_) => false
+    }
+}
+
+
+/// Builds the proof of possession of the private key: the signature of
+/// the public key with `possession_dst`.
+pub fn build_possession_proof<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, private_key: &Bigi<N>
+        ) -> Result<Point<N>, &'static str> {
+    let public_key = get_public_key(pairing, private_key);
+    let h = hash_to_g1(
+        pairing, &possession_dst(pairing),
+        &public_key_to_bytes(pairing, &public_key)
+    )?;
+    Ok(pairing.schema().curve.mul(&h, private_key))
+}
+
+
+/// Checks the proof of possession of the private key for the public key.
+pub fn check_possession_proof<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, public_key: &Point2<N>, proof: &Point<N>
+        ) -> bool {
+    if public_key.is_zero || !pairing.is_in_g2(public_key) ||
+            !pairing.is_in_g1(proof) {
+        return false;
+    }
+    match hash_to_g1(
+        pairing, &possession_dst(pairing),
+        &public_key_to_bytes(pairing, public_key)
+    ) {
+        Ok(h) => check_pairs(pairing, &mut vec![(h, *public_key)], proof),
+        Err(_) => false
+    }
+}
+
+
+/// Converts the public key to the compressed encoding of ZCash that other
+/// BLS12-381 implementations use: big-endian `x.c1` and `x.c0`, the top
+/// bits of the first byte are the flags: `0x80` for the compression,
+/// `0x40` for the zero point and `0x20` if `y` is lexicographically
+/// largest (`y > -y` comparing `c1` first). If the modulo leaves less than
+/// three free bits (Fp254BNb), the flags take a leading byte.
+pub fn public_key_to_bytes<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, public_key: &Point2<N>
+        ) -> Vec<u8> {
+    let m = &pairing.schema().curve.m;
+    let len = (m.bit_length() + 7) >> 3;
+    let mut bytes = vec![0u8; len << 1];
+    let mut flags = 0x80;
+    if public_key.is_zero {
+        flags |= 0x40;
+    } else {
+        let (x, y) = (&public_key.x, &public_key.y);
+        bytes[..len].copy_from_slice(&bigi_to_be_bytes(&x.c1, len));
+        bytes[len..].copy_from_slice(&bigi_to_be_bytes(&x.c0, len));
+        let largest = if y.c1.is_zero() {
+            y.c0 > *m - &y.c0
+        } else {
+            y.c1 > *m - &y.c1
+        };
+        if largest {
+            flags |= 0x20;
+        }
+    }
+    if (len << 3) - m.bit_length() < 3 {
+        bytes.insert(0, 0);
+    }
+    bytes[0] |= flags;
+    bytes
+}
+
+
+/// Checks `e(P1, Q1) e(P2, Q2) ... e(S, -G2) = 1`.
+fn check_pairs<P: PairingTrait<N>, const N: usize>(
+            pairing: &P, pairs: &mut Vec<(Point<N>, Point2<N>)>,
+            signature: &Point<N>
+        ) -> bool {
+    pairs.push((*signature, pairing.twist().inv(pairing.g2())));
+    pairing.pairing_product(pairs).is_one()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::pairing::{load_fp254bnb_pairing, load_bls12_381_pairing};
+    use crate::utils::bytes_to_hex;
+
+    #[test]
+    fn test_signature() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let (private_key, public_key) = generate_pair(&mut rng, &pairing);
+
+        let h = hash_to_g1(&pairing, DST_SIGNATURE, b"hello").unwrap();
+        assert!(pairing.is_in_g1(&h));
+        assert_eq!(h, hash_to_g1(&pairing, DST_SIGNATURE, b"hello").unwrap());
+        assert_ne!(h, hash_to_g1(&pairing, DST_POSSESSION, b"hello").unwrap());
+
+        let signature = build_signature(&pairing, &private_key, b"hello")
+            .unwrap();
+        assert!(check_signature(&pairing, &public_key, b"hello", &signature));
+        assert!(!check_signature(&pairing, &public_key, b"hellO", &signature));
+
+        let (_, other_key) = generate_pair(&mut rng, &pairing);
+        assert!(!check_signature(&pairing, &other_key, b"hello", &signature));
+        assert!(!check_signature(
+            &pairing, &pairing.twist.zero(), b"hello",
+            &pairing.schema.curve.zero()
+        ));
+    }
+
+    #[test]
+    fn test_signature_bls12_381() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_bls12_381_pairing();
+        let (private_key, public_key) = generate_pair(&mut rng, &pairing);
+
+        let signature = build_signature(&pairing, &private_key, b"hello")
+            .unwrap();
+        assert!(pairing.is_in_g1(&signature));
+        assert!(check_signature(&pairing, &public_key, b"hello", &signature));
+        assert!(!check_signature(&pairing, &public_key, b"hellO", &signature));
+
+        let proof = build_possession_proof(&pairing, &private_key).unwrap();
+        assert!(check_possession_proof(&pairing, &public_key, &proof));
+    }
+
+    #[test]
+    fn test_hash_to_g1_bls12_381() {
+        let pairing = load_bls12_381_pairing();
+        assert_eq!(signature_dst(&pairing),
+                   b"BLS_SIG_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_".to_vec());
+        assert_eq!(possession_dst(&pairing),
+                   b"BLS_POP_BLS12381G1_XMD:SHA-256_SSWU_RO_POP_".to_vec());
+
+        // RFC 9380 vector of BLS12381G1_XMD:SHA-256_SSWU_RO_
+        let dst = b"QUUX-V01-CS02-with-BLS12381G1_XMD:SHA-256_SSWU_RO_";
+        assert_eq!(
+            hash_to_g1(&pairing, dst, b"abc").unwrap(),
+            point!(
+                Bigi::<6>::from_hex("0x3567BC5EF9C690C2AB2ECDF6A96EF1C139CC0B2F284DCA0A9A7943388A49A3AEE664BA5379A7655D3C68900BE2F6903"),
+                Bigi::<6>::from_hex("0xB9C15F3FE6E5CF4211F346271D7B01C8F3B28BE689C8429C85B67AF215533311F0B8DFAAA154FA6B88176C229F2885D")
+            )
+        );
+
+        // Fp254BNb has no suite
+        assert_eq!(signature_dst(&load_fp254bnb_pairing()),
+                   DST_SIGNATURE.to_vec());
+    }
+
+    #[test]
+    fn test_public_key_to_bytes() {
+        // Multiples of G2 from the compressed test vectors of the bls12_381
+        // crate (ZCash)
+        let pairing = load_bls12_381_pairing();
+        let vectors = [
+            "c00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8",
+            "aa4edef9c1ed7f729f520e47730a124fd70662a904ba1074728114d1031e1572c6c886f6b57ec72a6178288c47c335771638533957d540a9d2370f17cc7ed5863bc0b995b8825e0ee1ea1e1e4d00dbae81f14b0bf3611b78c952aacab827a053",
+            "89380275bbc8e5dcea7dc4dd7e0550ff2ac480905396eda55062650f8d251c96eb480673937cc6d9d6a44aaa56ca66dc122915c824a0857e2ee414a3dccb23ae691ae54329781315a0c75df1c04d6d7a50a030fc866f09d516020ef82324afae",
+            "870227d3f13684fdb7ce31b8065ba3acb35f7bde6fe2ddfefa359f8b35d08a9ab9537b43e24f4ffb720b5a0bda2a82f20e7a30979a8853a077454eb63b8dcee75f106221b262886bb8e01b0abb043368da82f60899cc1412e33e4120195fc557"
+        ];
+        for (k, hex) in vectors.iter().enumerate() {
+            let public_key = get_public_key(
+                &pairing, &Bigi::<6>::from(k as u64)
+            );
+            assert_eq!(
+                bytes_to_hex(&public_key_to_bytes(&pairing, &public_key)),
+                *hex
+            );
+        }
+
+        // Fp254BNb has two free bits only
+        let pairing = load_fp254bnb_pairing();
+        let public_key = get_public_key(&pairing, &Bigi::<4>::from(1));
+        let bytes = public_key_to_bytes(&pairing, &public_key);
+        assert_eq!(bytes.len(), 65);
+        assert_eq!(bytes[0] & 0x80, 0x80);
+    }
+
+    #[test]
+    fn test_aggregate_signature() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let messages: [&[u8]; 3] = [b"first", b"second", b"third"];
+        let keys: Vec<(Bigi<4>, Point2<4>)> = (0..3)
+            .map(|_| generate_pair(&mut rng, &pairing)).collect();
+        let signatures: Vec<Point<4>> = keys.iter().zip(messages.iter())
+            .map(|((x, _), msg)| build_signature(&pairing, x, msg).unwrap())
+            .collect();
+        let aggregate = aggregate_signatures(&pairing, &signatures);
+
+        let items: Vec<(&Point2<4>, &[u8])> = keys.iter().zip(messages.iter())
+            .map(|((_, p), msg)| (p, *msg)).collect();
+        assert!(check_aggregate_signature(&pairing, &items, &aggregate));
+
+        // Wrong pairs and incomplete aggregates fail
+        let swapped = [items[1], items[0], items[2]];
+        assert!(!check_aggregate_signature(&pairing, &swapped, &aggregate));
+        assert!(!check_aggregate_signature(&pairing, &items[..2], &aggregate));
+        assert!(!check_aggregate_signature(&pairing, &[], &aggregate));
+
+        // Repeated messages are rejected
+        let signature = build_signature(&pairing, &keys[0].0, b"first")
+            .unwrap();
+        let aggregate = aggregate_signatures(
+            &pairing, &[signatures[0], signature]
+        );
+        assert!(!check_aggregate_signature(
+            &pairing, &[items[0], items[0]], &aggregate
+        ));
+    }
+
+    #[test]
+    fn test_aggregate_signature_same_message() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let keys: Vec<(Bigi<4>, Point2<4>)> = (0..3)
+            .map(|_| generate_pair(&mut rng, &pairing)).collect();
+        let public_keys: Vec<Point2<4>> = keys.iter().map(|k| k.1).collect();
+        let signatures: Vec<Point<4>> = keys.iter()
+            .map(|(x, _)| build_signature(&pairing, x, b"vote").unwrap())
+            .collect();
+        let aggregate = aggregate_signatures(&pairing, &signatures);
+
+        assert!(check_aggregate_signature_same_message(
+            &pairing, &public_keys, b"vote", &aggregate
+        ));
+        assert!(!check_aggregate_signature_same_message(
+            &pairing, &public_keys, b"veto", &aggregate
+        ));
+        assert!(!check_aggregate_signature_same_message(
+            &pairing, &public_keys[..2], b"vote", &aggregate
+        ));
+    }
+
+    #[test]
+    fn test_possession_proof() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let (private_key, public_key) = generate_pair(&mut rng, &pairing);
+        let proof = build_possession_proof(&pairing, &private_key).unwrap();
+        assert!(check_possession_proof(&pairing, &public_key, &proof));
+
+        // A proof is not a signature of the encoded key
+        let bytes = public_key_to_bytes(&pairing, &public_key);
+        assert!(!check_signature(&pairing, &public_key, &bytes, &proof));
+
+        // Rogue key P' = x' G2 - P lets forge a same-message aggregate of
+        // P and P', but it has no proof of possession
+        let (attacker_key, attacker_public) =
+            generate_pair(&mut rng, &pairing);
+        let rogue = pairing.twist.add(
+            &attacker_public, &pairing.twist.inv(&public_key)
+        );
+        let forged = build_signature(&pairing, &attacker_key, b"vote")
+            .unwrap();
+        assert!(check_aggregate_signature_same_message(
+            &pairing, &[public_key, rogue], b"vote", &forged
+        ));
+        let rogue_proof = build_possession_proof(&pairing, &attacker_key)
+            .unwrap();
+        assert!(!check_possession_proof(&pairing, &rogue, &rogue_proof));
+    }
+
+    #[bench]
+    fn bench_check_signature(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let (private_key, public_key) = generate_pair(&mut rng, &pairing);
+        let signature = build_signature(&pairing, &private_key, b"hello")
+            .unwrap();
+        bencher.iter(|| {
+            check_signature(&pairing, &public_key, b"hello", &signature)
+        });
+    }
+}
@@ -0,0 +1,1435 @@
+//! This module implements counting of points on elliptic curves over
+//! the field of integers modulo a prime `p`: naive enumeration for tiny
+//! primes, baby-step giant-step search of the order within Hasse interval
+//! for medium ones, [Schoof's algorithm](https://en.wikipedia.org/wiki/Schoof%27s_algorithm)
+//! and its improvement by Elkies and Atkin
+//! ([SEA](https://en.wikipedia.org/wiki/Schoof%E2%80%93Elkies%E2%80%93Atkin_algorithm))
+//! for Weierstrass curves. The result contains the number of points and
+//! the trace of Frobenius `t = p + 1 - order`.
+//!
+//! Schoof's algorithm works with polynomials of degree `(l^2 - 1) / 2` for
+//! primes `l` up to `O(log p)`, it is tested up to 64-bit moduli. SEA needs
+//! only the kernel polynomials of degree `(l - 1) / 2` for the Elkies
+//! primes, where the canonical modular polynomial `G_l(X, j)` has a root,
+//! the Atkin primes exclude the values of the trace and the rest of it is
+//! found with baby-step giant-step. The modular polynomials are loaded from
+//! `data/modular-polynomials.txt` for `l < 100`, they are enough for moduli
+//! of about 128 bits, it is tested with secp128r1. The polynomials are
+//! multiplied with Karatsuba algorithm and reduced with the precomputed
+//! inverse of the modulus.
+//!
+//! The modulo must be a prime greater than 3 and the curve must be
+//! non-singular, otherwise the functions return an error.
+//!
+//! Usage example:
+//! ```rust
+//! use bigi::Bigi;
+//! use bigi_ecc::WeierstrassCurve;
+//! use bigi_ecc::counting::count_points;
+//!
+//! let curve = WeierstrassCurve::<1> {
+//!     a: Bigi::<1>::from(2),
+//!     b: Bigi::<1>::from(3),
+//!     m: Bigi::<1>::from(1009)
+//! };
+//! let mut rng = rand::thread_rng();
+//! let count = count_points(&mut rng, &curve).unwrap();
+//! assert_eq!(count.order, Bigi::<1>::from(1068));
+//! ```
+use std::collections::HashMap;
+use rand::Rng;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod, inv_mod};
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::numbers::{is_prime, pow_mod, isqrt, small_primes, div_small};
+
+
+/// Primes up to this size of bits are counted naively by `count_points`.
+pub const NAIVE_BITS: usize = 16;
+
+/// Primes up to this size of bits are counted with baby-step giant-step by
+/// `count_points`, larger ones with SEA.
+pub const BSGS_BITS: usize = 64;
+
+/// The number of random points to try in baby-step giant-step.
+const BSGS_ATTEMPTS: usize = 20;
+
+/// SEA collects the residues of the trace until at most `2^SEA_SEARCH_BITS`
+/// candidates for the order are left for baby-step giant-step.
+const SEA_SEARCH_BITS: usize = 26;
+
+/// SEA fails if more than `2^SEA_MAX_SEARCH_BITS` candidates are left when
+/// the modular polynomials are over.
+const SEA_MAX_SEARCH_BITS: usize = 40;
+
+/// SEA finds the trace modulo Atkin primes up to this with Schoof's
+/// algorithm, the division polynomials are small for them.
+const SEA_SCHOOF_PRIMES: u64 = 7;
+
+/// Polynomials shorter than this are multiplied with the schoolbook method.
+const KARATSUBA_THRESHOLD: usize = 16;
+
+/// Canonical modular polynomials `G_l(X, J)` for SEA.
+const MODULAR_POLYNOMIALS: &str = include_str!(
+    "../data/modular-polynomials.txt"
+);
+
+
+/// A polynomial modulo a prime, the coefficients are listed from
+/// the constant term.
+type Poly<const N: usize> = Vec<Bigi<N>>;
+
+/// A point `(X(x), y Y(x))` with the coordinates in the ring
+/// `F_p[x, y] / (psi_l(x), y^2 - x^3 - A x - B)`.
+type RingPoint<const N: usize> = (Poly<N>, Poly<N>);
+
+
+/// The number of points on the curve (including the zero point) and
+/// the trace of Frobenius `t = p + 1 - order`.
+#[derive(Debug, PartialEq)]
+pub struct CurveOrder<const N: usize> {
+    pub order: Bigi<N>,
+    /// The absolute value of the trace.
+    pub trace: Bigi<N>,
+    pub trace_is_negative: bool
+}
+
+
+impl<const N: usize> CurveOrder<N> {
+    /// Creates the result by the number of points on the curve modulo `p`.
+    pub fn from_order(order: Bigi<N>, p: &Bigi<N>) -> Self {
+        let p1 = *p + &Bigi::<N>::from(1);
+        if p1 >= order {
+            Self { order, trace: p1 - &order, trace_is_negative: false }
+        } else {
+            Self { order, trace: order - &p1, trace_is_negative: true }
+        }
+    }
+
+    /// Creates the result by the trace of Frobenius.
+    pub fn from_trace(trace: Bigi<N>, trace_is_negative: bool,
+                      p: &Bigi<N>) -> Self {
+        let p1 = *p + &Bigi::<N>::from(1);
+        let order = if trace_is_negative { p1 + &trace } else { p1 - &trace };
+        Self { order, trace, trace_is_negative }
+    }
+}
+
+
+/// A canonical modular polynomial `G_l(X, J)` modulo `p`, the rows are
+/// the coefficients at `X^i` that are polynomials in `J`.
+struct ModularPolynomial<const N: usize> {
+    l: u64,
+    s: u64,
+    rows: Vec<Poly<N>>
+}
+
+
+/// The ring `F_p[x] / (g)` with the curve parameters, where `g` is `psi_l`
+/// or its factor.
+struct Ring<const N: usize> {
+    modulus: Poly<N>,
+    f: Poly<N>,
+    a: Bigi<N>,
+    m: Bigi<N>,
+    /// The inverse of the reversed modulus as a power series, it turns
+    /// the division into multiplications.
+    inv_rev: Poly<N>
+}
+
+
+impl<const N: usize> Ring<N> {
+    fn new(modulus: &[Bigi<N>], f: &[Bigi<N>], a: Bigi<N>,
+           m: Bigi<N>) -> Self {
+        let modulus = poly_monic(modulus, &m);
+        let rev: Poly<N> = modulus.iter().rev().cloned().collect();
+        let inv_rev = poly_inv_series(&rev, modulus.len() - 1, &m);
+        let mut ring = Self { modulus, f: vec![], a, m, inv_rev };
+        ring.f = ring.rem(f);
+        ring
+    }
+
+    /// Gets the remainder modulo `g` of degree `n`: the quotient of `x` of
+    /// degree `n + k - 1` is the reversed product of the reversed `x` and
+    /// the inverse of the reversed `g` modulo `x^k`.
+    fn rem(&self, x: &[Bigi<N>]) -> Poly<N> {
+        let m = &self.m;
+        let n = self.modulus.len() - 1;
+        if x.len() <= n {
+            return x.to_vec();
+        }
+        let k = x.len() - n;
+        if k > n {
+            return poly_rem(x, &self.modulus, m);
+        }
+        let rev: Poly<N> = x.iter().rev().take(k).cloned().collect();
+        let inv = &self.inv_rev[..k.min(self.inv_rev.len())];
+        let mut q = poly_mul(&rev, inv, m);
+        q.resize(k, Bigi::<N>::from(0));
+        q.reverse();
+        poly_sub(x, &poly_mul(&poly_trim(q), &self.modulus, m), m)
+    }
+
+    fn mul(&self, x: &[Bigi<N>], y: &[Bigi<N>]) -> Poly<N> {
+        self.rem(&poly_mul(x, y, &self.m))
+    }
+
+    /// Raises to the power with windows of 4 bits, `x^w` for `w < 16` are
+    /// precomputed.
+    fn pow(&self, x: &[Bigi<N>], e: &Bigi<N>) -> Poly<N> {
+        let mut powers = vec![vec![Bigi::<N>::from(1)], self.rem(x)];
+        for w in 2..16 {
+            powers.push(self.mul(&powers[w - 1], &powers[1]));
+        }
+        let mut res = vec![Bigi::<N>::from(1)];
+        for i in (0..e.bit_length().div_ceil(4)).rev() {
+            for _ in 0..4 {
+                res = self.mul(&res, &res);
+            }
+            let w = (0..4).filter(|&k| e.get_bit(4 * i + k))
+                          .fold(0, |w, k| w | (1 << k));
+            if w > 0 {
+                res = self.mul(&res, &powers[w]);
+            }
+        }
+        res
+    }
+
+    /// Gets the inverse, it does not exist for zero divisors because
+    /// `psi_l` is not irreducible in general.
+    fn inv(&self, x: &[Bigi<N>]) -> Result<Poly<N>, &'static str> {
+        poly_inv(x, &self.modulus, &self.m)
+            .ok_or("the element is not invertible")
+    }
+
+    /// Sum of the points with different `X`. The slope is `y L`, so
+    /// `X3 = f L^2 - X1 - X2` and `Y3 = L (X1 - X3) - Y1`.
+    fn add(&self, p: &RingPoint<N>,
+           q: &RingPoint<N>) -> Result<RingPoint<N>, &'static str> {
+        let m = &self.m;
+        let l = self.mul(
+            &poly_sub(&p.1, &q.1, m), &self.inv(&poly_sub(&p.0, &q.0, m))?
+        );
+        Ok(self.finish(p, &l, &q.0))
+    }
+
+    /// Doubles the point, the slope is `y L` with
+    /// `L = (3 X^2 + A) / (2 f Y)`.
+    fn double(&self,
+              p: &RingPoint<N>) -> Result<RingPoint<N>, &'static str> {
+        let m = &self.m;
+        let num = poly_add(
+            &poly_scale(&self.mul(&p.0, &p.0), &Bigi::<N>::from(3), m),
+            &poly_trim(vec![self.a]), m
+        );
+        let den = poly_scale(&self.mul(&self.f, &p.1), &Bigi::<N>::from(2), m);
+        let l = self.mul(&num, &self.inv(&den)?);
+        Ok(self.finish(p, &l, &p.0))
+    }
+
+    fn finish(&self, p: &RingPoint<N>, l: &[Bigi<N>],
+              x2: &[Bigi<N>]) -> RingPoint<N> {
+        let m = &self.m;
+        let x3 = poly_sub(
+            &poly_sub(&self.mul(&self.f, &self.mul(l, l)), &p.0, m), x2, m
+        );
+        let y3 = poly_sub(&self.mul(l, &poly_sub(&p.0, &x3, m)), &p.1, m);
+        (x3, y3)
+    }
+
+    /// Multiplies the point by `0 < k < l` with repeated additions, so
+    /// the added points always have different `X`.
+    fn mul_point(&self, p: &RingPoint<N>,
+                 k: u64) -> Result<RingPoint<N>, &'static str> {
+        if k == 1 {
+            return Ok(p.clone());
+        }
+        let mut res = self.double(p)?;
+        for _ in 3..=k {
+            res = self.add(&res, p)?;
+        }
+        Ok(res)
+    }
+}
+
+
+/// Counts the points enumerating all `x` modulo `p`, it takes `O(p log p)`.
+pub fn count_points_naive<const N: usize>(
+            curve: &WeierstrassCurve<N>
+        ) -> CurveOrder<N> {
+    let m = &curve.m;
+    let one = Bigi::<N>::from(1);
+    let e = (*m - &one) >> 1;
+    let mut order = one;
+    let mut x = Bigi::<N>::from(0);
+    while x < *m {
+        let r = add_mod(
+            &mul_mod(&add_mod(&mul_mod(&x, &x, m), &curve.a, m), &x, m),
+            &curve.b, m
+        );
+        if r.is_zero() {
+            order = order + &one;
+        } else if pow_mod(&r, &e, m) == one {
+            order = order + &Bigi::<N>::from(2);
+        }
+        x = x + &one;
+    }
+    CurveOrder::from_order(order, m)
+}
+
+
+/// Finds the order with baby-step giant-step: for random points `P` all
+/// `M` within Hasse interval `[p + 1 - 2 sqrt(p), p + 1 + 2 sqrt(p)]`
+/// such that `M P = O` are found in `O(p^(1/4))` steps until there is only
+/// one candidate. It may fail if the group has no elements of large order.
+pub fn count_points_bsgs<R: Rng + ?Sized, T: CurveTrait<N>,
+                         const N: usize>(
+            rng: &mut R, curve: &T
+        ) -> Result<CurveOrder<N>, &'static str> {
+    check_curve(curve)?;
+    let m = curve.get_modulo();
+    let one = Bigi::<N>::from(1);
+    let p1 = m + &one;
+    let s = isqrt(&(m << 2));
+    let lo = if p1 > s { p1 - &s } else { one };
+    let hi = p1 + &s;
+    search_order(rng, curve, &lo, &one, &(hi - &lo), |_| true)
+}
+
+
+/// Finds the order among `first + k step` for `0 <= k <= count` with
+/// baby-step giant-step for random points until there is only one
+/// candidate that satisfies `filter`.
+fn search_order<R: Rng + ?Sized, T: CurveTrait<N>,
+                F: Fn(&Bigi<N>) -> bool, const N: usize>(
+            rng: &mut R, curve: &T, first: &Bigi<N>, step: &Bigi<N>,
+            count: &Bigi<N>, filter: F
+        ) -> Result<CurveOrder<N>, &'static str> {
+    let m = curve.get_modulo();
+    let steps = isqrt(count) + &Bigi::<N>::from(1);
+
+    let mut candidates: Option<Vec<Bigi<N>>> = None;
+    for _ in 0..BSGS_ATTEMPTS {
+        let p = random_point(rng, curve);
+        let found: Vec<Bigi<N>> = match &candidates {
+            None => match order_multiples(curve, &p, first, step, count,
+                                          &steps) {
+                Some(found) => found.into_iter().filter(&filter).collect(),
+                None => continue
+            },
+            Some(prev) => prev.iter().filter(
+                |k| curve.mul(&p, k) == curve.zero()
+            ).cloned().collect()
+        };
+        if found.len() == 1 {
+            return Ok(CurveOrder::from_order(found[0], &m));
+        }
+        candidates = Some(found);
+    }
+    Err("order is not determined")
+}
+
+
+/// Counts the points with Schoof's algorithm. The trace is found modulo 2
+/// and small primes `l` until their product exceeds `4 sqrt(p)`, then
+/// it is restored with CRT. The modulo must be a prime greater than 3.
+pub fn count_points_schoof<const N: usize>(
+            curve: &WeierstrassCurve<N>
+        ) -> Result<CurveOrder<N>, &'static str> {
+    check_curve(curve)?;
+    let m = &curve.m;
+    let f = poly_trim(vec![curve.b, curve.a, Bigi::<N>::from(0),
+                           Bigi::<N>::from(1)]);
+    let mut residues = vec![(2, trace_parity(curve, &f))];
+
+    let bound = (isqrt(m) << 2) + &Bigi::<N>::from(4);
+    let mut product = Bigi::<N>::from(2);
+    let mut primes = small_primes(1 << 10).into_iter().skip(1);
+    while product <= bound {
+        let l = primes.next().ok_or("too large modulo")?;
+        if *m == Bigi::<N>::from(l) {
+            continue;
+        }
+        residues.push((l, trace_modulo(curve, &f, l)?));
+        product = product * &Bigi::<N>::from(l);
+    }
+
+    let (t, modulo) = chinese_remainder(&residues)?;
+    Ok(if t > (modulo >> 1) {
+        CurveOrder::from_trace(modulo - &t, true, m)
+    } else {
+        CurveOrder::from_trace(t, false, m)
+    })
+}
+
+
+/// Counts the points with Schoof-Elkies-Atkin algorithm. For the Elkies
+/// primes `l` the trace is found modulo `l` from the eigenvalue of
+/// Frobenius on the kernel of an `l`-isogeny, the kernel polynomial is
+/// built from the isogenous curve that comes from the root of `G_l(X, j)`.
+/// For the Atkin primes `t^2 - 4 p` is a quadratic non-residue modulo `l`,
+/// it filters the candidates of the final baby-step giant-step search.
+/// The modulo must be a prime greater than 3 and `A B != 0` (`j` is not
+/// 0 or 1728).
+pub fn count_points_sea<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, curve: &WeierstrassCurve<N>
+        ) -> Result<CurveOrder<N>, &'static str> {
+    check_curve(curve)?;
+    if curve.a.is_zero() || curve.b.is_zero() {
+        return Err("j-invariant must not be 0 or 1728");
+    }
+    let m = &curve.m;
+    let c = |k: u64| Bigi::<N>::from(k) % m;
+    let f = poly_trim(vec![curve.b, curve.a, c(0), c(1)]);
+    let j = j_invariant(curve);
+
+    let mut residues = vec![(2, trace_parity(curve, &f))];
+    let mut atkin = Vec::new();
+    let bound = (isqrt(m) << 2) + &c(4);
+    let mut product = Bigi::<N>::from(2);
+    for g in modular_polynomials(m).iter() {
+        if product > (bound >> SEA_SEARCH_BITS) {
+            break;
+        }
+        if Bigi::<N>::from(g.l + 4) >= *m {
+            continue;
+        }
+        match elkies_trace(rng, curve, &j, g) {
+            Ok(Some(r)) => residues.push((g.l, r)),
+            _ if g.l <= SEA_SCHOOF_PRIMES => {
+                residues.push((g.l, trace_modulo(curve, &f, g.l)?));
+            },
+            Ok(None) => {
+                atkin.push(g.l);
+                continue;
+            },
+            Err(_) => continue
+        }
+        product = product * &Bigi::<N>::from(g.l);
+    }
+    let (t, modulo) = chinese_remainder(&residues)?;
+
+    // The orders p + 1 - t are first + k modulo in Hasse interval
+    let p1 = *m + &c(1);
+    let s = (isqrt(m) + &c(1)) << 1;
+    let lo = if p1 > s { p1 - &s } else { c(1) };
+    let hi = p1 + &s;
+    let r = (p1 % &modulo + &modulo - &t) % &modulo;
+    let first = lo + &((r + &modulo - &(lo % &modulo)) % &modulo);
+    let count = (hi - &first) / &modulo;
+    if count.bit_length() > SEA_MAX_SEARCH_BITS {
+        return Err("too large modulo");
+    }
+
+    let filter = |order: &Bigi<N>| atkin.iter().all(|&l| {
+        let t = (div_small(&p1, l).1 + l - div_small(order, l).1) % l;
+        let d = (t * t + 4 * (l - div_small(m, l).1)) % l;
+        d != 0 && (1..l).all(|y| y * y % l != d)
+    });
+    search_order(rng, curve, &first, &modulo, &count, filter)
+}
+
+
+/// Counts the points with the method depending on the size of the modulo.
+/// The modulo must be a prime greater than 3 and the curve must be
+/// non-singular. Moduli up to 64 bits are counted in seconds, larger ones
+/// go to SEA that is tested with 128-bit moduli. Schoof's algorithm is
+/// the fallback if SEA fails, for example, for `A B = 0`.
+pub fn count_points<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, curve: &WeierstrassCurve<N>
+        ) -> Result<CurveOrder<N>, &'static str> {
+    check_curve(curve)?;
+    let bits = curve.m.bit_length();
+    if bits <= NAIVE_BITS {
+        Ok(count_points_naive(curve))
+    } else if bits <= BSGS_BITS {
+        count_points_bsgs(rng, curve)
+            .or_else(|_| count_points_schoof(curve))
+    } else {
+        count_points_sea(rng, curve)
+            .or_else(|_| count_points_schoof(curve))
+    }
+}
+
+
+/// Gets the division polynomial `psi_l` for odd `l`. The polynomials
+/// `f_n` are `psi_n` for odd `n` and `psi_n / y` for even `n`, so they
+/// depend on `x` only.
+pub fn division_polynomial<const N: usize>(
+            curve: &WeierstrassCurve<N>, l: usize
+        ) -> Vec<Bigi<N>> {
+    assert!(l % 2 == 1);
+    let m = &curve.m;
+    let (a, b) = (&curve.a, &curve.b);
+    let c = |k: u64| Bigi::<N>::from(k);
+    let neg = |x: &Bigi<N>| sub_mod(&c(0), x, m);
+    let f = poly_trim(vec![*b, *a, c(0), c(1)]);
+    let f2 = poly_mul(&f, &f, m);
+    let a2 = mul_mod(a, a, m);
+    let b2 = mul_mod(b, b, m);
+
+    let mut memo: HashMap<usize, Poly<N>> = HashMap::new();
+    memo.insert(0, vec![]);
+    memo.insert(1, vec![c(1)]);
+    memo.insert(2, vec![c(2)]);
+    memo.insert(3, poly_trim(vec![
+        neg(&a2), mul_mod(&c(12), b, m), mul_mod(&c(6), a, m), c(0), c(3)
+    ]));
+    memo.insert(4, poly_scale(&poly_trim(vec![
+        neg(&add_mod(&mul_mod(&c(8), &b2, m), &mul_mod(&a2, a, m), m)),
+        neg(&mul_mod(&c(4), &mul_mod(a, b, m), m)),
+        neg(&mul_mod(&c(5), &a2, m)),
+        mul_mod(&c(20), b, m),
+        mul_mod(&c(5), a, m),
+        c(0),
+        c(1)
+    ]), &c(4), m));
+
+    division_polynomial_rec(l, &f2, m, &mut memo)
+}
+
+
+fn division_polynomial_rec<const N: usize>(
+            n: usize, f2: &[Bigi<N>], m: &Bigi<N>,
+            memo: &mut HashMap<usize, Poly<N>>
+        ) -> Poly<N> {
+    if let Some(res) = memo.get(&n) {
+        return res.clone();
+    }
+    let k = n >> 1;
+    let mut get = |i: usize| division_polynomial_rec(i, f2, m, memo);
+    let cube = |x: &[Bigi<N>]| poly_mul(x, &poly_mul(x, x, m), m);
+    let res = if n % 2 == 1 {
+        // f_{2k+1} = f_{k+2} f_k^3 - f_{k-1} f_{k+1}^3, where the even
+        // terms are multiplied by (y^2)^2
+        let (fk2, fk, fk1, fk_1) = (get(k + 2), get(k), get(k + 1), get(k - 1));
+        let left = poly_mul(&fk2, &cube(&fk), m);
+        let right = poly_mul(&fk_1, &cube(&fk1), m);
+        if k.is_multiple_of(2) {
+            poly_sub(&poly_mul(f2, &left, m), &right, m)
+        } else {
+            poly_sub(&left, &poly_mul(f2, &right, m), m)
+        }
+    } else {
+        // f_{2k} = f_k (f_{k+2} f_{k-1}^2 - f_{k-2} f_{k+1}^2) / 2
+        let (fk2, fk, fk1, fk_1, fk_2) = (
+            get(k + 2), get(k), get(k + 1), get(k - 1), get(k - 2)
+        );
+        let diff = poly_sub(
+            &poly_mul(&fk2, &poly_mul(&fk_1, &fk_1, m), m),
+            &poly_mul(&fk_2, &poly_mul(&fk1, &fk1, m), m), m
+        );
+        poly_scale(&poly_mul(&fk, &diff, m),
+                   &inv_mod(&Bigi::<N>::from(2), m), m)
+    };
+    memo.insert(n, res.clone());
+    res
+}
+
+
+/// Finds the trace modulo the odd prime `l` from the characteristic
+/// equation `pi^2 - t pi + q = 0` on `l`-torsion points, `q = p mod l`.
+fn trace_modulo<const N: usize>(curve: &WeierstrassCurve<N>, f: &[Bigi<N>],
+                                l: u64) -> Result<u64, &'static str> {
+    let m = &curve.m;
+    let one = Bigi::<N>::from(1);
+    let psi = division_polynomial(curve, l as usize);
+    let ring = Ring::new(&psi, f, curve.a, *m);
+    let p: RingPoint<N> = (vec![Bigi::<N>::from(0), one], vec![one]);
+
+    // pi = (x^p, y f^((p - 1) / 2)), pi^2 = (x^(p^2), y f^((p^2 - 1) / 2))
+    let xp = ring.pow(&p.0, m);
+    let yp = ring.pow(&ring.f, &((*m - &one) >> 1));
+    let xp2 = ring.pow(&xp, m);
+    let yp2 = ring.mul(&ring.pow(&yp, m), &yp);
+
+    let q = div_small(m, l).1;
+    let pq = ring.mul_point(&p, q)?;
+
+    let g = poly_gcd(&poly_sub(&xp2, &pq.0, m), &ring.modulus, m);
+    if g.len() > 1 {
+        // pi^2 P = +-q P for some P, so t = 0 or t = +-2 w, w^2 = q
+        return match (1..l).find(|w| w * w % l == q) {
+            None => Ok(0),
+            Some(w) => {
+                let pw = ring.mul_point(&p, w)?;
+                let gx = poly_gcd(&poly_sub(&xp, &pw.0, m), &ring.modulus, m);
+                if gx.len() == 1 {
+                    Ok(0)
+                } else {
+                    let gy = poly_gcd(&poly_sub(&yp, &pw.1, m),
+                                      &ring.modulus, m);
+                    Ok(if gy.len() > 1 { 2 * w % l } else { l - 2 * w % l })
+                }
+            }
+        };
+    }
+
+    let s = ring.add(&(xp2, yp2), &pq)?;
+    let pi = (xp, yp);
+    let mut tpi = pi.clone();
+    for tau in 1..=(l - 1) / 2 {
+        if tau == 2 {
+            tpi = ring.double(&pi)?;
+        } else if tau > 2 {
+            tpi = ring.add(&tpi, &pi)?;
+        }
+        if tpi.0 == s.0 {
+            return Ok(if tpi.1 == s.1 { tau } else { l - tau });
+        }
+    }
+    Err("trace is not found")
+}
+
+
+/// Finds the trace modulo 2, it is even if and only if there is a point of
+/// order 2, that is `f = x^3 + A x + B` has a root.
+fn trace_parity<const N: usize>(curve: &WeierstrassCurve<N>,
+                                f: &[Bigi<N>]) -> u64 {
+    let m = &curve.m;
+    let x = vec![Bigi::<N>::from(0), Bigi::<N>::from(1)];
+    let ring = Ring::new(f, f, curve.a, *m);
+    let xp = ring.pow(&x, m);
+    let g = poly_gcd(&poly_sub(&xp, &x, m), f, m);
+    if g.len() > 1 { 0 } else { 1 }
+}
+
+
+/// Restores the trace modulo the product of the primes from the residues
+/// with Chinese remainder theorem, returns the trace and the product.
+fn chinese_remainder<const N: usize>(
+            residues: &[(u64, u64)]
+        ) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
+    let mut t = Bigi::<N>::from(0);
+    let mut modulo = Bigi::<N>::from(1);
+    for &(l, r) in residues.iter() {
+        let tl = div_small(&t, l).1;
+        let ml = div_small(&modulo, l).1;
+        let k = ((r + l - tl) % l) * inv_small(ml, l)? % l;
+        t = t + &(modulo * &Bigi::<N>::from(k));
+        modulo = modulo * &Bigi::<N>::from(l);
+    }
+    Ok((t, modulo))
+}
+
+
+/// Gets `j = 1728 4 A^3 / (4 A^3 + 27 B^2)`.
+fn j_invariant<const N: usize>(curve: &WeierstrassCurve<N>) -> Bigi<N> {
+    let m = &curve.m;
+    let (a, b) = (&curve.a, &curve.b);
+    let c = |k: u64| Bigi::<N>::from(k) % m;
+    let a3 = mul_mod(&c(4), &mul_mod(a, &mul_mod(a, a, m), m), m);
+    div_mod(&mul_mod(&c(1728), &a3, m),
+            &add_mod(&a3, &mul_mod(&c(27), &mul_mod(b, b, m), m), m), m)
+}
+
+
+/// Finds the trace modulo the prime `l` if it is an Elkies prime, that is
+/// `G_l(X, j)` has a root `f`, `None` for Atkin primes. The derivatives
+/// `f' = -G_J j' / G_X` and `j' = 18 B j / A` give the isogenous curve
+/// with `j~` from `G_l(l^s / f, j~) = 0` (Mueller's formulas).
+fn elkies_trace<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, curve: &WeierstrassCurve<N>, j: &Bigi<N>,
+            g: &ModularPolynomial<N>
+        ) -> Result<Option<u64>, &'static str> {
+    let m = &curve.m;
+    let (a, b) = (&curve.a, &curve.b);
+    let c = |k: u64| Bigi::<N>::from(k) % m;
+    let neg = |x: &Bigi<N>| sub_mod(&c(0), x, m);
+    let l = g.l;
+
+    let phi = modular_in_x(g, j, m);
+    let f = match poly_roots(rng, &phi, m).first() {
+        Some(f) => *f,
+        None => return Ok(None)
+    };
+    let gx = poly_eval(&poly_derivative(&phi, m), &f, m);
+    let gj = poly_eval(&poly_derivative(&modular_in_j(g, &f, m), m), j, m);
+    if gx.is_zero() || f.is_zero() {
+        return Err("the isogenous curve is not found");
+    }
+    let dj = div_mod(&mul_mod(&mul_mod(&c(18), b, m), j, m), a, m);
+    let df = neg(&div_mod(&mul_mod(&gj, &dj, m), &gx, m));
+
+    // f~ = l^s / f is a root of G_l(X, j~)
+    let ft = div_mod(&pow_mod(&c(l), &c(g.s), m), &f, m);
+    let dft = neg(&div_mod(&mul_mod(&ft, &df, m), &f, m));
+    let psi = modular_in_j(g, &ft, m);
+    for jt in poly_roots(rng, &psi, m).iter() {
+        let gxt = poly_eval(&poly_derivative(&modular_in_x(g, jt, m), m),
+                            &ft, m);
+        let gjt = poly_eval(&poly_derivative(&psi, m), jt, m);
+        let jt1728 = sub_mod(jt, &c(1728), m);
+        if gjt.is_zero() || jt.is_zero() || jt1728.is_zero() {
+            continue;
+        }
+        let djt = neg(&div_mod(&mul_mod(&gxt, &dft, m), &gjt, m));
+
+        // A~ = -l^2 j~'^2 / (48 j~ (j~ - 1728)),
+        // B~ = -l^3 j~'^3 / (864 j~^2 (j~ - 1728))
+        let e4 = div_mod(&mul_mod(&mul_mod(&c(l * l), &djt, m), &djt, m),
+                         &mul_mod(jt, &jt1728, m), m);
+        let e6 = neg(&div_mod(&mul_mod(&mul_mod(&c(l), &djt, m), &e4, m),
+                              jt, m));
+        let at = neg(&div_mod(&e4, &c(48), m));
+        let bt = div_mod(&e6, &c(864), m);
+        if let Some(h) = kernel_polynomial(curve, &at, &bt, l) {
+            if let Some(r) = eigenvalue_trace(curve, &h, l)? {
+                return Ok(Some(r));
+            }
+        }
+    }
+    Err("the isogenous curve is not found")
+}
+
+
+/// Gets the kernel polynomial of the isogeny to the curve `(A~, B~)` of
+/// degree `d = (l - 1) / 2`. The Weierstrass functions of the curves differ
+/// by `sum (wp(z + P) - wp(P))` over the kernel, so the coefficients of
+/// their Laurent series give the power sums `S_k` of the roots through
+/// `wp^(2k) / (2k)!` that is a polynomial in `wp`. `S_1` is unknown, so
+/// the power sums are linear in it and the elementary symmetric
+/// polynomials `e_k` follow from Newton's identities, `S_1` is the common
+/// root of `e_(d+1)` and `e_(d+2)` that must vanish.
+fn kernel_polynomial<const N: usize>(curve: &WeierstrassCurve<N>,
+                                     at: &Bigi<N>, bt: &Bigi<N>,
+                                     l: u64) -> Option<Poly<N>> {
+    let m = &curve.m;
+    let c = |k: u64| Bigi::<N>::from(k) % m;
+    let zero = c(0);
+    let d = ((l - 1) / 2) as usize;
+    let wp = weierstrass_coefficients(&curve.a, &curve.b, d + 1, m);
+    let wpt = weierstrass_coefficients(at, bt, d + 1, m);
+    let derivatives = wp_derivatives(&curve.a, &curve.b, d + 1, m);
+
+    // S_k as polynomials in S_1
+    let mut sums: Vec<Poly<N>> = vec![poly_trim(vec![c(d as u64)]),
+                                      vec![c(0), c(1)]];
+    let half = inv_mod(&c(2), m);
+    for k in 1..=(d + 1) {
+        let dk = &derivatives[k];
+        let mut sum = poly_trim(vec![
+            mul_mod(&sub_mod(&wpt[k], &wp[k], m), &half, m)
+        ]);
+        for (i, s) in sums.iter().enumerate() {
+            sum = poly_sub(&sum, &poly_scale(s, dk.get(i).unwrap_or(&zero),
+                                             m), m);
+        }
+        sums.push(poly_scale(&sum, &inv_mod(&dk[k + 1], m), m));
+    }
+
+    // k e_k = sum_{i=1}^k (-1)^(i-1) e_(k-i) S_i
+    let mut e: Vec<Poly<N>> = vec![vec![c(1)]];
+    for k in 1..=(d + 2) {
+        let mut sum = vec![];
+        for i in 1..=k {
+            let term = poly_mul(&e[k - i], &sums[i], m);
+            sum = if i % 2 == 1 {
+                poly_add(&sum, &term, m)
+            } else {
+                poly_sub(&sum, &term, m)
+            };
+        }
+        e.push(poly_scale(&sum, &inv_mod(&c(k as u64), m), m));
+    }
+
+    let g = poly_gcd(&e[d + 1], &e[d + 2], m);
+    if g.len() != 2 {
+        return None;
+    }
+    let s1 = sub_mod(&zero, &g[0], m);
+    Some(poly_trim((0..=d).map(|i| {
+        let ek = poly_eval(&e[d - i], &s1, m);
+        if (d - i).is_multiple_of(2) { ek } else { sub_mod(&zero, &ek, m) }
+    }).collect()))
+}
+
+
+/// Finds the trace modulo `l` from the eigenvalue `k` of Frobenius on
+/// the kernel of the isogeny given by its polynomial `h`:
+/// `(x^p, y^p) = k (x, y)`, so `t = k + p / k`.
+fn eigenvalue_trace<const N: usize>(
+            curve: &WeierstrassCurve<N>, h: &[Bigi<N>], l: u64
+        ) -> Result<Option<u64>, &'static str> {
+    let m = &curve.m;
+    let one = Bigi::<N>::from(1);
+    let f = poly_trim(vec![curve.b, curve.a, Bigi::<N>::from(0), one]);
+    let ring = Ring::new(h, &f, curve.a, *m);
+    let p: RingPoint<N> = (ring.rem(&[Bigi::<N>::from(0), one]), vec![one]);
+    let xp = ring.pow(&p.0, m);
+    let yp = ring.pow(&ring.f, &((*m - &one) >> 1));
+
+    let q = div_small(m, l).1;
+    let mut kp = p.clone();
+    for k in 1..=(l - 1) / 2 {
+        if k == 2 {
+            kp = ring.double(&p)?;
+        } else if k > 2 {
+            kp = ring.add(&kp, &p)?;
+        }
+        if kp.0 == xp {
+            let k = if kp.1 == yp { k } else { l - k };
+            return Ok(Some((k + q * inv_small(k, l)?) % l));
+        }
+    }
+    Ok(None)
+}
+
+
+/// Gets the coefficients `c_k` of the Laurent series of the Weierstrass
+/// function `wp(z) = z^-2 + sum c_k z^(2k)` for `k <= n`: `c_1 = -A / 5`,
+/// `c_2 = -B / 7` and
+/// `c_k = 3 / ((k - 2) (2k + 3)) sum_{i=1}^{k-2} c_i c_(k-1-i)`.
+fn weierstrass_coefficients<const N: usize>(a: &Bigi<N>, b: &Bigi<N>,
+                                            n: usize,
+                                            m: &Bigi<N>) -> Vec<Bigi<N>> {
+    let c = |k: usize| Bigi::<N>::from(k as u64) % m;
+    let mut res = vec![c(0); n + 1];
+    res[1] = sub_mod(&c(0), &div_mod(a, &c(5), m), m);
+    res[2] = sub_mod(&c(0), &div_mod(b, &c(7), m), m);
+    for k in 3..=n {
+        let mut sum = c(0);
+        for i in 1..(k - 1) {
+            sum = add_mod(&sum, &mul_mod(&res[i], &res[k - 1 - i], m), m);
+        }
+        res[k] = div_mod(&mul_mod(&c(3), &sum, m),
+                         &mul_mod(&c(k - 2), &c(2 * k + 3), m), m);
+    }
+    res
+}
+
+
+/// Gets the polynomials `D_k` such that `wp^(2k) / (2k)! = D_k(wp)` for
+/// `k <= n`. They follow from `wp'^2 = 4 wp^3 + 4 A wp + 4 B` and
+/// `wp'' = 6 wp^2 + 2 A`.
+fn wp_derivatives<const N: usize>(a: &Bigi<N>, b: &Bigi<N>, n: usize,
+                                  m: &Bigi<N>) -> Vec<Poly<N>> {
+    let c = |k: usize| Bigi::<N>::from(k as u64) % m;
+    let wp1 = poly_trim(vec![mul_mod(&c(4), b, m), mul_mod(&c(4), a, m),
+                             c(0), c(4)]);
+    let wp2 = poly_trim(vec![mul_mod(&c(2), a, m), c(0), c(6)]);
+    let mut derivative = vec![c(0), c(1)];
+    let mut factorial = c(1);
+    let mut res = vec![derivative.clone()];
+    for k in 1..=n {
+        // (D(wp))'' = D''(wp) wp'^2 + D'(wp) wp''
+        let d1 = poly_derivative(&derivative, m);
+        let d2 = poly_derivative(&d1, m);
+        derivative = poly_add(&poly_mul(&d2, &wp1, m),
+                              &poly_mul(&d1, &wp2, m), m);
+        factorial = mul_mod(&factorial,
+                            &mul_mod(&c(2 * k - 1), &c(2 * k), m), m);
+        res.push(poly_scale(&derivative, &inv_mod(&factorial, m), m));
+    }
+    res
+}
+
+
+/// Reads the canonical modular polynomials modulo `m`.
+fn modular_polynomials<const N: usize>(
+            m: &Bigi<N>
+        ) -> Vec<ModularPolynomial<N>> {
+    let mut res: Vec<ModularPolynomial<N>> = Vec::new();
+    for line in MODULAR_POLYNOMIALS.lines() {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // The data is embedded, so it is well-formed
+        let fields: Vec<&str> = line.split(' ').collect();
+        if fields.len() == 2 {
+            let l: u64 = fields[0].parse().unwrap();
+            let s: u64 = fields[1].parse().unwrap();
+            res.push(ModularPolynomial {
+                l, s, rows: vec![vec![]; l as usize + 2]
+            });
+        } else {
+            let i: usize = fields[0].parse().unwrap();
+            let k: usize = fields[1].parse().unwrap();
+            let row = &mut res.last_mut().unwrap().rows[i];
+            if row.len() <= k {
+                row.resize(k + 1, Bigi::<N>::from(0));
+            }
+            row[k] = decimal_mod(fields[2], m);
+        }
+    }
+    for g in res.iter_mut() {
+        for row in g.rows.iter_mut() {
+            *row = poly_trim(row.clone());
+        }
+    }
+    res
+}
+
+
+/// Reduces the signed decimal number modulo `m`, the digits are taken by
+/// 18 at once.
+fn decimal_mod<const N: usize>(s: &str, m: &Bigi<N>) -> Bigi<N> {
+    let (negative, digits) = match s.strip_prefix('-') {
+        Some(digits) => (true, digits),
+        None => (false, s)
+    };
+    let mut res = Bigi::<N>::from(0);
+    for chunk in digits.as_bytes().chunks(18) {
+        let scale = Bigi::<N>::from(10u64.pow(chunk.len() as u32)) % m;
+        let value = chunk.iter().fold(0, |x, d| x * 10 + (d - b'0') as u64);
+        res = add_mod(&mul_mod(&res, &scale, m),
+                      &(Bigi::<N>::from(value) % m), m);
+    }
+    if negative { sub_mod(&Bigi::<N>::from(0), &res, m) } else { res }
+}
+
+
+/// Gets `G_l(X, y)` as a polynomial in `X`.
+fn modular_in_x<const N: usize>(g: &ModularPolynomial<N>, y: &Bigi<N>,
+                                m: &Bigi<N>) -> Poly<N> {
+    poly_trim(g.rows.iter().map(|row| poly_eval(row, y, m)).collect())
+}
+
+
+/// Gets `G_l(x, J)` as a polynomial in `J`.
+fn modular_in_j<const N: usize>(g: &ModularPolynomial<N>, x: &Bigi<N>,
+                                m: &Bigi<N>) -> Poly<N> {
+    let mut res = vec![];
+    let mut power = Bigi::<N>::from(1);
+    for row in g.rows.iter() {
+        res = poly_add(&res, &poly_scale(row, &power, m), m);
+        power = mul_mod(&power, x, m);
+    }
+    res
+}
+
+
+/// Finds all multiples of the order of `p` among `first + k step` for
+/// `0 <= k <= count` with baby-step giant-step, `None` if there are too
+/// many of them.
+fn order_multiples<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, first: &Bigi<N>, step: &Bigi<N>,
+            count: &Bigi<N>, steps: &Bigi<N>
+        ) -> Option<Vec<Bigi<N>>> {
+    let zero = curve.zero();
+    let one = Bigi::<N>::from(1);
+    let q = curve.mul(p, step);
+
+    // Baby steps j Q for 0 <= j < steps, Q = step P
+    let mut table: HashMap<Vec<u8>, Bigi<N>> = HashMap::new();
+    let mut jq = zero;
+    let mut j = Bigi::<N>::from(0);
+    while j < *steps {
+        if !j.is_zero() && jq == zero {
+            // The order of the point is small
+            return None;
+        }
+        table.insert(point_key(&jq, &zero), j);
+        jq = curve.add(&jq, &q);
+        j = j + &one;
+    }
+
+    // Giant steps R = first P + i steps Q, k = i steps + j if j Q = -R
+    let giant = curve.mul(&q, steps);
+    let mut r = curve.mul(p, first);
+    let mut base = Bigi::<N>::from(0);
+    let mut found = Vec::new();
+    while base <= *count {
+        if let Some(j) = table.get(&point_key(&curve.inv(&r), &zero)) {
+            let k = base + j;
+            if k <= *count {
+                found.push(*first + &(k * step));
+            }
+        }
+        r = curve.add(&r, &giant);
+        base = base + steps;
+    }
+    Some(found)
+}
+
+
+/// Gets the key of the point for hash tables, the zero point has an empty
+/// key because its coordinates may be arbitrary.
+pub(crate) fn point_key<const N: usize>(p: &Point<N>,
+                                       zero: &Point<N>) -> Vec<u8> {
+    if p == zero { vec![] } else { p.to_bytes() }
+}
+
+
+/// Gets a random point on the curve.
+pub(crate) fn random_point<R: Rng + ?Sized, T: CurveTrait<N>,
+                           const N: usize>(
+            rng: &mut R, curve: &T
+        ) -> Point<N> {
+    let m = curve.get_modulo();
+    loop {
+        let x = Bigi::<N>::gen_random(rng, m.bit_length(), false) % &m;
+        if let Ok(roots) = curve.find_y(&x) {
+            let y = if rng.gen::<bool>() { roots.0 } else { roots.1 };
+            let p = point!(x, y);
+            if curve.check(&p) {
+                return p;
+            }
+        }
+    }
+}
+
+
+/// Checks that the modulo is a prime greater than 3 and the curve is
+/// non-singular, so the algorithms above can work.
+fn check_curve<T: CurveTrait<N>, const N: usize>(
+            curve: &T
+        ) -> Result<(), &'static str> {
+    let m = curve.get_modulo();
+    if m <= Bigi::<N>::from(3) || !is_prime(&m) {
+        Err("modulo must be a prime greater than 3")
+    } else if curve.is_singular() {
+        Err("curve is singular")
+    } else {
+        Ok(())
+    }
+}
+
+
+fn inv_small(x: u64, l: u64) -> Result<u64, &'static str> {
+    (1..l).find(|y| x * y % l == 1).ok_or("not invertible")
+}
+
+
+fn poly_trim<const N: usize>(mut a: Poly<N>) -> Poly<N> {
+    while a.last().is_some_and(|c| c.is_zero()) {
+        a.pop();
+    }
+    a
+}
+
+
+fn poly_add<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                            m: &Bigi<N>) -> Poly<N> {
+    let zero = Bigi::<N>::from(0);
+    let size = a.len().max(b.len());
+    poly_trim((0..size).map(|i| add_mod(
+        a.get(i).unwrap_or(&zero), b.get(i).unwrap_or(&zero), m
+    )).collect())
+}
+
+
+fn poly_sub<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                            m: &Bigi<N>) -> Poly<N> {
+    let zero = Bigi::<N>::from(0);
+    let size = a.len().max(b.len());
+    poly_trim((0..size).map(|i| sub_mod(
+        a.get(i).unwrap_or(&zero), b.get(i).unwrap_or(&zero), m
+    )).collect())
+}
+
+
+fn poly_scale<const N: usize>(a: &[Bigi<N>], k: &Bigi<N>,
+                              m: &Bigi<N>) -> Poly<N> {
+    poly_trim(a.iter().map(|c| mul_mod(c, k, m)).collect())
+}
+
+
+/// Multiplies the polynomials with Karatsuba algorithm: for
+/// `a = a0 + x^k a1` and `b = b0 + x^k b1` the middle term
+/// `a0 b1 + a1 b0 = (a0 + a1) (b0 + b1) - a0 b0 - a1 b1` takes one product.
+fn poly_mul<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                            m: &Bigi<N>) -> Poly<N> {
+    if a.len() < KARATSUBA_THRESHOLD || b.len() < KARATSUBA_THRESHOLD {
+        return poly_mul_schoolbook(a, b, m);
+    }
+    let k = a.len().max(b.len()) / 2;
+    let (a0, a1) = a.split_at(k.min(a.len()));
+    let (b0, b1) = b.split_at(k.min(b.len()));
+    let low = poly_mul(a0, b0, m);
+    let high = poly_mul(a1, b1, m);
+    let middle = poly_sub(
+        &poly_sub(&poly_mul(&poly_add(a0, a1, m), &poly_add(b0, b1, m), m),
+                  &low, m),
+        &high, m
+    );
+    let mut res = vec![Bigi::<N>::from(0); a.len() + b.len() - 1];
+    for (shift, part) in [(0, &low), (k, &middle), (2 * k, &high)] {
+        for (i, x) in part.iter().enumerate() {
+            res[i + shift] = add_mod(&res[i + shift], x, m);
+        }
+    }
+    poly_trim(res)
+}
+
+
+fn poly_mul_schoolbook<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                                       m: &Bigi<N>) -> Poly<N> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut res = vec![Bigi::<N>::from(0); a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        if x.is_zero() {
+            continue;
+        }
+        for (j, y) in b.iter().enumerate() {
+            res[i + j] = add_mod(&res[i + j], &mul_mod(x, y, m), m);
+        }
+    }
+    poly_trim(res)
+}
+
+
+fn poly_divrem<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                               m: &Bigi<N>) -> (Poly<N>, Poly<N>) {
+    assert!(!b.is_empty());
+    let mut r = a.to_vec();
+    if r.len() < b.len() {
+        return (vec![], r);
+    }
+    let mut q = vec![Bigi::<N>::from(0); r.len() - b.len() + 1];
+    let lead = inv_mod(b.last().unwrap(), m);
+    while r.len() >= b.len() {
+        let c = mul_mod(r.last().unwrap(), &lead, m);
+        let d = r.len() - b.len();
+        for (i, y) in b.iter().enumerate() {
+            r[i + d] = sub_mod(&r[i + d], &mul_mod(&c, y, m), m);
+        }
+        q[d] = c;
+        r = poly_trim(r);
+    }
+    (poly_trim(q), r)
+}
+
+
+fn poly_rem<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                            m: &Bigi<N>) -> Poly<N> {
+    poly_divrem(a, b, m).1
+}
+
+
+fn poly_monic<const N: usize>(a: &[Bigi<N>], m: &Bigi<N>) -> Poly<N> {
+    match a.last() {
+        Some(lead) => poly_scale(a, &inv_mod(lead, m), m),
+        None => vec![]
+    }
+}
+
+
+/// Gets the inverse of the power series `a` modulo `x^n` with Newton's
+/// iterations `r = r (2 - a r)` that double the precision, `a_0 != 0`.
+fn poly_inv_series<const N: usize>(a: &[Bigi<N>], n: usize,
+                                   m: &Bigi<N>) -> Poly<N> {
+    let mut res = vec![inv_mod(&a[0], m)];
+    let mut k = 1;
+    while k < n {
+        k = (k * 2).min(n);
+        let ar = poly_truncate(poly_mul(&a[..k.min(a.len())], &res, m), k);
+        let t = poly_sub(&[Bigi::<N>::from(2)], &ar, m);
+        res = poly_truncate(poly_mul(&res, &t, m), k);
+    }
+    res
+}
+
+
+/// Gets the polynomial modulo `x^k`.
+fn poly_truncate<const N: usize>(mut a: Poly<N>, k: usize) -> Poly<N> {
+    a.truncate(k);
+    poly_trim(a)
+}
+
+
+fn poly_eval<const N: usize>(a: &[Bigi<N>], x: &Bigi<N>,
+                             m: &Bigi<N>) -> Bigi<N> {
+    a.iter().rev().fold(Bigi::<N>::from(0),
+                        |res, c| add_mod(&mul_mod(&res, x, m), c, m))
+}
+
+
+fn poly_derivative<const N: usize>(a: &[Bigi<N>], m: &Bigi<N>) -> Poly<N> {
+    poly_trim(a.iter().enumerate().skip(1).map(
+        |(i, c)| mul_mod(c, &(Bigi::<N>::from(i as u64) % m), m)
+    ).collect())
+}
+
+
+/// Finds the roots of the polynomial: `g = gcd(x^p - x, a)` is the product
+/// of `x - r` over them and it is split by
+/// `gcd((x + d)^((p - 1) / 2) - 1, g)` for random `d` (Cantor-Zassenhaus).
+fn poly_roots<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, a: &[Bigi<N>], m: &Bigi<N>
+        ) -> Vec<Bigi<N>> {
+    if a.len() < 2 {
+        return vec![];
+    }
+    let x = vec![Bigi::<N>::from(0), Bigi::<N>::from(1)];
+    let ring = Ring::new(a, &[], Bigi::<N>::from(0), *m);
+    let g = poly_gcd(&poly_sub(&ring.pow(&x, m), &x, m), &ring.modulus, m);
+    let mut roots = Vec::new();
+    split_roots(rng, g, m, &mut roots);
+    roots
+}
+
+
+fn split_roots<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, g: Poly<N>, m: &Bigi<N>, roots: &mut Vec<Bigi<N>>
+        ) {
+    if g.len() < 2 {
+        return;
+    }
+    if g.len() == 2 {
+        roots.push(sub_mod(&Bigi::<N>::from(0), &g[0], m));
+        return;
+    }
+    let one = Bigi::<N>::from(1);
+    let e = (*m - &one) >> 1;
+    let ring = Ring::new(&g, &[], Bigi::<N>::from(0), *m);
+    loop {
+        let d = Bigi::<N>::gen_random(rng, m.bit_length(), false) % m;
+        let h = poly_gcd(
+            &poly_sub(&ring.pow(&poly_trim(vec![d, one]), &e), &[one], m),
+            &g, m
+        );
+        if h.len() > 1 && h.len() < g.len() {
+            let q = poly_monic(&poly_divrem(&g, &h, m).0, m);
+            split_roots(rng, h, m, roots);
+            split_roots(rng, q, m, roots);
+            return;
+        }
+    }
+}
+
+
+/// Gets the monic greatest common divisor.
+fn poly_gcd<const N: usize>(a: &[Bigi<N>], b: &[Bigi<N>],
+                            m: &Bigi<N>) -> Poly<N> {
+    let mut a = a.to_vec();
+    let mut b = b.to_vec();
+    while !b.is_empty() {
+        let r = poly_rem(&a, &b, m);
+        a = b;
+        b = r;
+    }
+    poly_monic(&a, m)
+}
+
+
+/// Gets the inverse of `a` modulo `modulus` with the extended Euclidean
+/// algorithm.
+fn poly_inv<const N: usize>(a: &[Bigi<N>], modulus: &[Bigi<N>],
+                            m: &Bigi<N>) -> Option<Poly<N>> {
+    let (mut r0, mut r1) = (modulus.to_vec(), poly_rem(a, modulus, m));
+    let (mut s0, mut s1) = (vec![], vec![Bigi::<N>::from(1)]);
+    while !r1.is_empty() {
+        let (q, r) = poly_divrem(&r0, &r1, m);
+        let s = poly_sub(&s0, &poly_mul(&q, &s1, m), m);
+        r0 = r1;
+        r1 = r;
+        s0 = s1;
+        s1 = s;
+    }
+    if r0.len() != 1 {
+        return None;
+    }
+    Some(poly_rem(&poly_scale(&s0, &inv_mod(&r0[0], m), m), modulus, m))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use bigi::bigi;
+
+    fn check_order<T: CurveTrait<N>, const N: usize>(curve: &T,
+                                                     order: &Bigi<N>) {
+        let mut rng = rand::thread_rng();
+        for _ in 0..5 {
+            let p = random_point(&mut rng, curve);
+            assert_eq!(curve.mul(&p, order), curve.zero());
+        }
+    }
+
+    #[test]
+    fn test_naive() {
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 97] };
+        assert_eq!(count_points_naive(&curve), CurveOrder {
+            order: bigi![1; 100], trace: bigi![1; 2], trace_is_negative: true
+        });
+
+        // An anomalous curve
+        let curve = WeierstrassCurve { a: bigi![1; 1], b: bigi![1; 32],
+                                       m: bigi![1; 101] };
+        assert_eq!(count_points_naive(&curve).order, bigi![1; 101]);
+    }
+
+    #[test]
+    fn test_division_polynomial() {
+        // psi_3 = 3 x^4 + 6 A x^2 + 12 B x - A^2
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 97] };
+        assert_eq!(division_polynomial(&curve, 3),
+                   vec![bigi![1; 93], bigi![1; 36], bigi![1; 12],
+                        bigi![1; 0], bigi![1; 3]]);
+        assert_eq!(division_polynomial(&curve, 5).len(), 13);
+        assert_eq!(division_polynomial(&curve, 7).len(), 25);
+    }
+
+    #[test]
+    fn test_schoof() {
+        for &(a, b) in [(2, 3), (0, 7), (5, 0), (1, 1), (1006, 5)].iter() {
+            let curve = WeierstrassCurve { a: Bigi::<1>::from(a),
+                                           b: Bigi::<1>::from(b),
+                                           m: bigi![1; 1009] };
+            assert_eq!(count_points_schoof(&curve).unwrap(),
+                       count_points_naive(&curve));
+        }
+
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 4294967291] };
+        let count = count_points_schoof(&curve).unwrap();
+        assert_eq!(count, CurveOrder {
+            order: bigi![1; 4294854672],
+            trace: bigi![1; 112620],
+            trace_is_negative: false
+        });
+        check_order(&curve, &count.order);
+    }
+
+    #[test]
+    fn test_bsgs() {
+        let mut rng = rand::thread_rng();
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 4294967291] };
+        assert_eq!(count_points_bsgs(&mut rng, &curve).unwrap().order,
+                   bigi![1; 4294854672]);
+        assert_eq!(count_points(&mut rng, &curve).unwrap().order,
+                   bigi![1; 4294854672]);
+
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 1009] };
+        assert_eq!(count_points(&mut rng, &curve).unwrap().order,
+                   bigi![1; 1068]);
+    }
+
+    #[test]
+    fn test_poly_arithmetic() {
+        let mut rng = rand::thread_rng();
+        let m = bigi![1; 1000003];
+        let mut random = |len: usize| -> Poly<1> {
+            (0..len).map(|_| Bigi::<1>::from(rng.gen_range(0..1000003)))
+                    .collect()
+        };
+        for &(n, k) in [(5, 40), (40, 40), (33, 70), (70, 17)].iter() {
+            let (a, b) = (random(n), random(k));
+            assert_eq!(poly_mul(&a, &b, &m), poly_mul_schoolbook(&a, &b, &m));
+        }
+
+        let modulus = random(30);
+        let ring = Ring::new(&modulus, &[], bigi![1; 0], m);
+        let x = random(59);
+        assert_eq!(ring.rem(&x), poly_rem(&x, &ring.modulus, &m));
+        let x = random(100);
+        assert_eq!(ring.rem(&x), poly_rem(&x, &ring.modulus, &m));
+    }
+
+    #[test]
+    fn test_modular_polynomials() {
+        let m = bigi![1; 1009];
+        let polys = modular_polynomials(&m);
+        assert_eq!(polys.iter().map(|g| g.l).collect::<Vec<u64>>(),
+                   [3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 53,
+                    61, 67, 73, 79, 89, 97]);
+
+        // G_3 = X^4 + 36 X^3 + 270 X^2 + (756 - J) X + 729
+        let g = &polys[0];
+        assert_eq!(g.s, 6);
+        assert_eq!(g.rows, vec![
+            vec![bigi![1; 729]], vec![bigi![1; 756], bigi![1; 1008]],
+            vec![bigi![1; 270]], vec![bigi![1; 36]], vec![bigi![1; 1]]
+        ]);
+        assert_eq!(decimal_mod("-123456789012345678901234567890", &m),
+                   bigi![1; 378]);
+    }
+
+    #[test]
+    fn test_elkies() {
+        // t = 112620
+        let mut rng = rand::thread_rng();
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 4294967291] };
+        let j = j_invariant(&curve);
+        let mut elkies = Vec::new();
+        for g in modular_polynomials(&curve.m).iter().take(13) {
+            if let Some(r) = elkies_trace(&mut rng, &curve, &j, g).unwrap() {
+                assert_eq!(r, 112620 % g.l);
+                elkies.push(g.l);
+            }
+        }
+        assert!(elkies.len() > 3);
+    }
+
+    #[test]
+    fn test_sea() {
+        let mut rng = rand::thread_rng();
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 4294967291] };
+        assert_eq!(count_points_sea(&mut rng, &curve).unwrap().order,
+                   bigi![1; 4294854672]);
+
+        // secp128r1 of SEC 2, the order is prime
+        let m = Bigi::<3>::from_hex("0xFFFFFFFDFFFFFFFFFFFFFFFFFFFFFFFF");
+        let curve = WeierstrassCurve {
+            a: Bigi::<3>::from_hex("0xFFFFFFFDFFFFFFFFFFFFFFFFFFFFFFFC"),
+            b: Bigi::<3>::from_hex("0xE87579C11079F43DD824993C2CEE5ED3"),
+            m
+        };
+        let count = count_points(&mut rng, &curve).unwrap();
+        assert_eq!(count.order,
+                   Bigi::<3>::from_hex("0xFFFFFFFE0000000075A30D1B9038A115"));
+        check_order(&curve, &count.order);
+    }
+
+    #[test]
+    #[ignore]
+    fn test_schoof_64() {
+        // Takes a few minutes: l goes up to 31, the polynomials have
+        // degree up to 480. The modulo is 2^64 - 59.
+        let mut rng = rand::thread_rng();
+        let m = Bigi::<2>::from_hex("0xFFFFFFFFFFFFFFC5");
+        let curve = WeierstrassCurve { a: bigi![2; 2], b: bigi![2; 3], m };
+        let count = count_points_schoof(&curve).unwrap();
+        assert_eq!(count, count_points_bsgs(&mut rng, &curve).unwrap());
+        check_order(&curve, &count.order);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut rng = rand::thread_rng();
+
+        // y^2 = x^3 - 3 x + 2 = (x - 1)^2 (x + 2)
+        let curve = WeierstrassCurve { a: bigi![1; 1006], b: bigi![1; 2],
+                                       m: bigi![1; 1009] };
+        assert_eq!(count_points(&mut rng, &curve), Err("curve is singular"));
+        assert_eq!(count_points_schoof(&curve), Err("curve is singular"));
+        assert_eq!(count_points_bsgs(&mut rng, &curve),
+                   Err("curve is singular"));
+        assert_eq!(count_points_sea(&mut rng, &curve),
+                   Err("curve is singular"));
+
+        let curve = WeierstrassCurve { a: bigi![1; 0], b: bigi![1; 7],
+                                       m: bigi![1; 1009] };
+        assert_eq!(count_points_sea(&mut rng, &curve),
+                   Err("j-invariant must not be 0 or 1728"));
+
+        for &m in [2, 3, 1001].iter() {
+            let curve = WeierstrassCurve { a: bigi![1; 1], b: bigi![1; 1],
+                                           m: Bigi::<1>::from(m) };
+            assert_eq!(count_points(&mut rng, &curve),
+                       Err("modulo must be a prime greater than 3"));
+            assert_eq!(count_points_schoof(&curve),
+                       Err("modulo must be a prime greater than 3"));
+        }
+    }
+
+    #[bench]
+    fn bench_schoof_32(bencher: &mut Bencher) {
+        let curve = WeierstrassCurve { a: bigi![1; 2], b: bigi![1; 3],
+                                       m: bigi![1; 4294967291] };
+        bencher.iter(|| count_points_schoof(&curve).unwrap());
+    }
+}
@@ -0,0 +1,410 @@
+//! This module implements algorithms of the discrete logarithm problem
+//! on elliptic curves: find `k` such that `k P = Q`. They are meant for
+//! teaching, auditing weak parameters and small exponents, the running
+//! time is about the square root of the order (or of the interval).
+//!
+//! * `dlog_bsgs` - baby-step giant-step with a table of `sqrt(n)` points.
+//! * `dlog_rho` - Pollard's rho with an adding walk and distinguished points,
+//!   the order must be prime.
+//! * `dlog_kangaroo` - Pollard's kangaroo for `k` in a bounded interval.
+//! * `dlog_pohlig_hellman` - reduces the problem for a composite order to
+//!   its prime factors.
+//!
+//! Usage example:
+//! ```rust
+//! use bigi::Bigi;
+//! use bigi_ecc::schemas::load_secp256k1;
+//! use bigi_ecc::dlog::dlog_kangaroo;
+//!
+//! let schema = load_secp256k1();
+//! let k = Bigi::<4>::from(123456);
+//! let q = schema.get_point(&k);
+//! let found = dlog_kangaroo(
+//!     &schema.curve, &schema.generator, &q,
+//!     &Bigi::<4>::from(100000), &Bigi::<4>::from(200000)
+//! );
+//! assert_eq!(found, Some(k));
+//! ```
+use std::collections::HashMap;
+use rand::Rng;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod};
+use crate::base::{Point, CurveTrait};
+use crate::numbers::{isqrt, pow_mod, factorize};
+use crate::counting::point_key;
+
+
+/// The number of points in the adding walk of Pollard's rho.
+const WALK_SIZE: usize = 16;
+
+/// The maximum bit length of a prime order that is solved by baby-step
+/// giant-step in Pohlig-Hellman algorithm, larger ones use Pollard's rho.
+pub const BSGS_BITS: usize = 32;
+
+/// The bound of small primes for trial division of the order.
+const TRIAL_DIVISION_BOUND: u64 = 1 << 16;
+
+
+/// Finds `k` in `[0, n)` such that `k P = Q` with baby-step giant-step
+/// algorithm, `n` is the order of `P` or its multiple. It stores `sqrt(n)`
+/// points. Returns `None` if `Q` is not a multiple of `P`.
+pub fn dlog_bsgs<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, q: &Point<N>, n: &Bigi<N>
+        ) -> Option<Bigi<N>> {
+    let zero = curve.zero();
+    let one = Bigi::<N>::from(1);
+    let steps = isqrt(n) + &one;
+
+    // Baby steps j P for 0 <= j < steps
+    let mut table: HashMap<Vec<u8>, Bigi<N>> = HashMap::new();
+    let mut jp = zero;
+    let mut j = Bigi::<N>::from(0);
+    while j < steps {
+        table.entry(point_key(&jp, &zero)).or_insert(j);
+        jp = curve.add(&jp, p);
+        j = j + &one;
+    }
+
+    // Giant steps Q - i steps P
+    let giant = curve.inv(&jp);
+    let mut r = *q;
+    let mut base = Bigi::<N>::from(0);
+    while base < *n {
+        if let Some(j) = table.get(&point_key(&r, &zero)) {
+            let k = base + j;
+            if k < *n {
+                return Some(k);
+            }
+        }
+        r = curve.add(&r, &giant);
+        base = base + &steps;
+    }
+    None
+}
+
+
+/// Finds `k` in `[0, n)` such that `k P = Q` with Pollard's rho algorithm,
+/// `n` must be the prime order of `P`. The walks `X = a P + b Q` start
+/// at random points and stop at distinguished points (with the lowest
+/// bits of `x` equal to zero), the collision of two distinguished points
+/// gives `k`. Returns `None` if nothing is found in `32 sqrt(n)` steps.
+pub fn dlog_rho<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize>(
+            rng: &mut R, curve: &T, p: &Point<N>, q: &Point<N>, n: &Bigi<N>
+        ) -> Option<Bigi<N>> {
+    let zero = curve.zero();
+    if *q == zero {
+        return Some(Bigi::<N>::from(0));
+    }
+    let dp_bits = n.bit_length() >> 2;
+
+    // Points R_i = a_i P + b_i Q of the adding walk
+    let walk: Vec<(Bigi<N>, Bigi<N>, Point<N>)> = (0..WALK_SIZE).map(|_| {
+        let a = random_below(rng, n);
+        let b = random_below(rng, n);
+        let r = curve.mul_sum(&[*p, *q], &[a, b]);
+        (a, b, r)
+    }).collect();
+
+    let mut table: HashMap<Vec<u8>, (Bigi<N>, Bigi<N>)> = HashMap::new();
+    let mut budget = steps_bound(&(isqrt(n) << 5));
+    let walk_bound = 20u64 << dp_bits.min(58);
+    while budget > 0 {
+        let mut a = random_below(rng, n);
+        let mut b = random_below(rng, n);
+        let mut x = curve.mul_sum(&[*p, *q], &[a, b]);
+        for _ in 0..walk_bound.min(budget) {
+            budget -= 1;
+            if is_distinguished(&x, dp_bits) {
+                let key = point_key(&x, &zero);
+                if let Some((a2, b2)) = table.get(&key) {
+                    // a P + b Q = a2 P + b2 Q
+                    if b != *b2 {
+                        let k = div_mod(&sub_mod(a2, &a, n),
+                                        &sub_mod(&b, b2, n), n);
+                        if curve.mul(p, &k) == *q {
+                            return Some(k);
+                        }
+                    }
+                } else {
+                    table.insert(key, (a, b));
+                }
+                break;
+            }
+            let (ai, bi, ri) = &walk[walk_index(&x, WALK_SIZE)];
+            x = curve.add(&x, ri);
+            a = add_mod(&a, ai, n);
+            b = add_mod(&b, bi, n);
+        }
+    }
+    None
+}
+
+
+/// Finds `k` in `[lo, hi]` such that `k P = Q` with Pollard's kangaroo
+/// (lambda) algorithm. The tame kangaroo starts at `hi P`, the wild one
+/// starts at `Q`, both jump by the powers of two chosen by the current
+/// point, the tame-wild collision at a distinguished point gives `k`.
+/// Returns `None` if nothing is found in about `64 sqrt(hi - lo)` steps.
+pub fn dlog_kangaroo<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, q: &Point<N>, lo: &Bigi<N>,
+            hi: &Bigi<N>
+        ) -> Option<Bigi<N>> {
+    if hi < lo {
+        return None;
+    }
+    let zero = curve.zero();
+    let width = *hi - lo;
+    let levels = (isqrt(&width) + &Bigi::<N>::from(1)).bit_length();
+    let dp_bits = levels >> 1;
+
+    // Jumps 2^i P for i < levels where 2^levels is about sqrt(hi - lo)
+    let mut jumps = vec![*p];
+    for i in 1..levels {
+        jumps.push(curve.double(&jumps[i - 1]));
+    }
+
+    // The kangaroos: the point, the travelled distance, true if it is tame
+    let mut herd = [
+        (curve.mul(p, hi), Bigi::<N>::from(0), true),
+        (*q, Bigi::<N>::from(0), false)
+    ];
+    let mut table: HashMap<Vec<u8>, (Bigi<N>, bool)> = HashMap::new();
+    let bound = steps_bound(&(Bigi::<N>::from(32) << levels));
+    for _ in 0..bound {
+        for (x, dist, tame) in herd.iter_mut() {
+            if is_distinguished(x, dp_bits) {
+                let key = point_key(x, &zero);
+                match table.get(&key).copied() {
+                    Some((dist2, tame2)) if tame2 != *tame => {
+                        // hi + tame distance = k + wild distance
+                        let (dt, dw) = if *tame {
+                            (*dist, dist2)
+                        } else {
+                            (dist2, *dist)
+                        };
+                        let s = *hi + &dt;
+                        if s >= dw {
+                            let k = s - &dw;
+                            if k >= *lo && k <= *hi &&
+                                    curve.mul(p, &k) == *q {
+                                return Some(k);
+                            }
+                        }
+                    },
+                    Some(_) => {},
+                    None => {
+                        table.insert(key, (*dist, *tame));
+                    }
+                }
+            }
+            let i = walk_index(x, levels);
+            *x = curve.add(x, &jumps[i]);
+            *dist = *dist + &(Bigi::<N>::from(1) << i);
+        }
+    }
+    None
+}
+
+
+/// Finds `k` in `[0, n)` such that `k P = Q` with Pohlig-Hellman algorithm,
+/// `n` is the order of `P`. The order is factorized by trial division
+/// (the rest must be prime), the logarithm is found modulo each prime power
+/// digit by digit with baby-step giant-step (or Pollard's rho for primes
+/// larger than `BSGS_BITS` bits) and combined with the Chinese remainder
+/// theorem. Returns `None` if the order is not factorized or `Q` is not
+/// a multiple of `P`.
+pub fn dlog_pohlig_hellman<R: Rng + ?Sized, T: CurveTrait<N>,
+                           const N: usize>(
+            rng: &mut R, curve: &T, p: &Point<N>, q: &Point<N>, n: &Bigi<N>
+        ) -> Option<Bigi<N>> {
+    let one = Bigi::<N>::from(1);
+    let mut k = Bigi::<N>::from(0);
+    let mut modulo = one;
+    for (r, e) in factorize(n, TRIAL_DIVISION_BOUND)?.iter() {
+        // P0 has the order r, P1 and Q1 lie in the subgroup of order r^e
+        let re = (0..*e).fold(one, |acc, _| acc * r);
+        let cofactor = *n / &re;
+        let p1 = curve.mul(p, &cofactor);
+        let q1 = curve.mul(q, &cofactor);
+        let p0 = curve.mul(&p1, &(re / r));
+
+        // k = k_0 + k_1 r + k_2 r^2 + ... modulo r^e
+        let mut ki = Bigi::<N>::from(0);
+        let mut rj = one;
+        for _ in 0..*e {
+            rj = rj * r;
+            let h = curve.mul(
+                &curve.add(&q1, &curve.inv(&curve.mul(&p1, &ki))),
+                &(re / &rj)
+            );
+            let d = if r.bit_length() <= BSGS_BITS {
+                dlog_bsgs(curve, &p0, &h, r)?
+            } else {
+                dlog_rho(rng, curve, &p0, &h, r)?
+            };
+            ki = ki + &(d * &(rj / r));
+        }
+
+        // k = k + modulo t where modulo t = ki - k modulo r^e
+        let phi = re / r * &(*r - &one);
+        let inv = pow_mod(&(modulo % &re), &(phi - &one), &re);
+        let t = mul_mod(&sub_mod(&ki, &(k % &re), &re), &inv, &re);
+        k = k + &(modulo * &t);
+        modulo = modulo * &re;
+    }
+    if curve.mul(p, &k) == *q {
+        Some(k)
+    } else {
+        None
+    }
+}
+
+
+/// Returns true if the lowest `bits` bits of `x` are zero.
+fn is_distinguished<const N: usize>(p: &Point<N>, bits: usize) -> bool {
+    (0..bits).all(|i| !p.x.get_bit(i))
+}
+
+
+/// Gets the index of the next step in a walk of `size` steps by the point.
+fn walk_index<const N: usize>(p: &Point<N>, size: usize) -> usize {
+    let bytes = p.x.to_bytes();
+    (bytes[0] as usize | (bytes[1] as usize) << 8) % size
+}
+
+
+/// Gets the number of steps for a loop, it is saturated at `u64::MAX`.
+fn steps_bound<const N: usize>(x: &Bigi<N>) -> u64 {
+    if x.bit_length() > 64 {
+        u64::MAX
+    } else {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&x.to_bytes()[..8]);
+        u64::from_le_bytes(bytes)
+    }
+}
+
+
+fn random_below<R: Rng + ?Sized, const N: usize>(rng: &mut R,
+                                                  n: &Bigi<N>) -> Bigi<N> {
+    Bigi::<N>::gen_random(rng, n.bit_length() + 64, false) % n
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::point;
+    use crate::weierstrass::WeierstrassCurve;
+    use crate::schemas::{load_secp256k1, load_curve25519};
+
+    // y^2 = x^3 + 2 x + 9 over F_1048573 has the prime order 1049603
+    fn prime_order_curve() -> (WeierstrassCurve<1>, Point<1>, Bigi<1>) {
+        let curve = WeierstrassCurve::<1> {
+            a: Bigi::<1>::from(2),
+            b: Bigi::<1>::from(9),
+            m: Bigi::<1>::from(1048573)
+        };
+        let p = point!(Bigi::<1>::from(1), Bigi::<1>::from(2048));
+        (curve, p, Bigi::<1>::from(1049603))
+    }
+
+    // y^2 = x^3 + 2 x + 11 over F_1048573 has the cyclic group of order
+    // 1048736 = 2^5 13 2521
+    fn smooth_order_curve() -> (WeierstrassCurve<1>, Point<1>, Bigi<1>) {
+        let curve = WeierstrassCurve::<1> {
+            a: Bigi::<1>::from(2),
+            b: Bigi::<1>::from(11),
+            m: Bigi::<1>::from(1048573)
+        };
+        let p = point!(Bigi::<1>::from(7), Bigi::<1>::from(266342));
+        (curve, p, Bigi::<1>::from(1048736))
+    }
+
+    #[test]
+    fn test_bsgs() {
+        let mut rng = rand::thread_rng();
+        let (curve, p, n) = prime_order_curve();
+        assert!(curve.check(&p));
+        for _ in 0..5 {
+            let k = random_below(&mut rng, &n);
+            let q = curve.mul(&p, &k);
+            assert_eq!(dlog_bsgs(&curve, &p, &q, &n), Some(k));
+        }
+        assert_eq!(dlog_bsgs(&curve, &p, &curve.zero(), &n),
+                   Some(Bigi::<1>::from(0)));
+
+        // Q is not a multiple of P
+        let (curve, p, _) = smooth_order_curve();
+        let p2 = curve.mul(&p, &Bigi::<1>::from(2));
+        assert_eq!(dlog_bsgs(&curve, &p2, &p, &Bigi::<1>::from(524368)),
+                   None);
+    }
+
+    #[test]
+    fn test_rho() {
+        let mut rng = rand::thread_rng();
+        let (curve, p, n) = prime_order_curve();
+        for _ in 0..5 {
+            let k = random_below(&mut rng, &n);
+            let q = curve.mul(&p, &k);
+            assert_eq!(dlog_rho(&mut rng, &curve, &p, &q, &n), Some(k));
+        }
+    }
+
+    #[test]
+    fn test_kangaroo() {
+        let mut rng = rand::thread_rng();
+        let (curve, p, _) = prime_order_curve();
+        let lo = Bigi::<1>::from(1000);
+        let hi = Bigi::<1>::from(101000);
+        for _ in 0..5 {
+            let k = random_below(&mut rng, &Bigi::<1>::from(100001)) + &lo;
+            let q = curve.mul(&p, &k);
+            assert_eq!(dlog_kangaroo(&curve, &p, &q, &lo, &hi), Some(k));
+        }
+        assert_eq!(dlog_kangaroo(&curve, &p, &p, &lo, &lo), None);
+        assert_eq!(dlog_kangaroo(&curve, &p, &p, &Bigi::<1>::from(1),
+                                 &Bigi::<1>::from(1)),
+                   Some(Bigi::<1>::from(1)));
+
+        // Montgomery curve of 255 bits
+        let schema = load_curve25519();
+        let k = Bigi::<4>::from(0xB0A3);
+        let q = schema.get_point(&k);
+        assert_eq!(dlog_kangaroo(&schema.curve, &schema.generator, &q,
+                                 &Bigi::<4>::from(0),
+                                 &Bigi::<4>::from(1 << 16)),
+                   Some(k));
+    }
+
+    #[test]
+    fn test_pohlig_hellman() {
+        let mut rng = rand::thread_rng();
+        let (curve, p, n) = smooth_order_curve();
+        for _ in 0..5 {
+            let k = random_below(&mut rng, &n);
+            let q = curve.mul(&p, &k);
+            assert_eq!(dlog_pohlig_hellman(&mut rng, &curve, &p, &q, &n),
+                       Some(k));
+        }
+
+        // Prime order is solved by a single step
+        let (curve, p, n) = prime_order_curve();
+        let k = random_below(&mut rng, &n);
+        let q = curve.mul(&p, &k);
+        assert_eq!(dlog_pohlig_hellman(&mut rng, &curve, &p, &q, &n),
+                   Some(k));
+    }
+
+    #[bench]
+    fn bench_kangaroo_20_bits(bencher: &mut Bencher) {
+        let schema = load_secp256k1();
+        let q = schema.get_point(&Bigi::<4>::from(0x2F0A3));
+        let lo = Bigi::<4>::from(0);
+        let hi = Bigi::<4>::from(1 << 20);
+        bencher.iter(|| dlog_kangaroo(&schema.curve, &schema.generator, &q,
+                                      &lo, &hi));
+    }
+}
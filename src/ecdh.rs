@@ -0,0 +1,353 @@
+//! This module implements [ECDH](https://en.wikipedia.org/wiki/Elliptic-curve_Diffie%E2%80%93Hellman)
+//! key agreement with the validation of the peer's public key, optional
+//! cofactor multiplication (cofactor ECDH per NIST SP 800-56A) and key
+//! derivation through HKDF-SHA256 ([RFC 5869](https://www.rfc-editor.org/rfc/rfc5869))
+//! or ConcatKDF-SHA256 (NIST SP 800-56A).
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::schemas;
+//! use bigi_ecc::ecdh::derive_key;
+//!
+//! // Load schema
+//! let schema = schemas::load_secp256k1();
+//!
+//! // Generate key pairs of both sides
+//! let mut rng = rand::thread_rng();
+//! let (private_key_a, public_key_a) = schema.generate_pair(&mut rng);
+//! let (private_key_b, public_key_b) = schema.generate_pair(&mut rng);
+//!
+//! // Derive 32 bytes of a symmetric key on both sides
+//! let key_a = derive_key(
+//!     &schema, &private_key_a, &public_key_b, false, b"salt", b"info", 32
+//! ).unwrap();
+//! let key_b = derive_key(
+//!     &schema, &private_key_b, &public_key_a, false, b"salt", b"info", 32
+//! ).unwrap();
+//!
+//! assert_eq!(key_a, key_b);
+//! ```
+use sha2::{Sha256, Digest};
+use bigi::Bigi;
+use crate::base::{Point, CurveTrait};
+use crate::schemas::Schema;
+use crate::utils::bigi_to_be_bytes;
+
+
+const SHA256_BLOCK_SIZE: usize = 64;
+const SHA256_SIZE: usize = 32;
+
+
+/// Checks the peer's public key: it must not be the zero point, it must lie
+/// on the curve and belong to the subgroup generated by the generator.
+pub fn validate_public_key<T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            public_key: &Point<N>
+        ) -> Result<(), &'static str> {
+    let zero = schema.curve.zero();
+    if *public_key == zero {
+        return Err("public key is the zero point");
+    }
+    if !schema.curve.check(public_key) {
+        return Err("public key is not on the curve");
+    }
+    if schema.curve.mul(public_key, &schema.order) != zero {
+        return Err("public key is not in the subgroup");
+    }
+    Ok(())
+}
+
+
+/// Calculates the shared secret as big-endian bytes of `x` of the point
+/// `d Q`, or `d (h Q)` if `cofactor` is set, where `d` is the private key,
+/// `Q` is the peer's public key and `h` is the cofactor of the schema.
+pub fn shared_secret<T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            public_key: &Point<N>,
+            cofactor: bool
+        ) -> Result<Vec<u8>, &'static str> {
+    validate_public_key(schema, public_key)?;
+
+    let point = if cofactor {
+        schema.curve.mul(
+            &schema.curve.mul(public_key, &schema.cofactor), private_key
+        )
+    } else {
+        schema.curve.mul(public_key, private_key)
+    };
+
+    if point == schema.curve.zero() {
+        return Err("shared secret is the zero point");
+    }
+
+    let size = (schema.curve.get_modulo().bit_length() + 7) >> 3;
+    Ok(bigi_to_be_bytes(&point.x, size))
+}
+
+
+/// Derives a symmetric key of `len` bytes from the shared secret with
+/// HKDF-SHA256.
+pub fn derive_key<T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            public_key: &Point<N>,
+            cofactor: bool,
+            salt: &[u8],
+            info: &[u8],
+            len: usize
+        ) -> Result<Vec<u8>, &'static str> {
+    let secret = shared_secret(schema, private_key, public_key, cofactor)?;
+    hkdf_sha256(salt, &secret, info, len)
+}
+
+
+/// Calculates HMAC-SHA256 of the data.
+pub fn hmac_sha256(key: &[u8], data: &[u8]) -> Vec<u8> {
+    let mut key_block = [0u8; SHA256_BLOCK_SIZE];
+    if key.len() > SHA256_BLOCK_SIZE {
+        key_block[..SHA256_SIZE].copy_from_slice(&Sha256::digest(key));
+    } else {
+        key_block[..key.len()].copy_from_slice(key);
+    }
+
+    let ipad: Vec<u8> = key_block.iter().map(|b| b ^ 0x36).collect();
+    let opad: Vec<u8> = key_block.iter().map(|b| b ^ 0x5c).collect();
+
+    let mut hasher = Sha256::new();
+    hasher.update(&ipad);
+    hasher.update(data);
+    let inner = hasher.finalize();
+
+    let mut hasher = Sha256::new();
+    hasher.update(&opad);
+    hasher.update(&inner);
+    hasher.finalize().to_vec()
+}
+
+
+/// Derives `len` bytes from the input keying material with HKDF-SHA256
+/// (extract and expand steps). `len` must not exceed `255 * 32`.
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8],
+                   len: usize) -> Result<Vec<u8>, &'static str> {
+    if len > 255 * SHA256_SIZE {
+        return Err("too long output for HKDF");
+    }
+
+    let prk = hmac_sha256(salt, ikm);
+
+    let mut okm = Vec::with_capacity(len);
+    let mut block = Vec::new();
+    let mut counter = 1u8;
+    while okm.len() < len {
+        let mut data = block.clone();
+        data.extend_from_slice(info);
+        data.push(counter);
+        block = hmac_sha256(&prk, &data);
+        okm.extend_from_slice(&block);
+        counter = counter.wrapping_add(1);
+    }
+    okm.truncate(len);
+
+    Ok(okm)
+}
+
+
+/// Derives `len` bytes from the shared secret with the single-step
+/// ConcatKDF based on SHA256: `SHA256(counter || z || other_info)`.
+pub fn concat_kdf_sha256(z: &[u8], other_info: &[u8], len: usize) -> Vec<u8> {
+    let mut res = Vec::with_capacity(len);
+    let mut counter = 1u32;
+    while res.len() < len {
+        let mut hasher = Sha256::new();
+        hasher.update(counter.to_be_bytes());
+        hasher.update(z);
+        hasher.update(other_info);
+        res.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    res.truncate(len);
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::{point, point_zero, schemas};
+    use crate::utils::{bytes_from_hex, bytes_to_hex};
+
+    #[test]
+    fn test_hmac_sha256() {
+        assert_eq!(
+            bytes_to_hex(&hmac_sha256(b"Jefe", b"what do ya want for nothing?")),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+
+    #[test]
+    fn test_hkdf_sha256() {
+        let okm = hkdf_sha256(
+            &bytes_from_hex("000102030405060708090a0b0c").unwrap(),
+            &[0x0b; 22],
+            &bytes_from_hex("f0f1f2f3f4f5f6f7f8f9").unwrap(),
+            42
+        ).unwrap();
+        assert_eq!(
+            bytes_to_hex(&okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf\
+             34007208d5b887185865"
+        );
+        assert!(hkdf_sha256(b"", b"", b"", 255 * 32 + 1).is_err());
+    }
+
+    #[test]
+    fn test_concat_kdf_sha256() {
+        let z: Vec<u8> = (0..32).collect();
+        assert_eq!(
+            bytes_to_hex(&concat_kdf_sha256(&z, b"other info", 48)),
+            "aeef5c9139c3631e52a140b69c6e0638e51afa133fc7245acc309f8cbb79e8af\
+             c39057eba9aa1a0ddc5caf9630818a9c"
+        );
+    }
+
+    #[test]
+    fn test_shared_secret() {
+        let schema = schemas::load_secp256k1();
+        let private_key = Bigi::<4>::from_hex(
+            "1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF1234567890ABCDEF"
+        );
+        let public_key = point!(
+            Bigi::<4>::from_hex(
+                "97855F402631F09E602E5CCADC219503F07CDD4C73B2215B5418F52A7FDBFCD9"
+            ),
+            Bigi::<4>::from_hex(
+                "7C59D67B478562B62269EC23D6DFC5566BACBDC25606D4CCFD5DE7CFADCF4BE8"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(
+                &shared_secret(&schema, &private_key, &public_key, false)
+                    .unwrap()
+            ),
+            "2a15f55ab4d9474165e927d1b3c8e9dc0df2de76c09e4ef8072087c829af171d"
+        );
+
+        assert!(shared_secret(
+            &schema, &private_key, &point_zero!(4), false
+        ).is_err());
+        assert!(shared_secret(
+            &schema, &private_key,
+            &point!(public_key.x, public_key.x), false
+        ).is_err());
+    }
+
+    #[test]
+    fn test_brainpool_rfc7027() {
+        let schema = schemas::load_brainpoolp256r1();
+        let private_key = Bigi::<4>::from_hex(
+            "81DB1EE100150FF2EA338D708271BE38300CB54241D79950F77B063039804F1D"
+        );
+        let public_key = point!(
+            Bigi::<4>::from_hex(
+                "8D2D688C6CF93E1160AD04CC4429117DC2C41825E1E9FCA0ADDD34E6F1B39F7B"
+            ),
+            Bigi::<4>::from_hex(
+                "990C57520812BE512641E47034832106BC7D3E8DD0E4C7F1136D7006547CEC6A"
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(
+                &shared_secret(&schema, &private_key, &public_key, false)
+                    .unwrap()
+            ),
+            "89afc39d41d3b327814b80940b042590f96556ec91e6ae7939bce31f3a18bf2b"
+        );
+    }
+
+    #[test]
+    fn test_ecdh() {
+        let mut rng = rand::thread_rng();
+
+        let schema = schemas::load_curve25519();
+        let (private_key_a, public_key_a) = schema.generate_pair(&mut rng);
+        let (private_key_b, public_key_b) = schema.generate_pair(&mut rng);
+
+        for cofactor in [false, true].iter() {
+            assert_eq!(
+                shared_secret(
+                    &schema, &private_key_a, &public_key_b, *cofactor
+                ).unwrap(),
+                shared_secret(
+                    &schema, &private_key_b, &public_key_a, *cofactor
+                ).unwrap()
+            );
+        }
+
+        let key = derive_key(
+            &schema, &private_key_a, &public_key_b, true, b"", b"", 16
+        ).unwrap();
+        assert_eq!(key.len(), 16);
+
+        // A point of order 2 is out of the subgroup
+        let low_order = point!(Bigi::<4>::from(0), Bigi::<4>::from(0));
+        assert!(schema.curve.check(&low_order));
+        assert_eq!(
+            validate_public_key(&schema, &low_order),
+            Err("public key is not in the subgroup")
+        );
+
+        // A point of the full group (generator plus the point of order 2)
+        let mixed = schema.curve.add(&public_key_b, &low_order);
+        assert!(validate_public_key(&schema, &mixed).is_err());
+    }
+
+    #[test]
+    fn test_ecdh_edwards() {
+        let mut rng = rand::thread_rng();
+
+        let schema = schemas::load_curve1174();
+        let (private_key_a, public_key_a) = schema.generate_pair(&mut rng);
+        let (private_key_b, public_key_b) = schema.generate_pair(&mut rng);
+        assert!(validate_public_key(&schema, &public_key_a).is_ok());
+
+        for cofactor in [false, true].iter() {
+            assert_eq!(
+                shared_secret(
+                    &schema, &private_key_a, &public_key_b, *cofactor
+                ).unwrap(),
+                shared_secret(
+                    &schema, &private_key_b, &public_key_a, *cofactor
+                ).unwrap()
+            );
+        }
+
+        // The zero point of Edwards curves is (0, 1)
+        assert_eq!(
+            validate_public_key(&schema, &schema.curve.zero()),
+            Err("public key is the zero point")
+        );
+
+        // (0, -1) has order 2
+        let low_order = point!(
+            Bigi::<4>::from(0), schema.curve.m - &Bigi::<4>::from(1)
+        );
+        assert!(schema.curve.check(&low_order));
+        assert_eq!(
+            validate_public_key(&schema, &low_order),
+            Err("public key is not in the subgroup")
+        );
+    }
+
+    #[bench]
+    fn bench_derive_key(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_secp256k1();
+        let (private_key, _public_key) = schema.generate_pair(&mut rng);
+        let (_private_key, public_key) = schema.generate_pair(&mut rng);
+        b.iter(|| derive_key(
+            &schema, &private_key, &public_key, false, b"", b"", 32
+        ));
+    }
+}
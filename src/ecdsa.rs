@@ -1,5 +1,7 @@
 //! This module implements [ECDSA](https://en.wikipedia.org/wiki/Elliptic_Curve_Digital_Signature_Algorithm)
-//! algorithms.
+//! algorithms. The hash of a message is converted to an integer as in
+//! the standard (big-endian, truncated to the bit length of the order),
+//! so any hash function can be used with any schema.
 //!
 //! Usage example:
 //! ```rust
@@ -38,8 +40,17 @@ extern crate rand;
 use rand::Rng;
 use bigi::Bigi;
 use bigi::prime::{add_mod, mul_mod, div_mod, inv_mod};
+use crate::point;
 use crate::base::{CurveTrait, Point};
 use crate::schemas::Schema;
+use crate::utils::{gen_random_weight, bigi_from_be_bytes};
+
+
+/// An item for `check_signatures_batch`: the public key, the hash of a
+/// message, the signature and the optional recovery hint.
+pub type BatchItem<'a, const N: usize> = (
+    &'a Point<N>, &'a Vec<u8>, &'a (Bigi<N>, Bigi<N>), Option<bool>
+);
 
 
 /// Builds a signature for given schema, private key and hash of a message.
@@ -49,36 +60,47 @@ pub fn build_signature<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize> (
             private_key: &Bigi<N>,
             hash: &Vec<u8>
         ) -> (Bigi<N>, Bigi<N>) {
-    assert!(hash.len() == N << 3);
+    build_signature_recoverable(rng, schema, private_key, hash).0
+}
 
-    let mut hash_aligned = vec![0u8; N << 3];
-    hash_aligned[..hash.len()].copy_from_slice(hash);
 
-    let h = Bigi::<N>::from_bytes(&hash_aligned) % &schema.order;
+/// Builds a signature for given schema, private key and hash of a message
+/// together with the recovery hint: `true` if `y` of the point `R` is odd.
+/// The hint lets `check_signatures_batch` reconstruct `R` from `r`.
+pub fn build_signature_recoverable<R: Rng + ?Sized, T: CurveTrait<N>,
+                                   const N: usize> (
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            hash: &[u8]
+        ) -> ((Bigi<N>, Bigi<N>), bool) {
+    let h = hash_to_bigi(schema, hash);
 
-    let (k, r) = {
+    let (k, r, hint) = {
         let mut k;
         let mut r;
+        let mut hint;
         loop {
             let pair = schema.generate_pair(rng);
             k = pair.0;
             r = pair.1.x % &schema.order;
+            hint = pair.1.y.get_bit(0);
             if r != Bigi::<N>::from(0) {
                 break;
             }
         }
-        (k, r)
+        (k, r, hint)
     };
 
     let s = div_mod(
         &add_mod(
-            &mul_mod(&private_key, &r, &schema.order),
+            &mul_mod(private_key, &r, &schema.order),
             &h, &schema.order
         ),
         &k, &schema.order
     );
 
-    (r, s)
+    ((r, s), hint)
 }
 
 
@@ -90,12 +112,7 @@ pub fn check_signature<T: CurveTrait<N>, const N: usize> (
             hash: &Vec<u8>,
             signature: &(Bigi<N>, Bigi<N>)
         ) -> bool {
-    assert!(hash.len() == N << 3);
-
-    let mut hash_aligned = vec![0u8; N << 3];
-    hash_aligned[..hash.len()].copy_from_slice(hash);
-
-    let h = Bigi::<N>::from_bytes(&hash_aligned) % &schema.order;
+    let h = hash_to_bigi(schema, hash);
     let (r, s) = signature;
 
     if r.is_zero() || (r >= &schema.order) ||
@@ -114,13 +131,138 @@ pub fn check_signature<T: CurveTrait<N>, const N: usize> (
 }
 
 
+/// Checks many signatures at once. The hints come from
+/// `build_signature_recoverable`. The points `R` of the signatures
+/// with hints are reconstructed by `find_y`, so `s R == h G + r P` for all
+/// of them is combined with random weights into one multi-scalar
+/// multiplication check. The signatures without hints and all of them if
+/// the combined check fails are checked individually, the indices of the
+/// invalid ones are returned.
+pub fn check_signatures_batch<R: Rng + ?Sized, T: CurveTrait<N>,
+                              const N: usize> (
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            items: &[BatchItem<N>]
+        ) -> Result<(), Vec<usize>> {
+    let mut valid = true;
+    let mut sum = Bigi::<N>::from(0);
+    let mut left_points = Vec::with_capacity(items.len());
+    let mut left_scalars = Vec::with_capacity(items.len());
+    let mut right_points = vec![schema.generator];
+    let mut right_scalars = vec![Bigi::<N>::from(0)];
+    let mut rest = Vec::new();
+
+    for (idx, (public_key, hash, (r, s), hint)) in items.iter().enumerate() {
+        let hint = match hint {
+            Some(hint) => *hint,
+            None => {
+                rest.push(idx);
+                continue;
+            },
+        };
+
+        if r.is_zero() || (r >= &schema.order) ||
+                s.is_zero() || (s >= &schema.order) {
+            valid = false;
+            break;
+        }
+
+        let r_point = match schema.curve.find_y(r) {
+            Ok((y1, y2)) => {
+                if y1.get_bit(0) == hint {
+                    point!(*r, y1)
+                } else {
+                    point!(*r, y2)
+                }
+            },
+            Err(_) => {
+                valid = false;
+                break;
+            },
+        };
+
+        let h = hash_to_bigi(schema, hash);
+        let z = gen_random_weight(rng, &schema.order);
+
+        sum = add_mod(&sum, &mul_mod(&z, &h, &schema.order), &schema.order);
+        left_points.push(r_point);
+        left_scalars.push(mul_mod(&z, s, &schema.order));
+        right_points.push(**public_key);
+        right_scalars.push(mul_mod(&z, r, &schema.order));
+    }
+
+    if valid {
+        // [c] sum [z s]R == [c]([sum z h]G + sum [z r]P)
+        right_scalars[0] = sum;
+        let left = schema.curve.mul(
+            &schema.curve.mul_sum(&left_points, &left_scalars),
+            &schema.cofactor
+        );
+        let right = schema.curve.mul(
+            &schema.curve.mul_sum(&right_points, &right_scalars),
+            &schema.cofactor
+        );
+        valid = left == right;
+    }
+
+    if !valid {
+        rest = (0..items.len()).collect();
+    }
+
+    let invalid: Vec<usize> = rest.into_iter()
+        .filter(|&idx| {
+            let (public_key, hash, signature, _) = items[idx];
+            !check_signature(schema, public_key, hash, signature)
+        })
+        .collect();
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+
+fn hash_to_bigi<T: CurveTrait<N>, const N: usize>(schema: &Schema<T, N>,
+                                                  hash: &[u8]) -> Bigi<N> {
+    // The hash is big-endian, its bits beyond the bit length of the order
+    // are cut off (bits2int of the standard)
+    let bits = schema.order.bit_length();
+    let size = hash.len().min((bits + 7) >> 3);
+    let h = bigi_from_be_bytes::<N>(&hash[..size]);
+    let h = if (size << 3) > bits { h >> ((size << 3) - bits) } else { h };
+    h % &schema.order
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use bigi::bigi;
     use test::Bencher;
-    use sha2::{Sha256, Digest};
+    use std::collections::HashMap;
+    use sha2::{Sha256, Sha384, Sha512, Digest};
     use crate::schemas;
+    use crate::utils::bytes_from_hex;
+
+    #[test]
+    fn test_hash_to_bigi() {
+        // Version 1 read 32-byte hashes as little-endian integers, the same
+        // integer is obtained from the reversed hash for 256-bit orders
+        let schema = schemas::load_secp256k1();
+        let hash = Sha256::digest(b"a test phrase").to_vec();
+        let reversed: Vec<u8> = hash.iter().rev().cloned().collect();
+        assert_eq!(hash_to_bigi(&schema, &reversed),
+                   Bigi::<4>::from_bytes(&hash) % &schema.order);
+
+        // Longer hashes are truncated to the bit length of the order
+        let schema = schemas::load_curve25519();
+        let hash = Sha512::digest(b"a test phrase").to_vec();
+        assert_eq!(hash_to_bigi(&schema, &hash),
+                   (bigi_from_be_bytes::<4>(&hash[..32]) >> 3) %
+                        &schema.order);
+    }
 
     #[test]
     fn test_ecdsa() {
@@ -157,6 +299,279 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ecdsa_batch() {
+        let mut rng = rand::thread_rng();
+
+        let hashes: Vec<Vec<u8>> = (0..5).map(|i| {
+            let mut hasher = Sha256::new();
+            hasher.update(format!("message {}", i));
+            hasher.finalize().to_vec()
+        }).collect();
+
+        for schema in [schemas::load_secp256k1(),
+                       schemas::load_fp254bnb()].iter() {
+            let pairs: Vec<(Bigi<4>, Point<4>)> = (0..5).map(
+                |_| schema.generate_pair(&mut rng)
+            ).collect();
+            let mut signatures: Vec<((Bigi<4>, Bigi<4>), bool)> = pairs.iter()
+                .zip(hashes.iter())
+                .map(|((private_key, _), hash)| {
+                    build_signature_recoverable(
+                        &mut rng, schema, private_key, hash
+                    )
+                }).collect();
+
+            let items: Vec<_> = (0..5).map(|i| (
+                &pairs[i].1, &hashes[i], &signatures[i].0,
+                if i == 2 { None } else { Some(signatures[i].1) }
+            )).collect();
+            assert_eq!(check_signatures_batch(&mut rng, schema, &items),
+                       Ok(()));
+            assert_eq!(check_signatures_batch(&mut rng, schema, &[]),
+                       Ok(()));
+
+            signatures[1].0.1 = signatures[3].0.1;
+            signatures[2].0.1 = signatures[3].0.1;
+            signatures[4].1 = !signatures[4].1;
+            let items: Vec<_> = (0..5).map(|i| (
+                &pairs[i].1, &hashes[i], &signatures[i].0,
+                if i == 2 { None } else { Some(signatures[i].1) }
+            )).collect();
+            assert_eq!(check_signatures_batch(&mut rng, schema, &items),
+                       Err(vec![1, 2]));
+        }
+    }
+
+    fn check_sign_verify<T: CurveTrait<N>, const N: usize>(
+                schema: &Schema<T, N>
+            ) {
+        let mut rng = rand::thread_rng();
+        let hash = Sha256::digest(b"a test phrase").to_vec();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+
+        let signature = build_signature(&mut rng, schema, &private_key, &hash);
+        assert!(check_signature(schema, &public_key, &hash, &signature));
+
+        let other = Sha256::digest(b"another phrase").to_vec();
+        assert!(!check_signature(schema, &public_key, &other, &signature));
+    }
+
+    #[test]
+    fn test_ecdsa_legacy() {
+        check_sign_verify(&schemas::load_secp160k1());
+        check_sign_verify(&schemas::load_secp160r1());
+        check_sign_verify(&schemas::load_secp192k1());
+        check_sign_verify(&schemas::load_secp192r1());
+        check_sign_verify(&schemas::load_secp224k1());
+        check_sign_verify(&schemas::load_secp224r1());
+    }
+
+    fn check_vector<T: CurveTrait<N>, const N: usize>(
+                schema: &Schema<T, N>,
+                fields: &HashMap<&str, &str>,
+                hash: &Vec<u8>
+            ) -> bool {
+        let value = |key: &str| bigi_from_be_bytes::<N>(
+            &bytes_from_hex(fields[key]).unwrap()
+        );
+        let public_key = point!(value("Qx"), value("Qy"));
+        check_signature(schema, &public_key, hash, &(value("R"), value("S")))
+    }
+
+    fn check_vectors(data: &str) -> usize {
+        let mut curve = "";
+        let mut hash_name = "";
+        let mut fields = HashMap::new();
+        let mut count = 0;
+
+        for line in data.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                let (c, h) = line[1..(line.len() - 1)].split_once(',')
+                    .unwrap();
+                curve = c;
+                hash_name = h;
+                continue;
+            }
+
+            let (key, value) = line.split_once(" = ").unwrap();
+            fields.insert(key, value);
+
+            if key == "Result" {
+                let msg = bytes_from_hex(fields["Msg"]).unwrap();
+                let hash = match hash_name {
+                    "SHA-256" => Sha256::digest(&msg).to_vec(),
+                    "SHA-384" => Sha384::digest(&msg).to_vec(),
+                    "SHA-512" => Sha512::digest(&msg).to_vec(),
+                    _ => panic!("unknown hash {}", hash_name),
+                };
+                let valid = match curve {
+                    "P-192" => check_vector(
+                        &schemas::load_secp192r1(), &fields, &hash
+                    ),
+                    "P-224" => check_vector(
+                        &schemas::load_secp224r1(), &fields, &hash
+                    ),
+                    "P-256" => check_vector(
+                        &schemas::load_secp256r1(), &fields, &hash
+                    ),
+                    "P-384" => check_vector(
+                        &schemas::load_secp384r1(), &fields, &hash
+                    ),
+                    "P-521" => check_vector(
+                        &schemas::load_secp521r1(), &fields, &hash
+                    ),
+                    "brainpoolP256r1" => check_vector(
+                        &schemas::load_brainpoolp256r1(), &fields, &hash
+                    ),
+                    "brainpoolP384r1" => check_vector(
+                        &schemas::load_brainpoolp384r1(), &fields, &hash
+                    ),
+                    "brainpoolP512r1" => check_vector(
+                        &schemas::load_brainpoolp512r1(), &fields, &hash
+                    ),
+                    _ => panic!("unknown curve {}", curve),
+                };
+                assert_eq!(valid, value.starts_with('P'),
+                           "{} {} {}", curve, hash_name, fields["Msg"]);
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    fn check_cavp_vector<T: CurveTrait<N>, const N: usize>(
+                schema: &Schema<T, N>,
+                fields: &HashMap<&str, &str>
+            ) {
+        let value = |key: &str| bigi_from_be_bytes::<N>(
+            &bytes_from_hex(fields[key]).unwrap()
+        );
+        let digest = bytes_from_hex(fields["Digest"]).unwrap();
+        let public_key = point!(value("X"), value("Y"));
+        let signature = (value("R"), value("S"));
+        assert_eq!(
+            check_signature(schema, &public_key, &digest, &signature),
+            !fields.contains_key("Invalid"),
+            "{} {}", fields["Curve"], fields["Digest"]
+        );
+
+        // Signature generation entries have the private key and `k`
+        if let Some(k) = fields.get("K") {
+            let private_key = value("Private");
+            let k = bigi_from_be_bytes::<N>(&bytes_from_hex(k).unwrap());
+            assert_eq!(schema.get_point(&private_key), public_key);
+            let r = schema.get_point(&k).x % &schema.order;
+            let s = div_mod(
+                &add_mod(
+                    &mul_mod(&private_key, &r, &schema.order),
+                    &hash_to_bigi(schema, &digest), &schema.order
+                ),
+                &k, &schema.order
+            );
+            assert_eq!((r, s), signature, "{}", fields["Digest"]);
+        }
+    }
+
+    fn check_cavp_vectors(data: &str) -> usize {
+        let mut count = 0;
+        for block in data.split("\n\n") {
+            let fields: HashMap<&str, &str> = block.lines()
+                .filter(|line| !line.starts_with('#'))
+                .filter_map(|line| line.split_once('='))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect();
+            if !fields.contains_key("Curve") {
+                continue;
+            }
+            match fields["Curve"] {
+                "P-224" => check_cavp_vector(
+                    &schemas::load_secp224r1(), &fields
+                ),
+                "P-256" => check_cavp_vector(
+                    &schemas::load_secp256r1(), &fields
+                ),
+                "P-384" => check_cavp_vector(
+                    &schemas::load_secp384r1(), &fields
+                ),
+                "P-521" => check_cavp_vector(
+                    &schemas::load_secp521r1(), &fields
+                ),
+                curve => panic!("unknown curve {}", curve),
+            }
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn test_cavp_sigver_vectors() {
+        // SHA-1 to SHA-512 digests, so the truncation is covered
+        assert_eq!(
+            check_cavp_vectors(include_str!("../data/ecdsa-cavp-sigver.txt")),
+            300
+        );
+    }
+
+    #[test]
+    fn test_cavp_siggen_vectors() {
+        assert_eq!(
+            check_cavp_vectors(include_str!("../data/ecdsa-cavp-siggen.txt")),
+            240
+        );
+    }
+
+    #[test]
+    fn test_rfc6979_vectors() {
+        // The only known answers for P-192
+        assert_eq!(
+            check_vectors(include_str!("../data/ecdsa-rfc6979-sigver.rsp")),
+            150
+        );
+    }
+
+    #[test]
+    fn test_brainpool_openssl_vectors() {
+        // Keys, signatures and results come from OpenSSL, these are not
+        // the BSI TR-03111 test vectors
+        assert_eq!(
+            check_vectors(
+                include_str!("../data/ecdsa-brainpool-openssl.rsp")
+            ),
+            54
+        );
+    }
+
+    #[test]
+    fn test_ecdsa_nist() {
+        let message = b"a test phrase";
+        let mut rng = rand::thread_rng();
+
+        let hash = Sha256::digest(&message[..]).to_vec();
+        let schema = schemas::load_secp256r1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature(
+            &mut rng, &schema, &private_key, &hash
+        );
+        assert!(check_signature(&schema, &public_key, &hash, &signature));
+
+        let hash = Sha512::digest(&message[..]).to_vec();
+        let schema = schemas::load_secp521r1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature(
+            &mut rng, &schema, &private_key, &hash
+        );
+        assert!(check_signature(&schema, &public_key, &hash, &signature));
+        assert!(!check_signature(
+            &schema, &public_key, &hash[1..].to_vec(), &signature
+        ));
+    }
+
     #[bench]
     fn bench_build_signature(b: &mut Bencher) {
         let message = b"a test phrase";
@@ -196,4 +611,27 @@ mod tests {
             &schema, &public_key, &hash.to_vec(), &signature)
         );
     }
+
+    #[bench]
+    fn bench_check_signatures_batch(b: &mut Bencher) {
+        let message = b"a test phrase";
+
+        let mut hasher = Sha256::new();
+        hasher.reset();
+        hasher.update(&message[..]);
+        let hash = hasher.finalize().to_vec();
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_secp256k1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+
+        let (signature, hint) = build_signature_recoverable(
+            &mut rng, &schema, &private_key, &hash
+        );
+        let items: Vec<_> = (0..16).map(
+            |_| (&public_key, &hash, &signature, Some(hint))
+        ).collect();
+
+        b.iter(|| check_signatures_batch(&mut rng, &schema, &items));
+    }
 }
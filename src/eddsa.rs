@@ -0,0 +1,638 @@
+//! This module implements [EdDSA](https://en.wikipedia.org/wiki/EdDSA)
+//! signatures (RFC 8032 construction) over any Edwards curve schema.
+//! The hash function is a parameter of the algorithm, it also defines the
+//! length of encoded points and scalars (a half of the hash length).
+//! The pure variant as well as the context (like Ed25519ctx) and
+//! the prehash (like Ed25519ph) variants are supported. `Sha512Hash` gives
+//! Ed25519 style signatures, `Shake256Hash` gives Ed448 ones (with
+//! `load_ed448`).
+//!
+//! Usage example:
+//! ```rust
+//! use bigi_ecc::schemas;
+//! use bigi_ecc::eddsa::{generate_pair, build_signature, check_signature,
+//!                       Sha512Hash, Variant};
+//!
+//! let message = b"a test phrase";
+//!
+//! // Load a crypto schema
+//! let schema = schemas::load_curve1174();
+//!
+//! // Generate a key pair
+//! let mut rng = rand::thread_rng();
+//! let (private_key, public_key) =
+//!     generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema);
+//!
+//! // Build signature
+//! let signature = build_signature::<Sha512Hash, 4>(
+//!     &schema, &private_key, &message[..], &Variant::Pure
+//! );
+//!
+//! // Check the signature
+//! assert!(check_signature::<Sha512Hash, 4>(
+//!     &schema, &public_key, &message[..], &signature, &Variant::Pure
+//! ));
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use sha2::{Sha512, Digest};
+use sha3::Shake256;
+use sha3::digest::{ExtendableOutput, XofReader};
+use bigi::Bigi;
+use bigi::prime::{add_mod, mul_mod};
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::edwards::EdwardsCurve;
+use crate::schemas::Schema;
+use crate::utils::{gen_random_weight, bigi_from_le_bytes, bigi_to_le_bytes,
+                   bigi_from_le_bytes_mod};
+
+
+/// Variant of EdDSA: pure, with a context or with a prehashed message.
+/// The context must not be longer than 255 bytes.
+#[derive(Copy, Clone)]
+pub enum Variant<'a> {
+    Pure,
+    Context(&'a [u8]),
+    Prehash(&'a [u8])
+}
+
+
+/// `EddsaHash` is a trait that defines the hash function of EdDSA
+/// together with its domain separation and prehash function.
+pub trait EddsaHash {
+    /// Size of the hash in bytes, it is twice the size of encodings.
+    const SIZE: usize;
+
+    /// Calculates the hash of the data.
+    fn hash(data: &[u8]) -> Vec<u8>;
+
+    /// Calculates the prehash of the message for the prehash variant.
+    fn prehash(message: &[u8]) -> Vec<u8>;
+
+    /// Returns the domain separation prefix for the variant.
+    fn dom(variant: &Variant) -> Vec<u8>;
+}
+
+
+/// SHA512 hash with `dom2` prefix as in Ed25519, Ed25519ctx and Ed25519ph.
+pub struct Sha512Hash;
+
+
+impl EddsaHash for Sha512Hash {
+    const SIZE: usize = 64;
+
+    fn hash(data: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        hasher.update(data);
+        hasher.finalize().to_vec()
+    }
+
+    fn prehash(message: &[u8]) -> Vec<u8> {
+        Self::hash(message)
+    }
+
+    fn dom(variant: &Variant) -> Vec<u8> {
+        match variant {
+            Variant::Pure => vec![],
+            Variant::Context(context) => dom_prefix(
+                b"SigEd25519 no Ed25519 collisions", 0, context
+            ),
+            Variant::Prehash(context) => dom_prefix(
+                b"SigEd25519 no Ed25519 collisions", 1, context
+            ),
+        }
+    }
+}
+
+
+/// SHAKE256 hash (114 bytes) with `dom4` prefix as in Ed448 and Ed448ph.
+pub struct Shake256Hash;
+
+
+impl Shake256Hash {
+    fn shake(data: &[u8], size: usize) -> Vec<u8> {
+        let mut hasher = Shake256::default();
+        sha3::digest::Update::update(&mut hasher, data);
+        let mut res = vec![0u8; size];
+        hasher.finalize_xof().read(&mut res);
+        res
+    }
+}
+
+
+impl EddsaHash for Shake256Hash {
+    const SIZE: usize = 114;
+
+    fn hash(data: &[u8]) -> Vec<u8> {
+        Self::shake(data, Self::SIZE)
+    }
+
+    fn prehash(message: &[u8]) -> Vec<u8> {
+        Self::shake(message, 64)
+    }
+
+    fn dom(variant: &Variant) -> Vec<u8> {
+        match variant {
+            Variant::Pure => dom_prefix(b"SigEd448", 0, &[]),
+            Variant::Context(context) => dom_prefix(b"SigEd448", 0, context),
+            Variant::Prehash(context) => dom_prefix(b"SigEd448", 1, context),
+        }
+    }
+}
+
+
+/// Builds a domain separation prefix `tag || flag || len(context) || context`.
+pub fn dom_prefix(tag: &[u8], flag: u8, context: &[u8]) -> Vec<u8> {
+    assert!(context.len() <= 255);
+    let mut res = tag.to_vec();
+    res.push(flag);
+    res.push(context.len() as u8);
+    res.extend(context);
+    res
+}
+
+
+/// Encodes the point as `y` coordinate in little-endian with the lowest bit
+/// of `x` in the highest bit.
+pub fn encode_point<H: EddsaHash, const N: usize>(p: &Point<N>) -> Vec<u8> {
+    let size = H::SIZE >> 1;
+    let mut bytes = bigi_to_le_bytes(&p.y, size);
+    if p.x.get_bit(0) {
+        bytes[size - 1] |= 0x80;
+    }
+    bytes
+}
+
+
+/// Decodes the point from the bytes, the point is checked to be on the curve.
+pub fn decode_point<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            bytes: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let size = H::SIZE >> 1;
+    if bytes.len() != size {
+        return Err("wrong length of encoded point");
+    }
+
+    let mut bytes_y = bytes.to_vec();
+    let x_bit = (bytes_y[size - 1] & 0x80) != 0;
+    bytes_y[size - 1] &= 0x7F;
+    if bytes_y.iter().skip(N << 3).any(|&b| b != 0) {
+        return Err("y is out of range");
+    }
+
+    let y = bigi_from_le_bytes::<N>(&bytes_y);
+    if y >= schema.curve.m {
+        return Err("y is out of range");
+    }
+
+    // The curve equation is symmetric, so `find_y` finds `x` by `y`
+    let roots = schema.curve.find_y(&y)?;
+    if roots.0.is_zero() && x_bit {
+        return Err("wrong sign of x");
+    }
+    let x = if roots.0.get_bit(0) == x_bit { roots.0 } else { roots.1 };
+
+    Ok(point!(x, y))
+}
+
+
+/// Gets the public key for the private key.
+pub fn get_public_key<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            private_key: &[u8]
+        ) -> Vec<u8> {
+    let (s, _prefix) = expand_private_key::<H, N>(schema, private_key);
+    encode_point::<H, N>(&schema.get_point(&s))
+}
+
+
+/// Generates a random private key and the corresponding public key.
+pub fn generate_pair<R: Rng + ?Sized, H: EddsaHash, const N: usize>(
+            rng: &mut R,
+            schema: &Schema<EdwardsCurve<N>, N>
+        ) -> (Vec<u8>, Vec<u8>) {
+    let mut private_key = vec![0u8; H::SIZE >> 1];
+    rng.fill(&mut private_key[..]);
+    let public_key = get_public_key::<H, N>(schema, &private_key);
+    (private_key, public_key)
+}
+
+
+/// Builds a signature for given schema, private key and message.
+pub fn build_signature<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            private_key: &[u8],
+            message: &[u8],
+            variant: &Variant
+        ) -> Vec<u8> {
+    let (s, prefix) = expand_private_key::<H, N>(schema, private_key);
+    let public_key = encode_point::<H, N>(&schema.get_point(&s));
+    let dom = H::dom(variant);
+    let message = prepare_message::<H>(message, variant);
+
+    let r = hash_to_scalar::<H, N>(
+        schema, &[&dom, &prefix, &message]
+    );
+    let r_point = encode_point::<H, N>(&schema.get_point(&r));
+
+    let k = hash_to_scalar::<H, N>(
+        schema, &[&dom, &r_point, &public_key, &message]
+    );
+    let s = add_mod(
+        &r,
+        &mul_mod(&k, &(s % &schema.order), &schema.order),
+        &schema.order
+    );
+
+    let mut signature = r_point;
+    signature.extend(bigi_to_le_bytes(&s, H::SIZE >> 1));
+    signature
+}
+
+
+/// Checks the signature for given schema, public key and message.
+pub fn check_signature<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            public_key: &[u8],
+            message: &[u8],
+            signature: &[u8],
+            variant: &Variant
+        ) -> bool {
+    let (a_point, r_point, s, k) = match parse_signature::<H, N>(
+        schema, public_key, message, signature, variant
+    ) {
+        Some(parsed) => parsed,
+        None => return false,
+    };
+
+    // [c][S]B == [c]R + [c][k]A, where c is the cofactor
+    let left = schema.curve.mul(&schema.get_point(&s), &schema.cofactor);
+    let right = schema.curve.mul(
+        &schema.curve.add(&r_point, &schema.curve.mul(&a_point, &k)),
+        &schema.cofactor
+    );
+    left == right
+}
+
+
+/// Checks many signatures at once. Each item is a tuple of the public key,
+/// the message and the signature. The signatures are combined with random
+/// weights into one multi-scalar multiplication check, if it fails every
+/// signature is checked individually and the indices of the invalid ones
+/// are returned.
+pub fn check_signatures_batch<R: Rng + ?Sized, H: EddsaHash,
+                              const N: usize>(
+            rng: &mut R,
+            schema: &Schema<EdwardsCurve<N>, N>,
+            items: &[(&[u8], &[u8], &[u8])],
+            variant: &Variant
+        ) -> Result<(), Vec<usize>> {
+    let mut valid = true;
+    let mut sum = Bigi::<N>::from(0);
+    let mut points = Vec::with_capacity(items.len() << 1);
+    let mut scalars = Vec::with_capacity(items.len() << 1);
+
+    for (public_key, message, signature) in items.iter() {
+        let (a_point, r_point, s, k) = match parse_signature::<H, N>(
+            schema, public_key, message, signature, variant
+        ) {
+            Some(parsed) => parsed,
+            None => {
+                valid = false;
+                break;
+            },
+        };
+        let z = gen_random_weight(rng, &schema.order);
+
+        sum = add_mod(&sum, &mul_mod(&z, &s, &schema.order), &schema.order);
+        points.push(r_point);
+        scalars.push(z);
+        points.push(a_point);
+        scalars.push(mul_mod(&z, &k, &schema.order));
+    }
+
+    if valid {
+        // [c][sum z S]B == [c](sum [z]R + sum [z k]A)
+        let left = schema.curve.mul(&schema.get_point(&sum), &schema.cofactor);
+        let right = schema.curve.mul(
+            &schema.curve.mul_sum(&points, &scalars), &schema.cofactor
+        );
+        valid = left == right;
+    }
+
+    if valid {
+        return Ok(());
+    }
+
+    let invalid: Vec<usize> = items.iter().enumerate()
+        .filter(|(_, (public_key, message, signature))| {
+            !check_signature::<H, N>(
+                schema, public_key, message, signature, variant
+            )
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+
+fn parse_signature<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            public_key: &[u8],
+            message: &[u8],
+            signature: &[u8],
+            variant: &Variant
+        ) -> Option<(Point<N>, Point<N>, Bigi<N>, Bigi<N>)> {
+    let size = H::SIZE >> 1;
+    if signature.len() != size << 1 {
+        return None;
+    }
+
+    let a_point = decode_point::<H, N>(schema, public_key).ok()?;
+    let r_point = decode_point::<H, N>(schema, &signature[..size]).ok()?;
+
+    if signature[size..].iter().skip(N << 3).any(|&b| b != 0) {
+        return None;
+    }
+    let s = bigi_from_le_bytes::<N>(&signature[size..]);
+    if s >= schema.order {
+        return None;
+    }
+
+    let dom = H::dom(variant);
+    let message = prepare_message::<H>(message, variant);
+    let k = hash_to_scalar::<H, N>(
+        schema, &[&dom, &signature[..size], public_key, &message]
+    );
+
+    Some((a_point, r_point, s, k))
+}
+
+
+fn expand_private_key<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            private_key: &[u8]
+        ) -> (Bigi<N>, Vec<u8>) {
+    let size = H::SIZE >> 1;
+    let h = H::hash(private_key);
+
+    // Clear the lowest bits by the cofactor, set the bit `n` and clear
+    // the bits above, where `n` is the highest bit of the modulo.
+    let c = schema.cofactor.bit_length() - 1;
+    let n = schema.curve.m.bit_length() - 1;
+    let mut bytes = h[..size].to_vec();
+    bytes[0] &= !((1u8 << c) - 1);
+    for i in (n + 1)..(size << 3) {
+        bytes[i >> 3] &= !(1u8 << (i & 7));
+    }
+    bytes[n >> 3] |= 1u8 << (n & 7);
+
+    (bigi_from_le_bytes::<N>(&bytes), h[size..].to_vec())
+}
+
+
+fn prepare_message<H: EddsaHash>(message: &[u8], variant: &Variant) -> Vec<u8> {
+    match variant {
+        Variant::Prehash(_) => H::prehash(message),
+        _ => message.to_vec(),
+    }
+}
+
+
+fn hash_to_scalar<H: EddsaHash, const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>,
+            parts: &[&[u8]]
+        ) -> Bigi<N> {
+    let h = H::hash(&parts.concat());
+    bigi_from_le_bytes_mod(&h, &schema.order)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::schemas;
+    use crate::utils::bytes_from_hex;
+
+    #[test]
+    fn test_encode_point() {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+        for _ in 0..10 {
+            let (_k, p) = schema.generate_pair(&mut rng);
+            let bytes = encode_point::<Sha512Hash, 4>(&p);
+            assert_eq!(bytes.len(), 32);
+            assert_eq!(decode_point::<Sha512Hash, 4>(&schema, &bytes), Ok(p));
+        }
+        assert_eq!(
+            decode_point::<Sha512Hash, 4>(
+                &schema, &encode_point::<Sha512Hash, 4>(&schema.curve.zero())
+            ),
+            Ok(schema.curve.zero())
+        );
+        assert!(decode_point::<Sha512Hash, 4>(&schema, &[0xFF; 32]).is_err());
+    }
+
+    #[test]
+    fn test_eddsa() {
+        let message = b"a test phrase";
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+        let (private_key, public_key) =
+            generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema);
+
+        let variants = [
+            Variant::Pure, Variant::Context(b"foo"), Variant::Prehash(b"foo")
+        ];
+
+        for variant in variants.iter() {
+            let signature = build_signature::<Sha512Hash, 4>(
+                &schema, &private_key, &message[..], variant
+            );
+            assert_eq!(signature.len(), 64);
+
+            assert!(check_signature::<Sha512Hash, 4>(
+                &schema, &public_key, &message[..], &signature, variant
+            ));
+
+            assert!(!check_signature::<Sha512Hash, 4>(
+                &schema, &public_key, &b"another phrase"[..],
+                &signature, variant
+            ));
+
+            assert!(!check_signature::<Sha512Hash, 4>(
+                &schema, &public_key, &message[..], &signature,
+                &Variant::Context(b"bar")
+            ));
+        }
+    }
+
+    #[test]
+    fn test_eddsa_batch() {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+
+        let messages: Vec<Vec<u8>> = (0..5).map(
+            |i| format!("message {}", i).into_bytes()
+        ).collect();
+        let pairs: Vec<(Vec<u8>, Vec<u8>)> = (0..5).map(
+            |_| generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema)
+        ).collect();
+        let mut signatures: Vec<Vec<u8>> = pairs.iter().zip(messages.iter())
+            .map(|((private_key, _), message)| {
+                build_signature::<Sha512Hash, 4>(
+                    &schema, private_key, message, &Variant::Pure
+                )
+            }).collect();
+
+        let items: Vec<(&[u8], &[u8], &[u8])> = (0..5).map(|i| (
+            &pairs[i].1[..], &messages[i][..], &signatures[i][..]
+        )).collect();
+        assert_eq!(check_signatures_batch::<_, Sha512Hash, 4>(
+            &mut rng, &schema, &items, &Variant::Pure
+        ), Ok(()));
+        assert_eq!(check_signatures_batch::<_, Sha512Hash, 4>(
+            &mut rng, &schema, &[], &Variant::Pure
+        ), Ok(()));
+        assert_eq!(check_signatures_batch::<_, Sha512Hash, 4>(
+            &mut rng, &schema, &items, &Variant::Context(b"foo")
+        ), Err(vec![0, 1, 2, 3, 4]));
+
+        signatures[1][0] ^= 1;
+        signatures[3] = signatures[4].clone();
+        let items: Vec<(&[u8], &[u8], &[u8])> = (0..5).map(|i| (
+            &pairs[i].1[..], &messages[i][..], &signatures[i][..]
+        )).collect();
+        assert_eq!(check_signatures_batch::<_, Sha512Hash, 4>(
+            &mut rng, &schema, &items, &Variant::Pure
+        ), Err(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_eddsa_deterministic() {
+        let schema = schemas::load_curve1174();
+        let private_key = [7u8; 32];
+        let signature1 = build_signature::<Sha512Hash, 4>(
+            &schema, &private_key, b"abc", &Variant::Pure
+        );
+        let signature2 = build_signature::<Sha512Hash, 4>(
+            &schema, &private_key, b"abc", &Variant::Pure
+        );
+        assert_eq!(signature1, signature2);
+    }
+
+    #[test]
+    fn test_ed448() {
+        let schema = schemas::load_ed448();
+
+        // Test vectors from RFC 8032: Ed448 (empty message), Ed448 with
+        // context "foo" and Ed448ph ("abc")
+        let vectors = [
+            (
+                "6c82a562cb808d10d632be89c8513ebf6c929f34ddfa8c9f63c9960ef6e348a3528c8a3fcc2f044e39a3fc5b94492f8f032e7549a20098f95b",
+                "5fd7449b59b461fd2ce787ec616ad46a1da1342485a70e1f8a0ea75d80e96778edf124769b46c7061bd6783df1e50f6cd1fa1abeafe8256180",
+                "",
+                Variant::Pure,
+                "533a37f6bbe457251f023c0d88f976ae2dfb504a843e34d2074fd823d41a591f2b233f034f628281f2fd7a22ddd47d7828c59bd0a21bfd3980ff0d2028d4b18a9df63e006c5d1c2d345b925d8dc00b4104852db99ac5c7cdda8530a113a0f4dbb61149f05a7363268c71d95808ff2e652600"
+            ),
+            (
+                "c4eab05d357007c632f3dbb48489924d552b08fe0c353a0d4a1f00acda2c463afbea67c5e8d2877c5e3bc397a659949ef8021e954e0a12274e",
+                "43ba28f430cdff456ae531545f7ecd0ac834a55d9358c0372bfa0c6c6798c0866aea01eb00742802b8438ea4cb82169c235160627b4c3a9480",
+                "03",
+                Variant::Context(b"foo"),
+                "d4f8f6131770dd46f40867d6fd5d5055de43541f8c5e35abbcd001b32a89f7d2151f7647f11d8ca2ae279fb842d607217fce6e042f6815ea000c85741de5c8da1144a6a1aba7f96de42505d7a7298524fda538fccbbb754f578c1cad10d54d0d5428407e85dcbc98a49155c13764e66c3c00"
+            ),
+            (
+                "833fe62409237b9d62ec77587520911e9a759cec1d19755b7da901b96dca3d42ef7822e0d5104127dc05d6dbefde69e3ab2cec7c867c6e2c49",
+                "259b71c19f83ef77a7abd26524cbdb3161b590a48f7d17de3ee0ba9c52beb743c09428a131d6b1b57303d90d8132c276d5ed3d5d01c0f53880",
+                "616263",
+                Variant::Prehash(b""),
+                "822f6901f7480f3d5f562c592994d9693602875614483256505600bbc281ae381f54d6bce2ea911574932f52a4e6cadd78769375ec3ffd1b801a0d9b3f4030cd433964b6457ea39476511214f97469b57dd32dbc560a9a94d00bff07620464a3ad203df7dc7ce360c3cd3696d9d9fab90f00"
+            ),
+        ];
+
+        for (private_key, public_key, message, variant, signature) in
+                vectors.iter() {
+            let private_key = bytes_from_hex(private_key).unwrap();
+            let message = bytes_from_hex(message).unwrap();
+            let public_key = bytes_from_hex(public_key).unwrap();
+            let signature = bytes_from_hex(signature).unwrap();
+
+            assert_eq!(
+                get_public_key::<Shake256Hash, 7>(&schema, &private_key),
+                public_key
+            );
+            assert_eq!(
+                build_signature::<Shake256Hash, 7>(
+                    &schema, &private_key, &message, variant
+                ),
+                signature
+            );
+            assert!(check_signature::<Shake256Hash, 7>(
+                &schema, &public_key, &message, &signature, variant
+            ));
+            assert!(!check_signature::<Shake256Hash, 7>(
+                &schema, &public_key, b"another message", &signature, variant
+            ));
+        }
+    }
+
+    #[bench]
+    fn bench_build_signature(b: &mut Bencher) {
+        let message = b"a test phrase";
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+        let (private_key, _public_key) =
+            generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema);
+
+        b.iter(|| build_signature::<Sha512Hash, 4>(
+            &schema, &private_key, &message[..], &Variant::Pure
+        ));
+    }
+
+    #[bench]
+    fn bench_check_signature(b: &mut Bencher) {
+        let message = b"a test phrase";
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+        let (private_key, public_key) =
+            generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema);
+
+        let signature = build_signature::<Sha512Hash, 4>(
+            &schema, &private_key, &message[..], &Variant::Pure
+        );
+
+        b.iter(|| check_signature::<Sha512Hash, 4>(
+            &schema, &public_key, &message[..], &signature, &Variant::Pure
+        ));
+    }
+
+    #[bench]
+    fn bench_check_signatures_batch(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve1174();
+        let (private_key, public_key) =
+            generate_pair::<_, Sha512Hash, 4>(&mut rng, &schema);
+        let signature = build_signature::<Sha512Hash, 4>(
+            &schema, &private_key, b"a test phrase", &Variant::Pure
+        );
+        let items: Vec<(&[u8], &[u8], &[u8])> = (0..16).map(|_| (
+            &public_key[..], &b"a test phrase"[..], &signature[..]
+        )).collect();
+        b.iter(|| check_signatures_batch::<_, Sha512Hash, 4>(
+            &mut rng, &schema, &items, &Variant::Pure
+        ));
+    }
+}
@@ -43,6 +43,11 @@ impl<const N: usize> CurveTrait<N> for EdwardsCurve<N> {
         left == right
     }
 
+    fn is_singular(&self) -> bool {
+        // D (1 - D) = 0
+        self.d.is_zero() || self.d == Bigi::<N>::from(1)
+    }
+
     fn find_y(&self, x: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
         let a = sub_mod(
             &mul_mod(&x, &x, &self.m),
@@ -104,7 +109,7 @@ mod tests {
     use super::*;
     use bigi::bigi;
     use crate::point_simple;
-    use crate::schemas::load_curve1174;
+    use crate::schemas::{load_curve1174, load_ed448};
     use test::Bencher;
 
     #[test]
@@ -117,6 +122,8 @@ mod tests {
         assert_eq!(curve.check(&point_simple!(4; 0, 0)), false);
         assert_eq!(curve.check(&curve.zero()), true);
         assert_eq!(curve.check(&point_simple!(4; 48, 28)), false);
+        assert!(!curve.is_singular());
+        assert!(EdwardsCurve { d: bigi![4; 1], m: bigi![4; 97] }.is_singular());
     }
 
     #[test]
@@ -167,6 +174,14 @@ mod tests {
         assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
     }
 
+    #[test]
+    fn test_ed448() {
+        let schema = load_ed448();
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&bigi![7; 25])));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
     #[bench]
     fn bench_curve1174_generate_pair(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -19,7 +19,7 @@
 //! let encrypted = encrypt(&mut rng, &schema, &public_key, &message[..]);
 //!
 //! // Decrypt the message
-//! let mut decripted = decrypt(&schema, &private_key, &encrypted);
+//! let mut decripted = decrypt(&schema, &private_key, &encrypted).unwrap();
 //!
 //! // Remove trailing zeros
 //! if let Some(idx) = decripted.iter().rposition(|&c| c != 0) {
@@ -36,10 +36,12 @@ use bigi::Bigi;
 use crate::point;
 use crate::base::{Point, CurveTrait};
 use crate::schemas::Schema;
+use crate::dlog::dlog_kangaroo;
 
 
 /// Encrypt `bytes` with `public_key` according to ElGamal encryption.
-/// The result is a pair of points.
+/// The result is a pair of points. The length of `bytes` must not exceed
+/// `get_capacity(schema)`.
 pub fn encrypt<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize> (
             rng: &mut R,
             schema: &Schema<T, N>,
@@ -55,23 +57,86 @@ pub fn encrypt<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize> (
 
 
 /// Decrypt a pair of points `encrypted` with `private_key` according to
-/// ElGamal encryption. The result is a vector of bytes.
+/// ElGamal encryption. The result is a vector of bytes. The first point
+/// must belong to the subgroup of the generator (see
+/// `Schema::validate_point`), otherwise a point of small order leaks
+/// the private key modulo the cofactor. The second point must lie on
+/// the curve.
 pub fn decrypt<T: CurveTrait<N>, const N: usize> (
             schema: &Schema<T, N>,
             private_key: &Bigi<N>,
             encrypted: &(Point<N>, Point<N>)
-        ) -> Vec<u8> {
+        ) -> Result<Vec<u8>, &'static str> {
     let (c1, c2) = encrypted;
+    schema.validate_point(c1)?;
+    if !schema.curve.check(c2) {
+        return Err("point is not on the curve");
+    }
     let s = schema.curve.mul(&c1, &private_key);
     let si = schema.curve.inv(&s);
     let p = schema.curve.add(&si, &c2);
-    bytes_from_point(&p)
+    Ok(bytes_from_point(&p, &schema.curve))
+}
+
+
+/// Encrypt a small integer `m` with `public_key` according to exponential
+/// ElGamal encryption: the second point is `m G + y H`. The ciphertexts are
+/// additively homomorphic, the sum of two ciphertexts encrypts `m1 + m2`.
+pub fn encrypt_exponent<R: Rng + ?Sized, T: CurveTrait<N>,
+                        const N: usize> (
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            public_key: &Point<N>,
+            m: &Bigi<N>
+        ) -> (Point<N>, Point<N>) {
+    let (y, c1) = schema.generate_pair(rng);
+    let c2 = schema.curve.mul_sum(&[schema.generator, *public_key],
+                                  &[*m, y]);
+    (c1, c2)
+}
+
+
+/// Decrypt a pair of points `encrypted` with `private_key` according to
+/// exponential ElGamal encryption. The point `m G` is decrypted as usual,
+/// then `m` in `[0, bound]` is found with Pollard's kangaroo algorithm
+/// in about `sqrt(bound)` steps.
+pub fn decrypt_exponent<T: CurveTrait<N>, const N: usize> (
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            encrypted: &(Point<N>, Point<N>),
+            bound: &Bigi<N>
+        ) -> Result<Bigi<N>, &'static str> {
+    let (c1, c2) = encrypted;
+    schema.validate_point(c1)?;
+    if !schema.curve.check(c2) {
+        return Err("point is not on the curve");
+    }
+    let s = schema.curve.mul(&c1, &private_key);
+    let p = schema.curve.add(&schema.curve.inv(&s), &c2);
+    dlog_kangaroo(&schema.curve, &schema.generator, &p,
+                  &Bigi::<N>::from(0), bound)
+        .ok_or("plaintext is out of bound")
+}
+
+
+/// Gets the maximum number of bytes that can be encrypted into a single
+/// point. The message is packed into `x` with one extra byte for the search
+/// of a point, and `x` must stay below the modulo of the curve.
+pub fn get_capacity<T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>
+        ) -> usize {
+    capacity(&schema.curve)
+}
+
+
+fn capacity<T: CurveTrait<N>, const N: usize>(curve: &T) -> usize {
+    (curve.get_modulo().bit_length() - 9) >> 3
 }
 
 
 fn bytes_to_point<T: CurveTrait<N>, const N: usize>(
             bytes: &[u8], curve: &T) -> Point<N> {
-    assert!(bytes.len() <= (N << 3) - 2);
+    assert!(bytes.len() <= capacity(curve));
 
     let mut bytes_aligned = vec![0u8; N << 3];
     bytes_aligned[..bytes.len()].copy_from_slice(bytes);
@@ -91,8 +156,9 @@ fn bytes_to_point<T: CurveTrait<N>, const N: usize>(
 }
 
 
-fn bytes_from_point<const N: usize>(p: &Point<N>) -> Vec<u8> {
-    (p.x >> 8).to_bytes()[..(N << 3) - 2].to_vec()
+fn bytes_from_point<T: CurveTrait<N>, const N: usize>(
+            p: &Point<N>, curve: &T) -> Vec<u8> {
+    (p.x >> 8).to_bytes()[..capacity(curve)].to_vec()
 }
 
 
@@ -112,7 +178,8 @@ mod tests {
         let (private_key, public_key) = schema.generate_pair(&mut rng);
 
         let encrypted = encrypt(&mut rng, &schema, &public_key, &message[..]);
-        let mut decripted = decrypt(&schema, &private_key, &encrypted);
+        let mut decripted = decrypt(&schema, &private_key, &encrypted)
+            .unwrap();
 
         if let Some(idx) = decripted.iter().rposition(|&c| c != 0) {
             decripted.truncate(idx + 1);
@@ -121,6 +188,77 @@ mod tests {
         assert_eq!(decripted, message);
     }
 
+    fn check_capacity<T: CurveTrait<N>, const N: usize>(
+                schema: &Schema<T, N>, capacity: usize) {
+        assert_eq!(get_capacity(schema), capacity);
+
+        let message: Vec<u8> = (1..=capacity as u8).collect();
+
+        let mut rng = rand::thread_rng();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+
+        let encrypted = encrypt(&mut rng, schema, &public_key, &message);
+        assert_eq!(decrypt(schema, &private_key, &encrypted).unwrap(),
+                   message);
+    }
+
+    #[test]
+    fn test_decrypt_small_subgroup() {
+        let message = b"a test phrase";
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve25519();
+
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let (c1, c2) = encrypt(&mut rng, &schema, &public_key, &message[..]);
+
+        // The point of order 2 added to the first point
+        let low_order = point!(Bigi::<4>::from(0), Bigi::<4>::from(0));
+        let mixed = schema.curve.add(&c1, &low_order);
+        assert_eq!(decrypt(&schema, &private_key, &(mixed, c2)),
+                   Err("point is not in the subgroup"));
+        assert_eq!(decrypt(&schema, &private_key, &(schema.curve.zero(), c2)),
+                   Err("point is the zero point"));
+        assert_eq!(decrypt(&schema, &private_key, &(c1, point!(c2.x, c2.x))),
+                   Err("point is not on the curve"));
+        assert!(decrypt(&schema, &private_key, &(c1, c2)).is_ok());
+    }
+
+    #[test]
+    fn test_exponent() {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_secp256k1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let bound = Bigi::<4>::from(1 << 16);
+
+        let m1 = Bigi::<4>::from(12345);
+        let m2 = Bigi::<4>::from(777);
+        let e1 = encrypt_exponent(&mut rng, &schema, &public_key, &m1);
+        let e2 = encrypt_exponent(&mut rng, &schema, &public_key, &m2);
+        assert_eq!(decrypt_exponent(&schema, &private_key, &e1, &bound),
+                   Ok(m1));
+
+        // The sum of ciphertexts decrypts to the sum of plaintexts
+        let sum = (schema.curve.add(&e1.0, &e2.0),
+                   schema.curve.add(&e1.1, &e2.1));
+        assert_eq!(decrypt_exponent(&schema, &private_key, &sum, &bound),
+                   Ok(m1 + &m2));
+
+        let e3 = encrypt_exponent(&mut rng, &schema, &public_key,
+                                  &Bigi::<4>::from(5000));
+        assert_eq!(decrypt_exponent(&schema, &private_key, &e3,
+                                    &Bigi::<4>::from(1000)),
+                   Err("plaintext is out of bound"));
+    }
+
+    #[test]
+    fn test_capacity() {
+        check_capacity(&schemas::load_secp160r1(), 18);
+        check_capacity(&schemas::load_secp224k1(), 26);
+        check_capacity(&schemas::load_secp256k1(), 30);
+        check_capacity(&schemas::load_secp521r1(), 64);
+    }
+
     #[bench]
     fn bench_encrypt(bencher: &mut Bencher) {
         let message = b"a test phrase";
@@ -0,0 +1,520 @@
+//! This module implements the tower of extension fields used by pairings:
+//!
+//! * `Fp2 = Fp[i] / (i^2 + 1)`, so `p = 3 (mod 4)` is required,
+//! * `Fp6 = Fp2[v] / (v^3 - xi)` where `xi = 1 + i`,
+//! * `Fp12 = Fp6[w] / (w^2 - v)`, so `w^6 = xi`.
+//!
+//! The elements do not store the modulo, it is passed to each operation
+//! like in `bigi::prime` functions.
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, inv_mod, sqrt_mod};
+use crate::base::FieldTrait;
+
+
+/// An element `c0 + c1 i` of `Fp2`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fp2<const N: usize> {
+    pub c0: Bigi<N>,
+    pub c1: Bigi<N>
+}
+
+
+/// An element `c0 + c1 v + c2 v^2` of `Fp6`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fp6<const N: usize> {
+    pub c0: Fp2<N>,
+    pub c1: Fp2<N>,
+    pub c2: Fp2<N>
+}
+
+
+/// An element `c0 + c1 w` of `Fp12`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fp12<const N: usize> {
+    pub c0: Fp6<N>,
+    pub c1: Fp6<N>
+}
+
+
+impl<const N: usize> Fp2<N> {
+    /// Creates an element from its coefficients.
+    pub fn new(c0: Bigi<N>, c1: Bigi<N>) -> Self {
+        Self { c0, c1 }
+    }
+
+    /// Embeds an element of `Fp`.
+    pub fn from_fp(c0: Bigi<N>) -> Self {
+        Self::new(c0, Bigi::<N>::from(0))
+    }
+
+    pub fn zero() -> Self {
+        Self::from_fp(Bigi::<N>::from(0))
+    }
+
+    pub fn one() -> Self {
+        Self::from_fp(Bigi::<N>::from(1))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn add(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(add_mod(&self.c0, &other.c0, m),
+                  add_mod(&self.c1, &other.c1, m))
+    }
+
+    pub fn sub(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(sub_mod(&self.c0, &other.c0, m),
+                  sub_mod(&self.c1, &other.c1, m))
+    }
+
+    pub fn neg(&self, m: &Bigi<N>) -> Self {
+        Self::zero().sub(self, m)
+    }
+
+    pub fn mul(&self, other: &Self, m: &Bigi<N>) -> Self {
+        // Karatsuba: (a0 + a1 i)(b0 + b1 i) = (a0 b0 - a1 b1) +
+        // ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) i
+        let t0 = mul_mod(&self.c0, &other.c0, m);
+        let t1 = mul_mod(&self.c1, &other.c1, m);
+        let t2 = mul_mod(
+            &add_mod(&self.c0, &self.c1, m),
+            &add_mod(&other.c0, &other.c1, m), m
+        );
+        Self::new(sub_mod(&t0, &t1, m),
+                  sub_mod(&sub_mod(&t2, &t0, m), &t1, m))
+    }
+
+    pub fn square(&self, m: &Bigi<N>) -> Self {
+        // (a0 + a1 i)^2 = (a0 + a1)(a0 - a1) + 2 a0 a1 i
+        let t = mul_mod(&self.c0, &self.c1, m);
+        Self::new(
+            mul_mod(&add_mod(&self.c0, &self.c1, m),
+                    &sub_mod(&self.c0, &self.c1, m), m),
+            add_mod(&t, &t, m)
+        )
+    }
+
+    /// Multiplies by an element of `Fp`.
+    pub fn mul_fp(&self, k: &Bigi<N>, m: &Bigi<N>) -> Self {
+        Self::new(mul_mod(&self.c0, k, m), mul_mod(&self.c1, k, m))
+    }
+
+    /// Multiplies by `xi = 1 + i`.
+    pub fn mul_by_xi(&self, m: &Bigi<N>) -> Self {
+        Self::new(sub_mod(&self.c0, &self.c1, m),
+                  add_mod(&self.c0, &self.c1, m))
+    }
+
+    /// Returns `c0 - c1 i` that is also the Frobenius map `x^p`.
+    pub fn conjugate(&self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0, sub_mod(&Bigi::<N>::from(0), &self.c1, m))
+    }
+
+    /// Returns the inverse, the zero element is mapped to zero.
+    pub fn inv(&self, m: &Bigi<N>) -> Self {
+        // 1 / (a0 + a1 i) = (a0 - a1 i) / (a0^2 + a1^2)
+        let norm = add_mod(&mul_mod(&self.c0, &self.c0, m),
+                           &mul_mod(&self.c1, &self.c1, m), m);
+        if norm.is_zero() {
+            return Self::zero();
+        }
+        self.conjugate(m).mul_fp(&inv_mod(&norm, m), m)
+    }
+
+    pub fn pow(&self, e: &Bigi<N>, m: &Bigi<N>) -> Self {
+        let mut res = Self::one();
+        for i in (0..e.bit_length()).rev() {
+            res = res.square(m);
+            if e.get_bit(i) {
+                res = res.mul(self, m);
+            }
+        }
+        res
+    }
+
+    /// Finds both square roots of the element.
+    pub fn sqrt(&self, m: &Bigi<N>) -> Result<(Self, Self), &'static str> {
+        if self.is_zero() {
+            return Ok((Self::zero(), Self::zero()));
+        }
+        let zero = Bigi::<N>::from(0);
+        let root = if self.c1.is_zero() {
+            // Either c0 or -c0 is a square in Fp because -1 is not
+            match sqrt_mod(&self.c0, m) {
+                Ok((r, _)) => Self::from_fp(r),
+                Err(_) => {
+                    let (r, _) = sqrt_mod(&sub_mod(&zero, &self.c0, m), m)?;
+                    Self::new(zero, r)
+                }
+            }
+        } else {
+            // x0^2 - x1^2 = c0 and 2 x0 x1 = c1, so
+            // x0^2 = (c0 +- sqrt(c0^2 + c1^2)) / 2
+            let norm = add_mod(&mul_mod(&self.c0, &self.c0, m),
+                               &mul_mod(&self.c1, &self.c1, m), m);
+            let (s, _) = sqrt_mod(&norm, m)?;
+            let half = inv_mod(&Bigi::<N>::from(2), m);
+            let t = mul_mod(&add_mod(&self.c0, &s, m), &half, m);
+            let x0 = match sqrt_mod(&t, m) {
+                Ok((r, _)) => r,
+                Err(_) => {
+                    let t = mul_mod(&sub_mod(&self.c0, &s, m), &half, m);
+                    sqrt_mod(&t, m)?.0
+                }
+            };
+            let x1 = mul_mod(&self.c1, &inv_mod(&add_mod(&x0, &x0, m), m), m);
+            Self::new(x0, x1)
+        };
+        if root.square(m) != *self {
+            return Err("not a square");
+        }
+        Ok((root, root.neg(m)))
+    }
+}
+
+
+impl<const N: usize> FieldTrait<N> for Fp2<N> {
+    fn zero() -> Self {
+        Fp2::zero()
+    }
+
+    fn from_bigi(x: &Bigi<N>) -> Self {
+        Fp2::from_fp(*x)
+    }
+
+    fn is_zero(&self) -> bool {
+        Fp2::is_zero(self)
+    }
+
+    fn add(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Fp2::add(self, other, m)
+    }
+
+    fn sub(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Fp2::sub(self, other, m)
+    }
+
+    fn mul(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Fp2::mul(self, other, m)
+    }
+
+    fn inv(&self, m: &Bigi<N>) -> Self {
+        Fp2::inv(self, m)
+    }
+
+    fn sqrt(&self, m: &Bigi<N>) -> Result<(Self, Self), &'static str> {
+        Fp2::sqrt(self, m)
+    }
+
+    fn neg(&self, m: &Bigi<N>) -> Self {
+        Fp2::neg(self, m)
+    }
+
+    fn square(&self, m: &Bigi<N>) -> Self {
+        Fp2::square(self, m)
+    }
+}
+
+
+impl<const N: usize> Fp6<N> {
+    pub fn new(c0: Fp2<N>, c1: Fp2<N>, c2: Fp2<N>) -> Self {
+        Self { c0, c1, c2 }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Fp2::zero(), Fp2::zero(), Fp2::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::new(Fp2::one(), Fp2::zero(), Fp2::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero() && self.c2.is_zero()
+    }
+
+    pub fn add(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0.add(&other.c0, m), self.c1.add(&other.c1, m),
+                  self.c2.add(&other.c2, m))
+    }
+
+    pub fn sub(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0.sub(&other.c0, m), self.c1.sub(&other.c1, m),
+                  self.c2.sub(&other.c2, m))
+    }
+
+    pub fn neg(&self, m: &Bigi<N>) -> Self {
+        Self::zero().sub(self, m)
+    }
+
+    pub fn mul(&self, other: &Self, m: &Bigi<N>) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+        let (b0, b1, b2) = (&other.c0, &other.c1, &other.c2);
+        let t0 = a0.mul(b0, m);
+        let t1 = a1.mul(b1, m);
+        let t2 = a2.mul(b2, m);
+
+        // c0 = t0 + xi ((a1 + a2)(b1 + b2) - t1 - t2)
+        let c0 = a1.add(a2, m).mul(&b1.add(b2, m), m)
+            .sub(&t1, m).sub(&t2, m).mul_by_xi(m).add(&t0, m);
+
+        // c1 = (a0 + a1)(b0 + b1) - t0 - t1 + xi t2
+        let c1 = a0.add(a1, m).mul(&b0.add(b1, m), m)
+            .sub(&t0, m).sub(&t1, m).add(&t2.mul_by_xi(m), m);
+
+        // c2 = (a0 + a2)(b0 + b2) - t0 - t2 + t1
+        let c2 = a0.add(a2, m).mul(&b0.add(b2, m), m)
+            .sub(&t0, m).sub(&t2, m).add(&t1, m);
+
+        Self::new(c0, c1, c2)
+    }
+
+    pub fn square(&self, m: &Bigi<N>) -> Self {
+        self.mul(self, m)
+    }
+
+    /// Multiplies by an element of `Fp2`.
+    pub fn mul_fp2(&self, k: &Fp2<N>, m: &Bigi<N>) -> Self {
+        Self::new(self.c0.mul(k, m), self.c1.mul(k, m), self.c2.mul(k, m))
+    }
+
+    /// Multiplies by `v`.
+    pub fn mul_by_v(&self, m: &Bigi<N>) -> Self {
+        Self::new(self.c2.mul_by_xi(m), self.c0, self.c1)
+    }
+
+    /// Returns the inverse, the zero element is mapped to zero.
+    pub fn inv(&self, m: &Bigi<N>) -> Self {
+        let (a0, a1, a2) = (&self.c0, &self.c1, &self.c2);
+
+        // A = a0^2 - xi a1 a2, B = xi a2^2 - a0 a1, C = a1^2 - a0 a2
+        let a = a0.square(m).sub(&a1.mul(a2, m).mul_by_xi(m), m);
+        let b = a2.square(m).mul_by_xi(m).sub(&a0.mul(a1, m), m);
+        let c = a1.square(m).sub(&a0.mul(a2, m), m);
+
+        // F = a0 A + xi (a2 B + a1 C)
+        let f = a0.mul(&a, m).add(
+            &a2.mul(&b, m).add(&a1.mul(&c, m), m).mul_by_xi(m), m
+        );
+        let fi = f.inv(m);
+        Self::new(a.mul(&fi, m), b.mul(&fi, m), c.mul(&fi, m))
+    }
+}
+
+
+impl<const N: usize> Fp12<N> {
+    pub fn new(c0: Fp6<N>, c1: Fp6<N>) -> Self {
+        Self { c0, c1 }
+    }
+
+    pub fn zero() -> Self {
+        Self::new(Fp6::zero(), Fp6::zero())
+    }
+
+    pub fn one() -> Self {
+        Self::new(Fp6::one(), Fp6::zero())
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.c0.is_zero() && self.c1.is_zero()
+    }
+
+    pub fn is_one(&self) -> bool {
+        *self == Self::one()
+    }
+
+    pub fn add(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0.add(&other.c0, m), self.c1.add(&other.c1, m))
+    }
+
+    pub fn sub(&self, other: &Self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0.sub(&other.c0, m), self.c1.sub(&other.c1, m))
+    }
+
+    pub fn mul(&self, other: &Self, m: &Bigi<N>) -> Self {
+        // (a0 + a1 w)(b0 + b1 w) = a0 b0 + a1 b1 v +
+        // ((a0 + a1)(b0 + b1) - a0 b0 - a1 b1) w
+        let t0 = self.c0.mul(&other.c0, m);
+        let t1 = self.c1.mul(&other.c1, m);
+        let c1 = self.c0.add(&self.c1, m).mul(&other.c0.add(&other.c1, m), m)
+            .sub(&t0, m).sub(&t1, m);
+        Self::new(t0.add(&t1.mul_by_v(m), m), c1)
+    }
+
+    pub fn square(&self, m: &Bigi<N>) -> Self {
+        self.mul(self, m)
+    }
+
+    /// Returns `c0 - c1 w` that is `x^(p^6)`, it is the inverse for
+    /// the elements of norm 1 (e.g. after the easy part of the final
+    /// exponentiation).
+    pub fn conjugate(&self, m: &Bigi<N>) -> Self {
+        Self::new(self.c0, self.c1.neg(m))
+    }
+
+    /// Returns the inverse, the zero element is mapped to zero.
+    pub fn inv(&self, m: &Bigi<N>) -> Self {
+        // 1 / (a0 + a1 w) = (a0 - a1 w) / (a0^2 - a1^2 v)
+        let t = self.c0.square(m).sub(&self.c1.square(m).mul_by_v(m), m)
+            .inv(m);
+        Self::new(self.c0.mul(&t, m), self.c1.mul(&t, m).neg(m))
+    }
+
+    pub fn pow(&self, e: &Bigi<N>, m: &Bigi<N>) -> Self {
+        let mut res = Self::one();
+        for i in (0..e.bit_length()).rev() {
+            res = res.square(m);
+            if e.get_bit(i) {
+                res = res.mul(self, m);
+            }
+        }
+        res
+    }
+
+    /// Calculates the Frobenius map `x^p`. As `Fp12 = Fp2[w] / (w^6 - xi)`,
+    /// the coefficient of `w^k` is conjugated and multiplied by
+    /// `gamma[k] = xi^(k (p - 1) / 6)` (see `frobenius_coeffs`).
+    pub fn frobenius(&self, gamma: &[Fp2<N>; 6], m: &Bigi<N>) -> Self {
+        // w^k: c0.c0, c1.c0, c0.c1, c1.c1, c0.c2, c1.c2 for k = 0..5
+        let f = |x: &Fp2<N>, k: usize| x.conjugate(m).mul(&gamma[k], m);
+        Self::new(
+            Fp6::new(f(&self.c0.c0, 0), f(&self.c0.c1, 2), f(&self.c0.c2, 4)),
+            Fp6::new(f(&self.c1.c0, 1), f(&self.c1.c1, 3), f(&self.c1.c2, 5))
+        )
+    }
+}
+
+
+/// Calculates the constants `xi^(k (p - 1) / 6)` for `k = 0..5` used by
+/// `Fp12::frobenius`. `p = 1 (mod 6)` is required.
+pub fn frobenius_coeffs<const N: usize>(m: &Bigi<N>) -> [Fp2<N>; 6] {
+    let xi = Fp2::new(Bigi::<N>::from(1), Bigi::<N>::from(1));
+    let g = xi.pow(&((*m - &Bigi::<N>::from(1)) / &Bigi::<N>::from(6)), m);
+    let mut res = [Fp2::one(); 6];
+    for k in 1..6 {
+        res[k] = res[k - 1].mul(&g, m);
+    }
+    res
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use rand::Rng;
+    use crate::schemas::load_fp254bnb;
+
+    fn random_fp2<R: Rng + ?Sized>(rng: &mut R, m: &Bigi<4>) -> Fp2<4> {
+        Fp2::new(Bigi::<4>::gen_random(rng, 256, false) % m,
+                 Bigi::<4>::gen_random(rng, 256, false) % m)
+    }
+
+    fn random_fp12<R: Rng + ?Sized>(rng: &mut R, m: &Bigi<4>) -> Fp12<4> {
+        let mut random_fp6 = || Fp6::new(
+            random_fp2(rng, m), random_fp2(rng, m), random_fp2(rng, m)
+        );
+        Fp12::new(random_fp6(), random_fp6())
+    }
+
+    #[test]
+    fn test_fp2() {
+        let mut rng = rand::thread_rng();
+        let m = load_fp254bnb().curve.m;
+        let a = random_fp2(&mut rng, &m);
+        let b = random_fp2(&mut rng, &m);
+
+        // i^2 = -1
+        let i = Fp2::new(Bigi::<4>::from(0), Bigi::<4>::from(1));
+        assert_eq!(i.square(&m), Fp2::one().neg(&m));
+
+        assert_eq!(a.mul(&b, &m), b.mul(&a, &m));
+        assert_eq!(a.square(&m), a.mul(&a, &m));
+        assert_eq!(a.mul(&a.inv(&m), &m), Fp2::one());
+        assert_eq!(a.pow(&m, &m), a.conjugate(&m));
+        assert_eq!(a.mul_by_xi(&m),
+                   a.mul(&Fp2::new(Bigi::<4>::from(1), Bigi::<4>::from(1)), &m));
+    }
+
+    #[test]
+    fn test_fp2_sqrt() {
+        let mut rng = rand::thread_rng();
+        let m = load_fp254bnb().curve.m;
+
+        let a = random_fp2(&mut rng, &m);
+        let (r1, r2) = a.square(&m).sqrt(&m).unwrap();
+        assert!(r1 == a || r2 == a);
+        assert_eq!(r1, r2.neg(&m));
+
+        // sqrt(-4) = 2i
+        let (r, _) = Fp2::from_fp(m - &Bigi::<4>::from(4)).sqrt(&m).unwrap();
+        assert_eq!(r.square(&m), Fp2::from_fp(m - &Bigi::<4>::from(4)));
+        assert!(r.c0.is_zero());
+
+        // xi = 1 + i is not a square
+        let xi = Fp2::new(Bigi::<4>::from(1), Bigi::<4>::from(1));
+        assert!(xi.sqrt(&m).is_err());
+        assert!(xi.mul(&a.square(&m), &m).sqrt(&m).is_err());
+    }
+
+    #[test]
+    fn test_fp6() {
+        let mut rng = rand::thread_rng();
+        let m = load_fp254bnb().curve.m;
+        let a = Fp6::new(random_fp2(&mut rng, &m), random_fp2(&mut rng, &m),
+                         random_fp2(&mut rng, &m));
+        let b = Fp6::new(random_fp2(&mut rng, &m), random_fp2(&mut rng, &m),
+                         random_fp2(&mut rng, &m));
+
+        // v^3 = xi
+        let v = Fp6::new(Fp2::zero(), Fp2::one(), Fp2::zero());
+        assert_eq!(v.mul(&v, &m).mul(&v, &m),
+                   Fp6::new(Fp2::one().mul_by_xi(&m), Fp2::zero(), Fp2::zero()));
+
+        assert_eq!(a.mul(&b, &m), b.mul(&a, &m));
+        assert_eq!(a.mul_by_v(&m), a.mul(&v, &m));
+        assert_eq!(a.mul(&a.inv(&m), &m), Fp6::one());
+    }
+
+    #[test]
+    fn test_fp12() {
+        let mut rng = rand::thread_rng();
+        let m = load_fp254bnb().curve.m;
+        let a = random_fp12(&mut rng, &m);
+        let b = random_fp12(&mut rng, &m);
+        let c = random_fp12(&mut rng, &m);
+
+        assert_eq!(a.mul(&b, &m), b.mul(&a, &m));
+        assert_eq!(a.mul(&b, &m).mul(&c, &m), a.mul(&b.mul(&c, &m), &m));
+        assert_eq!(a.add(&b, &m).mul(&c, &m),
+                   a.mul(&c, &m).add(&b.mul(&c, &m), &m));
+        assert_eq!(a.mul(&a.inv(&m), &m), Fp12::one());
+
+        // The Frobenius map is x^p and 12 of them are the identity
+        let gamma = frobenius_coeffs(&m);
+        assert_eq!(a.frobenius(&gamma, &m), a.pow(&m, &m));
+        let mut x = a;
+        for _ in 0..12 {
+            x = x.frobenius(&gamma, &m);
+        }
+        assert_eq!(x, a);
+
+        // Six Frobenius maps are the conjugation
+        let mut x = a;
+        for _ in 0..6 {
+            x = x.frobenius(&gamma, &m);
+        }
+        assert_eq!(x, a.conjugate(&m));
+    }
+
+    #[bench]
+    fn bench_fp12_mul(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let m = load_fp254bnb().curve.m;
+        let a = random_fp12(&mut rng, &m);
+        let b = random_fp12(&mut rng, &m);
+        bencher.iter(|| a.mul(&b, &m));
+    }
+}
@@ -0,0 +1,310 @@
+//! This module generates random curves of prime order (or with the given
+//! small cofactor) over the given prime field. The parameters and
+//! the generator are derived from a seed with SHA-256 hashing to the field,
+//! so anyone can verify that the curve was not chosen with a hidden
+//! weakness (like the verifiably random curves of ANSI X9.62).
+//!
+//! Weierstrass curves `y^2 = x^3 + A x + B` take `A` and `B` from the seed,
+//! Edwards curves `x^2 + y^2 = 1 + D x^2 y^2` take a non-square `D`, so
+//! the addition law is complete and the cofactor is 4.
+//!
+//! Usage example:
+//! ```rust
+//! use bigi::Bigi;
+//! use bigi_ecc::generation::{generate_weierstrass_schema,
+//!                            verify_weierstrass_schema};
+//!
+//! let mut rng = rand::thread_rng();
+//! let m = Bigi::<1>::from(4294967291);
+//! let (schema, seed) = generate_weierstrass_schema(
+//!     &mut rng, &m, &Bigi::<1>::from(1), "test32"
+//! );
+//! assert!(schema.validate().is_valid());
+//! assert!(verify_weierstrass_schema(&schema, &seed));
+//! ```
+use rand::Rng;
+use sha2::Sha256;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod};
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::edwards::EdwardsCurve;
+use crate::schemas::Schema;
+use crate::numbers::{is_prime, pow_mod};
+use crate::counting::count_points;
+use crate::hash_to_curve::hash_to_field;
+
+
+/// Domain separation tag to derive curve parameters from the seed.
+pub const DST_CURVE: &[u8] = b"BIGI_ECC_CURVE_GEN_SHA256_";
+
+/// Domain separation tag to derive the generator from the seed.
+pub const DST_GENERATOR: &[u8] = b"BIGI_ECC_GENERATOR_SHA256_";
+
+/// The size of a random seed in bytes.
+pub const SEED_SIZE: usize = 32;
+
+
+/// Derives Weierstrass curve from the seed: `A` and `B` are two elements
+/// of the field hashed from the seed.
+pub fn weierstrass_curve_from_seed<const N: usize>(
+            m: &Bigi<N>, seed: &[u8]
+        ) -> WeierstrassCurve<N> {
+    let u = hash_to_field::<Sha256, N>(seed, DST_CURVE, 2, m, field_len(m))
+        .unwrap();
+    WeierstrassCurve { a: u[0], b: u[1], m: *m }
+}
+
+
+/// Derives Edwards curve from the seed: `D` is an element of the field
+/// hashed from the seed, `None` if it is a square.
+pub fn edwards_curve_from_seed<const N: usize>(
+            m: &Bigi<N>, seed: &[u8]
+        ) -> Option<EdwardsCurve<N>> {
+    let d = hash_to_field::<Sha256, N>(seed, DST_CURVE, 1, m, field_len(m))
+        .unwrap()[0];
+    let e = (*m - &Bigi::<N>::from(1)) >> 1;
+    if d.is_zero() || pow_mod(&d, &e, m) == Bigi::<N>::from(1) {
+        None
+    } else {
+        Some(EdwardsCurve { d, m: *m })
+    }
+}
+
+
+/// Derives the generator from the seed: `x` is hashed from the seed with
+/// a counter until it belongs to a point, `y` is the even root, the point
+/// is multiplied by the cofactor.
+pub fn generator_from_seed<T: CurveTrait<N>, const N: usize>(
+            curve: &T, cofactor: &Bigi<N>, seed: &[u8]
+        ) -> Point<N> {
+    let m = curve.get_modulo();
+    let mut counter: u32 = 0;
+    loop {
+        let msg = [seed, &counter.to_be_bytes()[..]].concat();
+        let x = hash_to_field::<Sha256, N>(
+            &msg, DST_GENERATOR, 1, &m, field_len(&m)
+        ).unwrap()[0];
+        if let Ok((y1, y2)) = curve.find_y(&x) {
+            let y = if y1.get_bit(0) { y2 } else { y1 };
+            let p = point!(x, y);
+            if curve.check(&p) {
+                let g = curve.mul(&p, cofactor);
+                if g != curve.zero() {
+                    return g;
+                }
+            }
+        }
+        counter += 1;
+    }
+}
+
+
+/// Generates a Weierstrass curve with the order `cofactor * n` where `n`
+/// is a prime different from the modulo. The seeds are random, the one
+/// that gives the curve is returned with the schema.
+pub fn generate_weierstrass_schema<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, m: &Bigi<N>, cofactor: &Bigi<N>,
+            title: &'static str
+        ) -> (Schema<WeierstrassCurve<N>, N>, Vec<u8>) {
+    loop {
+        let seed = gen_seed(rng);
+        let curve = weierstrass_curve_from_seed(m, &seed);
+        if curve.is_singular() {
+            continue;
+        }
+        let count = match count_points(rng, &curve) {
+            Ok(count) => count,
+            Err(_) => continue
+        };
+        if let Some(order) = prime_part(&count.order, cofactor, m) {
+            let generator = generator_from_seed(&curve, cofactor, &seed);
+            let schema = Schema {
+                bits: m.bit_length(), title, curve, order,
+                cofactor: *cofactor, generator
+            };
+            return (schema, seed);
+        }
+    }
+}
+
+
+/// Generates a complete Edwards curve with the order `4 n` where `n` is
+/// a prime different from the modulo. The points are counted on
+/// the birationally equivalent Weierstrass curve.
+pub fn generate_edwards_schema<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, m: &Bigi<N>, title: &'static str
+        ) -> (Schema<EdwardsCurve<N>, N>, Vec<u8>) {
+    let cofactor = Bigi::<N>::from(4);
+    loop {
+        let seed = gen_seed(rng);
+        let curve = match edwards_curve_from_seed(m, &seed) {
+            Some(curve) => curve,
+            None => continue
+        };
+        let count = match count_points(rng, &edwards_to_weierstrass(&curve)) {
+            Ok(count) => count,
+            Err(_) => continue
+        };
+        if let Some(order) = prime_part(&count.order, &cofactor, m) {
+            let generator = generator_from_seed(&curve, &cofactor, &seed);
+            let schema = Schema {
+                bits: m.bit_length(), title, curve, order, cofactor, generator
+            };
+            return (schema, seed);
+        }
+    }
+}
+
+
+/// Checks that the curve and the generator of the schema are derived from
+/// the seed and the schema is valid.
+pub fn verify_weierstrass_schema<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>, seed: &[u8]
+        ) -> bool {
+    let curve = weierstrass_curve_from_seed(&schema.curve.m, seed);
+    curve.a == schema.curve.a && curve.b == schema.curve.b &&
+        generator_from_seed(&curve, &schema.cofactor, seed) ==
+            schema.generator &&
+        schema.validate().is_valid()
+}
+
+
+/// Checks that the curve and the generator of the schema are derived from
+/// the seed and the schema is valid.
+pub fn verify_edwards_schema<const N: usize>(
+            schema: &Schema<EdwardsCurve<N>, N>, seed: &[u8]
+        ) -> bool {
+    match edwards_curve_from_seed(&schema.curve.m, seed) {
+        Some(curve) => {
+            curve.d == schema.curve.d &&
+                generator_from_seed(&curve, &schema.cofactor, seed) ==
+                    schema.generator &&
+                schema.validate().is_valid()
+        },
+        None => false
+    }
+}
+
+
+/// Gets Weierstrass curve that is birationally equivalent to the Edwards
+/// curve through Montgomery curve `B v^2 = u^3 + A u^2 + u` with
+/// `A = 2 (1 + D) / (1 - D)` and `B = 4 / (1 - D)`, so it has the same
+/// number of points.
+pub fn edwards_to_weierstrass<const N: usize>(
+            curve: &EdwardsCurve<N>
+        ) -> WeierstrassCurve<N> {
+    let m = &curve.m;
+    let c = |k: u64| Bigi::<N>::from(k);
+    let one_minus_d = sub_mod(&c(1), &curve.d, m);
+    let a = div_mod(&mul_mod(&c(2), &add_mod(&c(1), &curve.d, m), m),
+                    &one_minus_d, m);
+    let b = div_mod(&c(4), &one_minus_d, m);
+    let a2 = mul_mod(&a, &a, m);
+    let b2 = mul_mod(&b, &b, m);
+
+    // A' = (3 - A^2) / (3 B^2), B' = (2 A^3 - 9 A) / (27 B^3)
+    WeierstrassCurve {
+        a: div_mod(&sub_mod(&c(3), &a2, m), &mul_mod(&c(3), &b2, m), m),
+        b: div_mod(
+            &mul_mod(&sub_mod(&mul_mod(&c(2), &a2, m), &c(9), m), &a, m),
+            &mul_mod(&c(27), &mul_mod(&b2, &b, m), m), m
+        ),
+        m: *m
+    }
+}
+
+
+/// Gets `order / cofactor` if it is a prime different from `m`.
+fn prime_part<const N: usize>(order: &Bigi<N>, cofactor: &Bigi<N>,
+                              m: &Bigi<N>) -> Option<Bigi<N>> {
+    if cofactor.is_zero() {
+        return None;
+    }
+    let n = *order / cofactor;
+    if n * cofactor == *order && n != *m && is_prime(&n) {
+        Some(n)
+    } else {
+        None
+    }
+}
+
+
+/// Gets the number of bytes to hash to a field element with the bias
+/// below `2^-128`.
+fn field_len<const N: usize>(m: &Bigi<N>) -> usize {
+    (m.bit_length() + 128 + 7) >> 3
+}
+
+
+fn gen_seed<R: Rng + ?Sized>(rng: &mut R) -> Vec<u8> {
+    let mut seed = vec![0u8; SEED_SIZE];
+    rng.fill(&mut seed[..]);
+    seed
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use bigi::bigi;
+    use crate::counting::count_points_naive;
+    use crate::schemas::load_curve1174;
+
+    #[test]
+    fn test_weierstrass() {
+        let mut rng = rand::thread_rng();
+        let m = bigi![1; 4294967291];
+
+        let (schema, seed) = generate_weierstrass_schema(
+            &mut rng, &m, &bigi![1; 1], "test"
+        );
+        assert!(schema.validate().is_valid());
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+        assert!(verify_weierstrass_schema(&schema, &seed));
+        assert!(!verify_weierstrass_schema(&schema, b"another seed"));
+
+        let (schema, seed) = generate_weierstrass_schema(
+            &mut rng, &m, &bigi![1; 2], "test"
+        );
+        assert_eq!(schema.cofactor, bigi![1; 2]);
+        assert!(schema.validate().is_valid());
+        assert!(verify_weierstrass_schema(&schema, &seed));
+    }
+
+    #[test]
+    fn test_edwards() {
+        let mut rng = rand::thread_rng();
+        let m = bigi![1; 4294967291];
+
+        let (schema, seed) = generate_edwards_schema(&mut rng, &m, "test");
+        assert_eq!(schema.cofactor, bigi![1; 4]);
+        assert!(schema.validate().is_valid());
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+        assert!(verify_edwards_schema(&schema, &seed));
+    }
+
+    #[test]
+    fn test_edwards_to_weierstrass() {
+        assert!(!edwards_to_weierstrass(&load_curve1174().curve).is_singular());
+
+        // The number of points is the same, 2 is not a square modulo 1019
+        let edwards = EdwardsCurve { d: bigi![1; 2], m: bigi![1; 1019] };
+        let order = count_points_naive(&edwards_to_weierstrass(&edwards)).order;
+        let mut count = 0u64;
+        for x in 0..1019u64 {
+            if let Ok((y1, y2)) = edwards.find_y(&Bigi::<1>::from(x)) {
+                count += if y1 == y2 { 1 } else { 2 };
+            }
+        }
+        assert_eq!(order, Bigi::<1>::from(count));
+    }
+
+    #[bench]
+    fn bench_weierstrass_curve_from_seed(bencher: &mut Bencher) {
+        let m = Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+        bencher.iter(|| weierstrass_curve_from_seed(&m, b"seed"));
+    }
+}
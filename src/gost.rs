@@ -0,0 +1,358 @@
+//! This module implements GOST R 34.10-2012 digital signatures
+//! ([RFC 7091](https://www.rfc-editor.org/rfc/rfc7091)). The hash of a
+//! message (usually Streebog) is interpreted as a big-endian integer, so the
+//! caller must reverse the digest if its hash function outputs the vector
+//! in little-endian order. The signature is a pair `(r, s)`.
+//!
+//! The parameter sets in the twisted Edwards form are supported through
+//! `GostCurve` that maps their points to the Weierstrass form of the
+//! standard, so the signatures are compatible with both forms.
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::schemas;
+//! use bigi_ecc::gost::{build_signature, check_signature};
+//!
+//! // The hash of a message
+//! let hash = [7u8; 32];
+//!
+//! // Load schema
+//! let schema = schemas::load_gost256a();
+//!
+//! // Generate a key pair
+//! let mut rng = rand::thread_rng();
+//! let (private_key, public_key) = schema.generate_pair(&mut rng);
+//!
+//! // Build and check the signature
+//! let signature = build_signature(&mut rng, &schema, &private_key, &hash);
+//! assert!(check_signature(&schema, &public_key, &hash, &signature));
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod, inv_mod};
+use crate::{point, point_zero};
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::edwards::EdwardsCurve;
+use crate::schemas::Schema;
+use crate::utils::bigi_from_be_bytes_mod;
+
+
+/// A curve that can be used for GOST signatures.
+pub trait GostCurve<const N: usize>: CurveTrait<N> {
+    /// Maps the point to the Weierstrass form of the standard.
+    fn to_weierstrass(&self, p: &Point<N>) -> Point<N>;
+}
+
+
+impl<const N: usize> GostCurve<N> for WeierstrassCurve<N> {
+    fn to_weierstrass(&self, p: &Point<N>) -> Point<N> {
+        *p
+    }
+}
+
+
+impl<const N: usize> GostCurve<N> for EdwardsCurve<N> {
+    /// The curve `u^2 + v^2 = 1 + d u^2 v^2` is mapped by
+    /// `x = s (1 + v) / (1 - v) + t`, `y = s (1 + v) / ((1 - v) u)`,
+    /// where `s = (1 - d) / 4` and `t = (1 + d) / 6`.
+    fn to_weierstrass(&self, p: &Point<N>) -> Point<N> {
+        let m = &self.m;
+        let one = Bigi::<N>::from(1);
+        if p.is_zero || p.y == one {
+            return point_zero!(N);
+        }
+
+        let s = div_mod(&sub_mod(&one, &self.d, m), &Bigi::<N>::from(4), m);
+        let t = div_mod(&add_mod(&one, &self.d, m), &Bigi::<N>::from(6), m);
+
+        // The point (0, -1) of order 2 goes to (t, 0)
+        if p.x.is_zero() {
+            return point!(t, Bigi::<N>::from(0));
+        }
+
+        let w = div_mod(
+            &mul_mod(&s, &add_mod(&one, &p.y, m), m),
+            &sub_mod(&one, &p.y, m), m
+        );
+        point!(add_mod(&w, &t, m), div_mod(&w, &p.x, m))
+    }
+}
+
+
+/// Builds a signature for the given schema, private key and hash of a
+/// message.
+pub fn build_signature<R: Rng + ?Sized, T: GostCurve<N>, const N: usize>(
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            hash: &[u8]
+        ) -> (Bigi<N>, Bigi<N>) {
+    let e = hash_to_bigi(schema, hash);
+    loop {
+        let (k, _) = schema.generate_pair(rng);
+        if let Some(signature) = sign_with_nonce(schema, private_key, &e, &k) {
+            return signature;
+        }
+    }
+}
+
+
+/// Checks the signature for the given schema, public key and hash of
+/// a message.
+pub fn check_signature<T: GostCurve<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            public_key: &Point<N>,
+            hash: &[u8],
+            signature: &(Bigi<N>, Bigi<N>)
+        ) -> bool {
+    let q = &schema.order;
+    let (r, s) = signature;
+    if r.is_zero() || (r >= q) || s.is_zero() || (s >= q) {
+        return false;
+    }
+
+    // z1 = s / e, z2 = -r / e, C = z1 P + z2 Q
+    let v = inv_mod(&hash_to_bigi(schema, hash), q);
+    let z1 = mul_mod(s, &v, q);
+    let z2 = sub_mod(&Bigi::<N>::from(0), &mul_mod(r, &v, q), q);
+    let c = schema.curve.to_weierstrass(&schema.curve.mul_sum(
+        &[schema.generator, *public_key], &[z1, z2]
+    ));
+    if c.is_zero {
+        return false;
+    }
+    c.x % q == *r
+}
+
+
+fn hash_to_bigi<T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>, hash: &[u8]) -> Bigi<N> {
+    let e = bigi_from_be_bytes_mod(hash, &schema.order);
+    if e.is_zero() {
+        Bigi::<N>::from(1)
+    } else {
+        e
+    }
+}
+
+
+fn sign_with_nonce<T: GostCurve<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            e: &Bigi<N>,
+            k: &Bigi<N>
+        ) -> Option<(Bigi<N>, Bigi<N>)> {
+    let q = &schema.order;
+
+    // r = x(k P), s = r d + k e
+    let r = schema.curve.to_weierstrass(&schema.get_point(k)).x % q;
+    if r.is_zero() {
+        return None;
+    }
+    let s = add_mod(&mul_mod(&r, private_key, q), &mul_mod(k, e, q), q);
+    if s.is_zero() {
+        return None;
+    }
+    Some((r, s))
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::schemas;
+    use crate::utils::bytes_from_hex;
+
+    // The test curve of RFC 7091, section 7.1
+    fn load_example_256() -> Schema<WeierstrassCurve<4>, 4> {
+        Schema {
+            bits: 256,
+            title: "rfc7091-256",
+            curve: WeierstrassCurve::<4> {
+                a: Bigi::<4>::from_hex("0x7"),
+                b: Bigi::<4>::from_hex("0x5FBFF498AA938CE739B8E022FBAFEF40563F6E6A3472FC2A514C0CE9DAE23B7E"),
+                m: Bigi::<4>::from_hex("0x8000000000000000000000000000000000000000000000000000000000000431")
+            },
+            order: Bigi::<4>::from_hex("0x8000000000000000000000000000000150FE8A1892976154C59CFC193ACCF5B3"),
+            cofactor: Bigi::<4>::from_hex("0x1"),
+            generator: point!(
+                Bigi::<4>::from_hex("0x2"),
+                Bigi::<4>::from_hex("0x08E2A8A0E65147D4BD6316030E16D19C85C97F0A9CA267122B96ABBCEA7E8FC8")
+            )
+        }
+    }
+
+    // The test curve of RFC 7091, section 7.2
+    fn load_example_512() -> Schema<WeierstrassCurve<8>, 8> {
+        Schema {
+            bits: 512,
+            title: "rfc7091-512",
+            curve: WeierstrassCurve::<8> {
+                a: Bigi::<8>::from_hex("0x7"),
+                b: Bigi::<8>::from_hex("0x1CFF0806A31116DA29D8CFA54E57EB748BC5F377E49400FDD788B649ECA1AC4361834013B2AD7322480A89CA58E0CF74BC9E540C2ADD6897FAD0A3084F302ADC"),
+                m: Bigi::<8>::from_hex("0x4531ACD1FE0023C7550D267B6B2FEE80922B14B2FFB90F04D4EB7C09B5D2D15DF1D852741AF4704A0458047E80E4546D35B8336FAC224DD81664BBF528BE6373")
+            },
+            order: Bigi::<8>::from_hex("0x4531ACD1FE0023C7550D267B6B2FEE80922B14B2FFB90F04D4EB7C09B5D2D15DA82F2D7ECB1DBAC719905C5EECC423F1D86E25EDBE23C595D644AAF187E6E6DF"),
+            cofactor: Bigi::<8>::from_hex("0x1"),
+            generator: point!(
+                Bigi::<8>::from_hex("0x24D19CC64572EE30F396BF6EBBFD7A6C5213B3B3D7057CC825F91093A68CD762FD60611262CD838DC6B60AA7EEE804E28BC849977FAC33B4B530F1B120248A9A"),
+                Bigi::<8>::from_hex("0x2BB312A43BD2CE6E0D020613C857ACDDCFBF061E91E5F2C3F32447C259F39B2C83AB156D77F1496BF7EB3351E1EE4E43DC1A18B91B24640B6DBB92CB1ADD371E")
+            )
+        }
+    }
+
+    #[test]
+    fn test_rfc7091_256() {
+        let schema = load_example_256();
+        let private_key = Bigi::<4>::from_hex(
+            "0x7A929ADE789BB9BE10ED359DD39A72C11B60961F49397EEE1D19CE9891EC3B28"
+        );
+        let public_key = schema.get_point(&private_key);
+        assert_eq!(
+            public_key,
+            point!(
+                Bigi::<4>::from_hex("0x7F2B49E270DB6D90D8595BEC458B50C58585BA1D4E9B788F6689DBD8E56FD80B"),
+                Bigi::<4>::from_hex("0x26F1B489D6701DD185C8413A977B3CBBAF64D1C593D26627DFFB101A87FF77DA")
+            )
+        );
+
+        let hash = bytes_from_hex(
+            "2dfbc1b372d89a1188c09c52e0eec61fce52032ab1022e8e67ece6672b043ee5"
+        ).unwrap();
+        let k = Bigi::<4>::from_hex(
+            "0x77105C9B20BCD3122823C8CF6FCC7B956DE33814E95B7FE64FED924594DCEAB3"
+        );
+        let signature = sign_with_nonce(
+            &schema, &private_key, &hash_to_bigi(&schema, &hash), &k
+        ).unwrap();
+        assert_eq!(
+            signature,
+            (
+                Bigi::<4>::from_hex("0x41AA28D2F1AB148280CD9ED56FEDA41974053554A42767B83AD043FD39DC0493"),
+                Bigi::<4>::from_hex("0x01456C64BA4642A1653C235A98A60249BCD6D3F746B631DF928014F6C5BF9C40")
+            )
+        );
+        assert!(check_signature(&schema, &public_key, &hash, &signature));
+        assert!(!check_signature(&schema, &public_key, &hash[1..], &signature));
+    }
+
+    #[test]
+    fn test_rfc7091_512() {
+        let schema = load_example_512();
+        let private_key = Bigi::<8>::from_hex(
+            "0x0BA6048AADAE241BA40936D47756D7C93091A0E8514669700EE7508E508B102072E8123B2200A0563322DAD2827E2714A2636B7BFD18AADFC62967821FA18DD4"
+        );
+        let public_key = schema.get_point(&private_key);
+        assert_eq!(
+            public_key,
+            point!(
+                Bigi::<8>::from_hex("0x115DC5BC96760C7B48598D8AB9E740D4C4A85A65BE33C1815B5C320C854621DD5A515856D13314AF69BC5B924C8B4DDFF75C45415C1D9DD9DD33612CD530EFE1"),
+                Bigi::<8>::from_hex("0x37C7C90CD40B0F5621DC3AC1B751CFA0E2634FA0503B3D52639F5D7FB72AFD61EA199441D943FFE7F0C70A2759A3CDB84C114E1F9339FDF27F35ECA93677BEEC")
+            )
+        );
+
+        let hash = bytes_from_hex(
+            "3754f3cfacc9e0615c4f4a7c4d8dab531b09b6f9c170c533a71d147035b0c591\
+             7184ee536593f4414339976c647c5d5a407adedb1d560c4fc6777d2972075b8c"
+        ).unwrap();
+        let k = Bigi::<8>::from_hex(
+            "0x0359E7F4B1410FEACC570456C6801496946312120B39D019D455986E364F365886748ED7A44B3E794434006011842286212273A6D14CF70EA3AF71BB1AE679F1"
+        );
+        let signature = sign_with_nonce(
+            &schema, &private_key, &hash_to_bigi(&schema, &hash), &k
+        ).unwrap();
+        assert_eq!(
+            signature,
+            (
+                Bigi::<8>::from_hex("0x2F86FA60A081091A23DD795E1E3C689EE512A3C82EE0DCC2643C78EEA8FCACD35492558486B20F1C9EC197C90699850260C93BCBCD9C5C3317E19344E173AE36"),
+                Bigi::<8>::from_hex("0x1081B394696FFE8E6585E7A9362D26B6325F56778AADBC081C0BFBE933D52FF5823CE288E8C4F362526080DF7F70CE406A6EEB1F56919CB92A9853BDE73E5B4A")
+            )
+        );
+        assert!(check_signature(&schema, &public_key, &hash, &signature));
+    }
+
+    fn check_schema<T: GostCurve<N>, const N: usize>(schema: &Schema<T, N>) {
+        assert!(schema.curve.check(&schema.generator));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+
+        let mut rng = rand::thread_rng();
+        let hash = [0x5Au8; 64];
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature(&mut rng, schema, &private_key, &hash);
+        assert!(check_signature(schema, &public_key, &hash, &signature));
+        assert!(!check_signature(schema, &public_key, &hash[1..], &signature));
+    }
+
+    #[test]
+    fn test_gost_schemas() {
+        check_schema(&schemas::load_gost256a());
+        check_schema(&schemas::load_gost256b());
+        check_schema(&schemas::load_gost256c());
+        check_schema(&schemas::load_gost256d());
+        check_schema(&schemas::load_gost512a());
+        check_schema(&schemas::load_gost512b());
+        check_schema(&schemas::load_gost512c());
+    }
+
+    #[test]
+    fn test_twisted_edwards() {
+        // paramSetA in the Weierstrass form of RFC 7836
+        let edwards = schemas::load_gost256a();
+        let weierstrass = Schema {
+            bits: 256,
+            title: "tc26-gost-3410-2012-256-paramSetA",
+            curve: WeierstrassCurve::<4> {
+                a: Bigi::<4>::from_hex("0xC2173F1513981673AF4892C23035A27CE25E2013BF95AA33B22C656F277E7335"),
+                b: Bigi::<4>::from_hex("0x295F9BAE7428ED9CCC20E7C359A9D41A22FCCD9108E17BF7BA9337A6F8AE9513"),
+                m: edwards.curve.m
+            },
+            order: edwards.order,
+            cofactor: edwards.cofactor,
+            generator: point!(
+                Bigi::<4>::from_hex("0x91E38443A5E82C0D880923425712B2BB658B9196932E02C78B2582FE742DAA28"),
+                Bigi::<4>::from_hex("0x32879423AB1A0375895786C4BB46E9565FDE0B5344766740AF268ADB32322E5C")
+            )
+        };
+        assert_eq!(edwards.curve.to_weierstrass(&edwards.generator),
+                   weierstrass.generator);
+        assert_eq!(edwards.curve.to_weierstrass(&edwards.curve.zero()),
+                   weierstrass.curve.zero());
+
+        let mut rng = rand::thread_rng();
+        let hash = [0xA5u8; 32];
+        let (private_key, public_key) = edwards.generate_pair(&mut rng);
+        let public_key_w = edwards.curve.to_weierstrass(&public_key);
+        assert_eq!(public_key_w, weierstrass.get_point(&private_key));
+
+        let signature = build_signature(&mut rng, &edwards, &private_key, &hash);
+        assert!(check_signature(&weierstrass, &public_key_w, &hash, &signature));
+
+        let signature = build_signature(
+            &mut rng, &weierstrass, &private_key, &hash
+        );
+        assert!(check_signature(&edwards, &public_key, &hash, &signature));
+    }
+
+    #[bench]
+    fn bench_build_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_gost256a();
+        let (private_key, _public_key) = schema.generate_pair(&mut rng);
+        b.iter(|| build_signature(&mut rng, &schema, &private_key, &[7u8; 32]));
+    }
+
+    #[bench]
+    fn bench_check_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_gost256b();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature(
+            &mut rng, &schema, &private_key, &[7u8; 32]
+        );
+        b.iter(|| check_signature(&schema, &public_key, &[7u8; 32], &signature));
+    }
+}
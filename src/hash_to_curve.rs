@@ -0,0 +1,712 @@
+//! This module implements hashing to elliptic curves by
+//! [RFC 9380](https://www.rfc-editor.org/rfc/rfc9380): `expand_message_xmd`,
+//! `hash_to_field`, the simplified SWU map for Weierstrass curves (through
+//! an isogeny if `A B = 0`, like for secp256k1) and Elligator 2 for
+//! Montgomery and Edwards curves. `hash_to_curve` gives a random oracle
+//! (`RO_` suites), `encode_to_curve` is twice faster, but the points are
+//! not uniform (`NU_` suites).
+//!
+//! The suites of RFC 9380 for P-256, P-384, P-521, secp256k1, curve25519
+//! and BLS12-381 G1 are included. The rest are out of scope: BLS12-381 G2
+//! needs the map over `F_p^2`, edwards25519 is a twisted Edwards curve
+//! (`a = -1`) that `EdwardsCurve` does not cover, curve448 and edwards448
+//! use `expand_message_xof` with SHAKE256.
+//!
+//! Usage example:
+//! ```rust
+//! use bigi_ecc::schemas::load_secp256r1;
+//! use bigi_ecc::hash_to_curve::{hash_to_curve, load_p256_suite};
+//!
+//! let schema = load_secp256r1();
+//! let suite = load_p256_suite();
+//! let dst = format!("MY-APP-V01-CS01-with-{}", suite.ro_id());
+//!
+//! let p = hash_to_curve(&schema, &suite, b"a test phrase",
+//!                       dst.as_bytes()).unwrap();
+//! assert!(schema.curve.check(&p));
+//! ```
+use std::marker::PhantomData;
+use sha2::{Sha256, Sha384, Sha512, Digest};
+use sha2::digest::BlockInput;
+use sha2::digest::generic_array::typenum::Unsigned;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod, sqrt_mod};
+use crate::{point, point_zero};
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::montgomery::MontgomeryCurve;
+use crate::edwards::EdwardsCurve;
+use crate::schemas::Schema;
+use crate::utils::bigi_from_be_bytes_mod;
+
+
+/// A rational map `(x_num / x_den, y y_num / y_den)` from the curve `curve`
+/// to the target curve. The coefficients are listed from the constant
+/// term, the leading coefficients of the denominators are `1` and omitted.
+pub struct Isogeny<const N: usize> {
+    pub curve: WeierstrassCurve<N>,
+    pub x_num: Vec<Bigi<N>>,
+    pub x_den: Vec<Bigi<N>>,
+    pub y_num: Vec<Bigi<N>>,
+    pub y_den: Vec<Bigi<N>>
+}
+
+
+/// A hash-to-curve suite: the hash function `D`, the length `l` of
+/// a field element in bytes, the constant `Z` of the mapping and
+/// the isogeny for the simplified SWU if it is needed.
+pub struct Suite<D, const N: usize> {
+    /// The suite identifier without `RO_` or `NU_` at the end.
+    pub name: &'static str,
+    pub l: usize,
+    pub z: Bigi<N>,
+    pub isogeny: Option<Isogeny<N>>,
+    /// The multiplier to clear the cofactor if it is not the cofactor of
+    /// the schema.
+    pub h_eff: Option<Bigi<N>>,
+    hash: PhantomData<D>
+}
+
+
+/// A curve that a field element can be mapped to with the parameters of
+/// a suite.
+pub trait MapToCurve<const N: usize>: CurveTrait<N> {
+    /// Maps the field element `u` to a point of the curve.
+    fn map_to_curve<D>(&self, suite: &Suite<D, N>, u: &Bigi<N>) -> Point<N>;
+}
+
+
+impl<const N: usize> Isogeny<N> {
+    /// Maps a point of `curve` to the target curve.
+    pub fn map(&self, p: &Point<N>) -> Point<N> {
+        let m = &self.curve.m;
+        let x_den = eval_monic(&self.x_den, &p.x, m);
+        let y_den = eval_monic(&self.y_den, &p.x, m);
+        if p.is_zero || x_den.is_zero() || y_den.is_zero() {
+            return point_zero!(N);
+        }
+        point!(
+            div_mod(&eval(&self.x_num, &p.x, m), &x_den, m),
+            div_mod(&mul_mod(&p.y, &eval(&self.y_num, &p.x, m), m), &y_den, m)
+        )
+    }
+}
+
+
+impl<D, const N: usize> Suite<D, N> {
+    pub fn new(name: &'static str, l: usize, z: Bigi<N>,
+               isogeny: Option<Isogeny<N>>) -> Self {
+        Self { name, l, z, isogeny, h_eff: None, hash: PhantomData }
+    }
+
+    /// Gets the identifier of the random oracle suite.
+    pub fn ro_id(&self) -> String {
+        format!("{}RO_", self.name)
+    }
+
+    /// Gets the identifier of the nonuniform suite.
+    pub fn nu_id(&self) -> String {
+        format!("{}NU_", self.name)
+    }
+}
+
+
+impl<const N: usize> MapToCurve<N> for WeierstrassCurve<N> {
+    fn map_to_curve<D>(&self, suite: &Suite<D, N>, u: &Bigi<N>) -> Point<N> {
+        match &suite.isogeny {
+            Some(isogeny) => isogeny.map(
+                &map_to_curve_sswu(&isogeny.curve, &suite.z, u)
+            ),
+            None => map_to_curve_sswu(self, &suite.z, u)
+        }
+    }
+}
+
+
+impl<const N: usize> MapToCurve<N> for MontgomeryCurve<N> {
+    fn map_to_curve<D>(&self, suite: &Suite<D, N>, u: &Bigi<N>) -> Point<N> {
+        map_to_curve_elligator2(self, &suite.z, u)
+    }
+}
+
+
+impl<const N: usize> MapToCurve<N> for EdwardsCurve<N> {
+    fn map_to_curve<D>(&self, suite: &Suite<D, N>, u: &Bigi<N>) -> Point<N> {
+        map_to_curve_elligator2_edwards(self, &suite.z, u)
+    }
+}
+
+
+/// Expands the message to `len` pseudorandom bytes with the hash `D`
+/// (`expand_message_xmd`). A tag longer than 255 bytes is hashed first.
+pub fn expand_message_xmd<D: Digest + BlockInput>(
+            msg: &[u8], dst: &[u8], len: usize
+        ) -> Result<Vec<u8>, &'static str> {
+    let b_len = D::output_size();
+    let s_len = D::BlockSize::to_usize();
+    let ell = len.div_ceil(b_len);
+    if ell > 255 || len > 65535 {
+        return Err("requested length is too big");
+    }
+
+    let dst = if dst.len() > 255 {
+        let mut hasher = D::new();
+        hasher.update(b"H2C-OVERSIZE-DST-");
+        hasher.update(dst);
+        hasher.finalize().to_vec()
+    } else {
+        dst.to_vec()
+    };
+    let dst_prime = [&dst[..], &[dst.len() as u8][..]].concat();
+
+    let mut hasher = D::new();
+    hasher.update(vec![0u8; s_len]);
+    hasher.update(msg);
+    hasher.update((len as u16).to_be_bytes());
+    hasher.update([0u8]);
+    hasher.update(&dst_prime);
+    let b0 = hasher.finalize().to_vec();
+
+    let mut res = Vec::with_capacity(ell * b_len);
+    let mut bi = vec![0u8; b_len];
+    for i in 1..=ell {
+        let mut hasher = D::new();
+        let block: Vec<u8> = b0.iter().zip(bi.iter())
+            .map(|(x, y)| x ^ y).collect();
+        hasher.update(&block);
+        hasher.update([i as u8]);
+        hasher.update(&dst_prime);
+        bi = hasher.finalize().to_vec();
+        res.extend_from_slice(&bi);
+    }
+    res.truncate(len);
+    Ok(res)
+}
+
+
+/// Hashes the message to `count` elements of the field modulo `m`,
+/// `l` bytes are taken for each one.
+pub fn hash_to_field<D: Digest + BlockInput, const N: usize>(
+            msg: &[u8], dst: &[u8], count: usize, m: &Bigi<N>, l: usize
+        ) -> Result<Vec<Bigi<N>>, &'static str> {
+    let bytes = expand_message_xmd::<D>(msg, dst, count * l)?;
+    Ok(bytes.chunks(l).map(|c| bigi_from_be_bytes_mod(c, m)).collect())
+}
+
+
+/// Hashes the message to a point of the prime order subgroup, the result
+/// is indistinguishable from a random oracle.
+pub fn hash_to_curve<D: Digest + BlockInput, T: MapToCurve<N>,
+                     const N: usize>(
+            schema: &Schema<T, N>, suite: &Suite<D, N>, msg: &[u8],
+            dst: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let u = hash_to_field::<D, N>(
+        msg, dst, 2, &schema.curve.get_modulo(), suite.l
+    )?;
+    let q0 = schema.curve.map_to_curve(suite, &u[0]);
+    let q1 = schema.curve.map_to_curve(suite, &u[1]);
+    Ok(clear_cofactor(schema, suite, &schema.curve.add(&q0, &q1)))
+}
+
+
+/// Encodes the message to a point of the prime order subgroup with one
+/// mapping, the distribution of the points is not uniform.
+pub fn encode_to_curve<D: Digest + BlockInput, T: MapToCurve<N>,
+                       const N: usize>(
+            schema: &Schema<T, N>, suite: &Suite<D, N>, msg: &[u8],
+            dst: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let u = hash_to_field::<D, N>(
+        msg, dst, 1, &schema.curve.get_modulo(), suite.l
+    )?;
+    let q = schema.curve.map_to_curve(suite, &u[0]);
+    Ok(clear_cofactor(schema, suite, &q))
+}
+
+
+/// The simplified Shallue-van de Woestijne-Ulas map to a Weierstrass curve
+/// with `A != 0` and `B != 0`, `Z` is a non-square.
+pub fn map_to_curve_sswu<const N: usize>(
+            curve: &WeierstrassCurve<N>, z: &Bigi<N>, u: &Bigi<N>
+        ) -> Point<N> {
+    let m = &curve.m;
+    let (a, b) = (&curve.a, &curve.b);
+    let one = Bigi::<N>::from(1);
+
+    // tv = Z^2 u^4 + Z u^2
+    let zu2 = mul_mod(z, &mul_mod(u, u, m), m);
+    let tv = add_mod(&mul_mod(&zu2, &zu2, m), &zu2, m);
+
+    // x1 = -B / A (1 + 1 / tv), or B / (Z A) if tv = 0
+    let x1 = if tv.is_zero() {
+        div_mod(b, &mul_mod(z, a, m), m)
+    } else {
+        let t = add_mod(&one, &div_mod(&one, &tv, m), m);
+        sub_mod(&Bigi::<N>::from(0), &mul_mod(&div_mod(b, a, m), &t, m), m)
+    };
+    let g = |x: &Bigi<N>| {
+        add_mod(&mul_mod(&add_mod(&mul_mod(x, x, m), a, m), x, m), b, m)
+    };
+
+    let (x, y) = match sqrt(&g(&x1), m) {
+        Some(y) => (x1, y),
+        None => {
+            let x2 = mul_mod(&zu2, &x1, m);
+            let y = sqrt(&g(&x2), m).unwrap();
+            (x2, y)
+        }
+    };
+    let y = if sgn0(u) != sgn0(&y) {
+        sub_mod(&Bigi::<N>::from(0), &y, m)
+    } else {
+        y
+    };
+    point!(x, y)
+}
+
+
+/// Elligator 2 map to a Montgomery curve `B y^2 = x^3 + A x^2 + x`,
+/// `Z` is a non-square.
+pub fn map_to_curve_elligator2<const N: usize>(
+            curve: &MontgomeryCurve<N>, z: &Bigi<N>, u: &Bigi<N>
+        ) -> Point<N> {
+    curve.elligator_map(u, z)
+}
+
+
+/// Elligator 2 map to an Edwards curve `x^2 + y^2 = 1 + D x^2 y^2` through
+/// the birationally equivalent Montgomery curve with `A = 2 (1 + D) /
+/// (1 - D)` and `B = 4 / (1 - D)`.
+pub fn map_to_curve_elligator2_edwards<const N: usize>(
+            curve: &EdwardsCurve<N>, z: &Bigi<N>, u: &Bigi<N>
+        ) -> Point<N> {
+    let m = &curve.m;
+    let one = Bigi::<N>::from(1);
+    let one_minus_d = sub_mod(&one, &curve.d, m);
+    let montgomery = MontgomeryCurve {
+        a: div_mod(&mul_mod(&Bigi::<N>::from(2), &add_mod(&one, &curve.d, m), m),
+                   &one_minus_d, m),
+        b: div_mod(&Bigi::<N>::from(4), &one_minus_d, m),
+        m: *m
+    };
+    let p = map_to_curve_elligator2(&montgomery, z, u);
+
+    // (x, y) = (s / t, (s - 1) / (s + 1)), the exceptional cases give
+    // the neutral point
+    let s_plus_1 = add_mod(&p.x, &one, m);
+    if p.y.is_zero() || s_plus_1.is_zero() {
+        return curve.zero();
+    }
+    point!(
+        div_mod(&p.x, &p.y, m),
+        div_mod(&sub_mod(&p.x, &one, m), &s_plus_1, m)
+    )
+}
+
+
+/// Returns the sign of the field element as in RFC 9380 (the parity).
+pub fn sgn0<const N: usize>(x: &Bigi<N>) -> bool {
+    x.get_bit(0)
+}
+
+
+/// Returns P256_XMD:SHA-256_SSWU_ suite for `load_secp256r1`.
+pub fn load_p256_suite() -> Suite<Sha256, 4> {
+    let m = Bigi::<4>::from_hex("0xFFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF");
+    Suite::new("P256_XMD:SHA-256_SSWU_", 48, m - &Bigi::<4>::from(10), None)
+}
+
+
+/// Returns P384_XMD:SHA-384_SSWU_ suite for `load_secp384r1`.
+pub fn load_p384_suite() -> Suite<Sha384, 6> {
+    let m = Bigi::<6>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFF");
+    Suite::new("P384_XMD:SHA-384_SSWU_", 72, m - &Bigi::<6>::from(12), None)
+}
+
+
+/// Returns P521_XMD:SHA-512_SSWU_ suite for `load_secp521r1`.
+pub fn load_p521_suite() -> Suite<Sha512, 9> {
+    let m = Bigi::<9>::from_hex("0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+    Suite::new("P521_XMD:SHA-512_SSWU_", 98, m - &Bigi::<9>::from(4), None)
+}
+
+
+/// Returns secp256k1_XMD:SHA-256_SSWU_ suite for `load_secp256k1`. As
+/// `A = 0`, the map goes to a 3-isogenous curve first.
+pub fn load_secp256k1_suite() -> Suite<Sha256, 4> {
+    let m = Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+    let hex = |s: &[&str]| -> Vec<Bigi<4>> {
+        s.iter().map(|h| Bigi::<4>::from_hex(h)).collect()
+    };
+    let isogeny = Isogeny {
+        curve: WeierstrassCurve {
+            a: Bigi::<4>::from_hex("0x3F8731ABDD661ADCA08A5558F0F5D272E953D363CB6F0E5D405447C01A444533"),
+            b: Bigi::<4>::from(1771),
+            m
+        },
+        x_num: hex(&[
+            "0x8E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38DAAAAA8C7",
+            "0x7D3D4C80BC321D5B9F315CEA7FD44C5D595D2FC0BF63B92DFFF1044F17C6581",
+            "0x534C328D23F234E6E2A413DECA25CAECE4506144037C40314ECBD0B53D9DD262",
+            "0x8E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38E38DAAAAA88C"
+        ]),
+        x_den: hex(&[
+            "0xD35771193D94918A9CA34CCBB7B640DD86CD409542F8487D9FE6B745781EB49B",
+            "0xEDADC6F64383DC1DF7C4B2D51B54225406D36B641F5E41BBC52A56612A8C6D14"
+        ]),
+        y_num: hex(&[
+            "0x4BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684B8E38E23C",
+            "0xC75E0C32D5CB7C0FA9D0A54B12A0A6D5647AB046D686DA6FDFFC90FC201D71A3",
+            "0x29A6194691F91A73715209EF6512E576722830A201BE2018A765E85A9ECEE931",
+            "0x2F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F684BDA12F38E38D84"
+        ]),
+        y_den: hex(&[
+            "0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFF93B",
+            "0x7A06534BB8BDB49FD5E9E6632722C2989467C1BFC8E8D978DFB425D2685C2573",
+            "0x6484AA716545CA2CF3A70C3FA8FE337E0A3D21162F0D6299A7BF8192BFD2A76F"
+        ])
+    };
+    Suite::new("secp256k1_XMD:SHA-256_SSWU_", 48, m - &Bigi::<4>::from(11),
+               Some(isogeny))
+}
+
+
+/// Returns BLS12381G1_XMD:SHA-256_SSWU_ suite for `load_bls12_381`. As
+/// `A = 0`, the map goes to an 11-isogenous curve first, the cofactor is
+/// cleared with `h_eff = 1 - u` (`u` is the parameter of the curve).
+pub fn load_bls12_381_g1_suite() -> Suite<Sha256, 6> {
+    let m = Bigi::<6>::from_hex("0x1A0111EA397FE69A4B1BA7B6434BACD764774B84F38512BF6730D2A0F6B0F6241EABFFFEB153FFFFB9FEFFFFFFFFAAAB");
+    let hex = |s: &[&str]| -> Vec<Bigi<6>> {
+        s.iter().map(|h| Bigi::<6>::from_hex(h)).collect()
+    };
+    let isogeny = Isogeny {
+        curve: WeierstrassCurve {
+            a: Bigi::<6>::from_hex("0x144698A3B8E9433D693A02C96D4982B0EA985383EE66A8D8E8981AEFD881AC98936F8DA0E0F97F5CF428082D584C1D"),
+            b: Bigi::<6>::from_hex("0x12E2908D11688030018B12E8753EEE3B2016C1F0F24F4070A0B9C14FCEF35EF55A23215A316CEAA5D1CC48E98E172BE0"),
+            m
+        },
+        x_num: hex(&[
+            "0x11A05F2B1E833340B809101DD99815856B303E88A2D7005FF2627B56CDB4E2C85610C2D5F2E62D6EAEAC1662734649B7",
+            "0x17294ED3E943AB2F0588BAB22147A81C7C17E75B2F6A8417F565E33C70D1E86B4838F2A6F318C356E834EEF1B3CB83BB",
+            "0xD54005DB97678EC1D1048C5D10A9A1BCE032473295983E56878E501EC68E25C958C3E3D2A09729FE0179F9DAC9EDCB0",
+            "0x1778E7166FCC6DB74E0609D307E55412D7F5E4656A8DBF25F1B33289F1B330835336E25CE3107193C5B388641D9B6861",
+            "0xE99726A3199F4436642B4B3E4118E5499DB995A1257FB3F086EEB65982FAC18985A286F301E77C451154CE9AC8895D9",
+            "0x1630C3250D7313FF01D1201BF7A74AB5DB3CB17DD952799B9ED3AB9097E68F90A0870D2DCAE73D19CD13C1C66F652983",
+            "0xD6ED6553FE44D296A3726C38AE652BFB11586264F0F8CE19008E218F9C86B2A8DA25128C1052ECADDD7F225A139ED84",
+            "0x17B81E7701ABDBE2E8743884D1117E53356DE5AB275B4DB1A682C62EF0F2753339B7C8F8C8F475AF9CCB5618E3F0C88E",
+            "0x80D3CF1F9A78FC47B90B33563BE990DC43B756CE79F5574A2C596C928C5D1DE4FA295F296B74E956D71986A8497E317",
+            "0x169B1F8E1BCFA7C42E0C37515D138F22DD2ECB803A0C5C99676314BAF4BB1B7FA3190B2EDC0327797F241067BE390C9E",
+            "0x10321DA079CE07E272D8EC09D2565B0DFA7DCCDDE6787F96D50AF36003B14866F69B771F8C285DECCA67DF3F1605FB7B",
+            "0x6E08C248E260E70BD1E962381EDEE3D31D79D7E22C837BC23C0BF1BC24C6B68C24B1B80B64D391FA9C8BA2E8BA2D229"
+        ]),
+        x_den: hex(&[
+            "0x8CA8D548CFF19AE18B2E62F4BD3FA6F01D5EF4BA35B48BA9C9588617FC8AC62B558D681BE343DF8993CF9FA40D21B1C",
+            "0x12561A5DEB559C4348B4711298E536367041E8CA0CF0800C0126C2588C48BF5713DAA8846CB026E9E5C8276EC82B3BFF",
+            "0xB2962FE57A3225E8137E629BFF2991F6F89416F5A718CD1FCA64E00B11ACEACD6A3D0967C94FEDCFCC239BA5CB83E19",
+            "0x3425581A58AE2FEC83AAFEF7C40EB545B08243F16B1655154CCA8ABC28D6FD04976D5243EECF5C4130DE8938DC62CD8",
+            "0x13A8E162022914A80A6F1D5F43E7A07DFFDFC759A12062BB8D6B44E833B306DA9BD29BA81F35781D539D395B3532A21E",
+            "0xE7355F8E4E667B955390F7F0506C6E9395735E9CE9CAD4D0A43BCEF24B8982F7400D24BC4228F11C02DF9A29F6304A5",
+            "0x772CAACF16936190F3E0C63E0596721570F5799AF53A1894E2E073062AEDE9CEA73B3538F0DE06CEC2574496EE84A3A",
+            "0x14A7AC2A9D64A8B230B3F5B074CF01996E7F63C21BCA68A81996E1CDF9822C580FA5B9489D11E2D311F7D99BBDCC5A5E",
+            "0xA10ECF6ADA54F825E920B3DAFC7A3CCE07F8D1D7161366B74100DA67F39883503826692ABBA43704776EC3A79A1D641",
+            "0x95FC13AB9E92AD4476D6E3EB3A56680F682B4EE96F7D03776DF533978F31C1593174E4B4B7865002D6384D168ECDD0A"
+        ]),
+        y_num: hex(&[
+            "0x90D97C81BA24EE0259D1F094980DCFA11AD138E48A869522B52AF6C956543D3CD0C7AEE9B3BA3C2BE9845719707BB33",
+            "0x134996A104EE5811D51036D776FB46831223E96C254F383D0F906343EB67AD34D6C56711962FA8BFE097E75A2E41C696",
+            "0xCC786BAA966E66F4A384C86A3B49942552E2D658A31CE2C344BE4B91400DA7D26D521628B00523B8DFE240C72DE1F6",
+            "0x1F86376E8981C217898751AD8746757D42AA7B90EEB791C09E4A3EC03251CF9DE405ABA9EC61DECA6355C77B0E5F4CB",
+            "0x8CC03FDEFE0FF135CAF4FE2A21529C4195536FBE3CE50B879833FD221351ADC2EE7F8DC099040A841B6DAECF2E8FEDB",
+            "0x16603FCA40634B6A2211E11DB8F0A6A074A7D0D4AFADB7BD76505C3D3AD5544E203F6326C95A807299B23AB13633A5F0",
+            "0x4AB0B9BCFAC1BBCB2C977D027796B3CE75BB8CA2BE184CB5231413C4D634F3747A87AC2460F415EC961F8855FE9D6F2",
+            "0x987C8D5333AB86FDE9926BD2CA6C674170A05BFE3BDD81FFD038DA6C26C842642F64550FEDFE935A15E4CA31870FB29",
+            "0x9FC4018BD96684BE88C9E221E4DA1BB8F3ABD16679DC26C1E8B6E6A1F20CABE69D65201C78607A360370E577BDBA587",
+            "0xE1BBA7A1186BDB5223ABDE7ADA14A23C42A0CA7915AF6FE06985E7ED1E4D43B9B3F7055DD4EBA6F2BAFAAEBCA731C30",
+            "0x19713E47937CD1BE0DFD0B8F1D43FB93CD2FCBCB6CAF493FD1183E416389E61031BF3A5CCE3FBAFCE813711AD011C132",
+            "0x18B46A908F36F6DEB918C143FED2EDCC523559B8AAF0C2462E6BFE7F911F643249D9CDF41B44D606CE07C8A4D0074D8E",
+            "0xB182CAC101B9399D155096004F53F447AA7B12A3426B08EC02710E807B4633F06C851C1919211F20D4C04F00B971EF8",
+            "0x245A394AD1ECA9B72FC00AE7BE315DC757B3B080D4C158013E6632D3C40659CC6CF90AD1C232A6442D9D3F5DB980133",
+            "0x5C129645E44CF1102A159F748C4A3FC5E673D81D7E86568D9AB0F5D396A7CE46BA1049B6579AFB7866B1E715475224B",
+            "0x15E6BE4E990F03CE4EA50B3B42DF2EB5CB181D8F84965A3957ADD4FA95AF01B2B665027EFEC01C7704B456BE69C8B604"
+        ]),
+        y_den: hex(&[
+            "0x16112C4C3A9C98B252181140FAD0EAE9601A6DE578980BE6EEC3232B5BE72E7A07F3688EF60C206D01479253B03663C1",
+            "0x1962D75C2381201E1A0CBD6C43C348B885C84FF731C4D59CA4A10356F453E01F78A4260763529E3532F6102C2E49A03D",
+            "0x58DF3306640DA276FAAAE7D6E8EB15778C4855551AE7F310C35A5DD279CD2ECA6757CD636F96F891E2538B53DBF67F2",
+            "0x16B7D288798E5395F20D23BF89EDB4D1D115C5DBDDBCD30E123DA489E726AF41727364F2C28297ADA8D26D98445F5416",
+            "0xBE0E079545F43E4B00CC912F8228DDCC6D19C9F0F69BBB0542EDA0FC9DEC916A20B15DC0FD2EDEDDA39142311A5001D",
+            "0x8D9E5297186DB2D9FB266EAAC783182B70152C65550D881C5ECD87B6F0F5A6449F38DB9DFA9CCE202C6477FAAF9B7AC",
+            "0x166007C08A99DB2FC3BA8734ACE9824B5EECFDFA8D0CF8EF5DD365BC400A0051D5FA9C01A58B1FB93D1A1399126A775C",
+            "0x16A3EF08BE3EA7EA03BCDDFABBA6FF6EE5A4375EFA1F4FD7FEB34FD206357132B920F5B00801DEE460EE415A15812ED9",
+            "0x1866C8ED336C61231A1BE54FD1D74CC4F9FB0CE4C6AF5920ABC5750C4BF39B4852CFE2F7BB9248836B233D9D55535D4A",
+            "0x167A55CDA70A6E1CEA820597D94A84903216F763E13D87BB5308592E7EA7D4FBC7385EA3D529B35E346EF48BB8913F55",
+            "0x4D2F259EEA405BD48F010A01AD2911D9C6DD039BB61A6290E591B36E636A5C871A5C29F4F83060400F8B49CBA8F6AA8",
+            "0xACCBB67481D033FF5852C1E48C50C477F94FF8AEFCE42D28C0F9A88CEA7913516F968986F7EBBEA9684B529E2561092",
+            "0xAD6B9514C767FE3C3613144B45F1496543346D98ADF02267D5CEEF9A00D9B8693000763E3B90AC11E99B138573345CC",
+            "0x2660400EB2E4F3B628BDD0D53CD76F2BF565B94E72927C1CB748DF27942480E420517BD8714CC80D1FADC1326ED06F7",
+            "0xE0FA1D816DDC03E6B24255E0D7819C171C40F65E273B853324EFCD6356CAA205CA2F570F13497804415473A1D634B8F"
+        ])
+    };
+    Suite {
+        h_eff: Some(Bigi::<6>::from_hex("0xD201000000010001")),
+        ..Suite::new("BLS12381G1_XMD:SHA-256_SSWU_", 64, Bigi::<6>::from(11),
+                     Some(isogeny))
+    }
+}
+
+
+/// Returns curve25519_XMD:SHA-512_ELL2_ suite for `load_curve25519`.
+pub fn load_curve25519_suite() -> Suite<Sha512, 4> {
+    Suite::new("curve25519_XMD:SHA-512_ELL2_", 48, Bigi::<4>::from(2), None)
+}
+
+
+/// Multiplies the point by `h_eff` of the suite or the cofactor of
+/// the schema.
+fn clear_cofactor<D, T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>, suite: &Suite<D, N>, p: &Point<N>
+        ) -> Point<N> {
+    schema.curve.mul(p, suite.h_eff.as_ref().unwrap_or(&schema.cofactor))
+}
+
+
+/// Gets a square root of `x` if it exists.
+fn sqrt<const N: usize>(x: &Bigi<N>, m: &Bigi<N>) -> Option<Bigi<N>> {
+    if x.is_zero() {
+        return Some(*x);
+    }
+    sqrt_mod(x, m).ok().map(|roots| roots.0)
+}
+
+
+/// Evaluates the polynomial with the coefficients from the constant term.
+fn eval<const N: usize>(coeffs: &[Bigi<N>], x: &Bigi<N>,
+                        m: &Bigi<N>) -> Bigi<N> {
+    coeffs.iter().rev().fold(Bigi::<N>::from(0), |acc, c| {
+        add_mod(&mul_mod(&acc, x, m), c, m)
+    })
+}
+
+
+/// Evaluates the polynomial with the omitted leading coefficient `1`.
+fn eval_monic<const N: usize>(coeffs: &[Bigi<N>], x: &Bigi<N>,
+                              m: &Bigi<N>) -> Bigi<N> {
+    let mut monic = coeffs.to_vec();
+    monic.push(Bigi::<N>::from(1));
+    eval(&monic, x, m)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::schemas::{load_secp256r1, load_secp384r1, load_secp521r1,
+                         load_secp256k1, load_curve25519, load_curve1174,
+                         load_bls12_381};
+    use crate::utils::bytes_to_hex;
+
+    fn quux(suite_id: &str) -> Vec<u8> {
+        format!("QUUX-V01-CS02-with-{}", suite_id).into_bytes()
+    }
+
+    /// The long message of RFC 9380 test vectors.
+    fn a512() -> Vec<u8> {
+        format!("a512_{}", "a".repeat(512)).into_bytes()
+    }
+
+    /// Checks `hash_to_curve` with RFC 9380 vectors `(msg, x, y)`.
+    fn check_ro<D: Digest + BlockInput, T: MapToCurve<N>, const N: usize>(
+                schema: &Schema<T, N>, suite: &Suite<D, N>,
+                vectors: &[(&[u8], &str, &str)]
+            ) {
+        let dst = quux(&suite.ro_id());
+        for &(msg, x, y) in vectors.iter() {
+            assert_eq!(
+                hash_to_curve(schema, suite, msg, &dst).unwrap(),
+                point!(Bigi::<N>::from_hex(x), Bigi::<N>::from_hex(y))
+            );
+        }
+    }
+
+    #[test]
+    fn test_expand_message_xmd() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+        assert_eq!(
+            bytes_to_hex(&expand_message_xmd::<Sha256>(b"", dst, 0x20).unwrap()),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+        assert_eq!(
+            bytes_to_hex(&expand_message_xmd::<Sha256>(b"abc", dst, 0x20).unwrap()),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+        assert_eq!(expand_message_xmd::<Sha256>(b"", dst, 0x80).unwrap().len(),
+                   0x80);
+        assert!(expand_message_xmd::<Sha256>(b"", dst, 256 * 32).is_err());
+    }
+
+    #[test]
+    fn test_p256() {
+        let schema = load_secp256r1();
+        let suite = load_p256_suite();
+        assert_eq!(
+            hash_to_curve(&schema, &suite, b"", &quux(&suite.ro_id())).unwrap(),
+            point!(
+                Bigi::<4>::from_hex("0x2C15230B26DBC6FC9A37051158C95B79656E17A1A920B11394CA91C44247D3E4"),
+                Bigi::<4>::from_hex("0x8A7A74985CC5C776CDFE4B1F19884970453912E9D31528C060BE9AB5C43E8415")
+            )
+        );
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b"abc"[..],
+             "0xBB8B87485551AA43ED54F009230450B492FEAD5F1CC91658775DAC4A3388A0F",
+             "0x5C41B3D0731A27A7B14BC0BF0CCDED2D8751F83493404C84A88E71FFD424212E"),
+            (&long[..],
+             "0x457AE2981F70CA85D8E24C308B14DB22F3E3862C5EA0F652CA38B5E49CD64BC5",
+             "0xECB9F0EADC9AEED232DABC53235368C1394C78DE05DD96893EEFA62B0F4757DC")
+        ]);
+        assert_eq!(
+            encode_to_curve(&schema, &suite, b"", &quux(&suite.nu_id())).unwrap(),
+            point!(
+                Bigi::<4>::from_hex("0xF871CAAD25EA3B59C16CF87C1894902F7E7B2C822C3D3F73596C5ACE8DDD14D1"),
+                Bigi::<4>::from_hex("0x87B9AE23335BEE057B99BAC1E68588B18B5691AF476234B8971BC4F011DDC99B")
+            )
+        );
+    }
+
+    #[test]
+    fn test_p384() {
+        let schema = load_secp384r1();
+        let suite = load_p384_suite();
+        assert_eq!(
+            hash_to_curve(&schema, &suite, b"", &quux(&suite.ro_id())).unwrap(),
+            point!(
+                Bigi::<6>::from_hex("0xEB9FE1B4F4E14E7140803C1D99D0A93CD823D2B024040F9C067A8ECA1F5A2EEAC9AD604973527A356F3FA3AEFF0E4D83"),
+                Bigi::<6>::from_hex("0x0C21708CFF382B7F4643C07B105C2EAEC2CEAD93A917D825601E63C8F21F6ABD9ABC22C93C2BED6F235954B25048BB1A")
+            )
+        );
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b"abc"[..],
+             "0xE02FC1A5F44A7519419DD314E29863F30DF55A514DA2D655775A81D413003C4D4E7FD59AF0826DFAAD4200AC6F60ABE1",
+             "0x1F638D04D98677D65BEF99AEF1A12A70A4CBB9270EC55248C04530D8BC1F8F90F8A6A859A7C1F1DDCCEDF8F96D675F6"),
+            (&long[..],
+             "0x7B18D210B1F090AC701F65F606F6CA18FB8D081E3BC6CBD937C5604325F1CDEA4C15C10A54EF303AABF2EA58BD9947A4",
+             "0xEA857285A33ABB516732915C353C75C576BF82CCC96ADB63C094DDE580021EDDEAFD91F8C0BFEE6F636528F3D0C47FD2")
+        ]);
+    }
+
+    #[test]
+    fn test_p521() {
+        let schema = load_secp521r1();
+        let suite = load_p521_suite();
+        assert_eq!(
+            hash_to_curve(&schema, &suite, b"", &quux(&suite.ro_id())).unwrap(),
+            point!(
+                Bigi::<9>::from_hex("0xFD767CEBB2452030358D0E9CF907F525F50920C8F607889A6A35680727F64F4D66B161FAFEB2654BEA0D35086BEC0A10B30B14ADEF3556ED9F7F1BC23CECC9C088"),
+                Bigi::<9>::from_hex("0x169BA78D8D851E930680322596E39C78F4FE31B97E57629EF6460DDD68F8763FD7BD767A4E94A80D3D21A3C2EE98347E024FC73EE1C27166DC3FE5EEEF782BE411D")
+            )
+        );
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b"abc"[..],
+             "0x2F89A1677B28054B50D15E1F81ED6669B5A2158211118EBDEF8A6EFC77F8CCAA528F698214E4340155ABC1FA08F8F613EF14A043717503D57E267D57155CF784A4",
+             "0x10E0BE5DC8E753DA8CE51091908B72396D3DEED14AE166F66D8EBF0A4E7059EAD169EA4BEAD0232E9B700DD380B316E9361CFDBA55A08C73545563A80966ECBB86D"),
+            (&long[..],
+             "0xC12BC3E28DB07B6B4D2A2B1167AB9E26FC2FA85C7B0498A17B0347EDF52392856D7E28B8FA7A2DD004611159505835B687ECF1A764857E27E9745848C436EF3925",
+             "0x1CD287DF9A50C22A9231BEB452346720BB163344A41C5F5A24E8335B6CCC595FD436AEA89737B1281AECB411EB835F0B939073FDD1DD4D5A2492E91EF4A3C55BCBD")
+        ]);
+    }
+
+    #[test]
+    fn test_secp256k1() {
+        let schema = load_secp256k1();
+        let suite = load_secp256k1_suite();
+        assert_eq!(
+            hash_to_curve(&schema, &suite, b"", &quux(&suite.ro_id())).unwrap(),
+            point!(
+                Bigi::<4>::from_hex("0xC1CAE290E291AEE617EBAEF1BE6D73861479C48B841EABA9B7B5852DDFEB1346"),
+                Bigi::<4>::from_hex("0x64FA678E07AE116126F08B022A94AF6DE15985C996C3A91B64C406A960E51067")
+            )
+        );
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b"abc"[..],
+             "0x3377E01EAB42DB296B512293120C6CEE72B6ECF9F9205760BD9FF11FB3CB2C4B",
+             "0x7F95890F33EFEBD1044D382A01B1BEE0900FB6116F94688D487C6C7B9C8371F6"),
+            (&long[..],
+             "0xE3C8D35AAAF0B9B647E88A0A0A7EE5D5BED5AD38238152E4E6FD8C1F8CB7C998",
+             "0x8446EEB6181BF12F56A9D24E262221CC2F0C4725C7E3803024B5888EE5823AA6")
+        ]);
+    }
+
+    #[test]
+    fn test_bls12_381() {
+        let schema = load_bls12_381();
+        let suite = load_bls12_381_g1_suite();
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b""[..],
+             "0x52926ADD2207B76CA4FA57A8734416C8DC95E24501772C814278700EED6D1E4E8CF62D9C09DB0FAC349612B759E79A1",
+             "0x8BA738453BFED09CB546DBB0783DBB3A5F1F566ED67BB6BE0E8C67E2E81A4CC68EE29813BB7994998F3EAE0C9C6A265"),
+            (&b"abc"[..],
+             "0x3567BC5EF9C690C2AB2ECDF6A96EF1C139CC0B2F284DCA0A9A7943388A49A3AEE664BA5379A7655D3C68900BE2F6903",
+             "0xB9C15F3FE6E5CF4211F346271D7B01C8F3B28BE689C8429C85B67AF215533311F0B8DFAAA154FA6B88176C229F2885D"),
+            (&long[..],
+             "0x82AABAE8B7DEDB0E78AEB619AD3BFD9277A2F77BA7FAD20EF6AABDC6C31D19BA5A6D12283553294C1825C4B3CA2DCFE",
+             "0x5B84AE5A942248EEA39E1D91030458C40153F3B654AB7872D779AD1E942856A20C438E8D99BC8ABFBF74729CE1F7AC8")
+        ]);
+    }
+
+    #[test]
+    fn test_curve25519() {
+        let schema = load_curve25519();
+        let suite = load_curve25519_suite();
+        assert_eq!(
+            hash_to_curve(&schema, &suite, b"", &quux(&suite.ro_id())).unwrap(),
+            point!(
+                Bigi::<4>::from_hex("0x2DE3780ABB67E861289F5749D16D3E217FFA722192D16BBD9D1BFB9D112B98C0"),
+                Bigi::<4>::from_hex("0x3B5DC2A498941A1033D176567D457845637554A2FE7A3507D21ABD1C1BD6E878")
+            )
+        );
+        let long = a512();
+        check_ro(&schema, &suite, &[
+            (&b"abc"[..],
+             "0x2B4419F1F2D48F5872DE692B0ACA72CC7B0A60915DD70BDE432E826B6ABC526D",
+             "0x1B8235F255A268F0A6FA8763E97EB3D22D149343D495DA1160EFF9703F2D07DD"),
+            (&long[..],
+             "0x1BC61845A138E912F047B5E70BA9606BA2A447A4DADE024C8EF3DD42B7BBC5FE",
+             "0x623D05E47B70E25F7F1D51DDA6D7C23C9A18CE015FE3548DF596EA9E38C69BF1")
+        ]);
+        assert_eq!(
+            encode_to_curve(&schema, &suite, b"", &quux(&suite.nu_id())).unwrap(),
+            point!(
+                Bigi::<4>::from_hex("0x1BB913F0C9DAEFA0B3375378FFA534BDA5526C97391952A7789EB976EDFE4D08"),
+                Bigi::<4>::from_hex("0x4548368F4F983243E747B62A600840AE7C1DAB5C723991F85D3A9768479F3EC4")
+            )
+        );
+    }
+
+    #[test]
+    fn test_edwards() {
+        // Z = -1 is the first non-square for p = 3 (mod 4)
+        let schema = load_curve1174();
+        let suite = Suite::<Sha512, 4>::new(
+            "curve1174_XMD:SHA-512_ELL2_", 48,
+            schema.curve.m - &Bigi::<4>::from(1), None
+        );
+        for msg in [&b""[..], &b"abc"[..], &b"a test phrase"[..]] {
+            let p = hash_to_curve(&schema, &suite, msg, b"TEST").unwrap();
+            assert!(schema.curve.check(&p));
+            assert_eq!(schema.curve.mul(&p, &schema.order), schema.curve.zero());
+        }
+    }
+
+    #[bench]
+    fn bench_hash_to_curve_p256(bencher: &mut Bencher) {
+        let schema = load_secp256r1();
+        let suite = load_p256_suite();
+        bencher.iter(|| hash_to_curve(&schema, &suite, b"abc", b"TEST"));
+    }
+}
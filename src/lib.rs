@@ -15,6 +15,25 @@ pub mod edwards;
 pub mod schemas;
 pub mod ecdsa;
 pub mod elgamal;
+pub mod eddsa;
+pub mod schnorr;
+pub mod ecdh;
+pub mod x25519;
+pub mod x448;
+pub mod sm3;
+pub mod sm2;
+pub mod gost;
+pub mod fields;
+pub mod pairing;
+pub mod bls;
+pub mod hash_to_curve;
+pub mod numbers;
+pub mod validation;
+pub mod counting;
+pub mod generation;
+pub mod dlog;
+pub mod torsion;
+pub mod utils;
 
 pub use base::*;
 pub use weierstrass::*;
@@ -35,6 +35,123 @@ impl<const N: usize> MontgomeryCurve<N> {
             &x, &self.m
         )
     }
+
+    /// Calculates `x` of `k P` by `x` of `P` with the Montgomery ladder
+    /// (RFC 7748) that processes `bits` lowest bits of `k`. The zero point
+    /// results in `0`.
+    pub fn ladder(&self, k: &Bigi<N>, u: &Bigi<N>, bits: usize) -> Bigi<N> {
+        let a24 = div_mod(
+            &sub_mod(&self.a, &Bigi::<N>::from(2), &self.m),
+            &Bigi::<N>::from(4), &self.m
+        );
+
+        let mut x2 = Bigi::<N>::from(1);
+        let mut z2 = Bigi::<N>::from(0);
+        let mut x3 = *u;
+        let mut z3 = Bigi::<N>::from(1);
+        let mut swap = false;
+
+        for t in (0..bits).rev() {
+            let bit = k.get_bit(t);
+            if swap != bit {
+                std::mem::swap(&mut x2, &mut x3);
+                std::mem::swap(&mut z2, &mut z3);
+            }
+            swap = bit;
+
+            let a = add_mod(&x2, &z2, &self.m);
+            let aa = mul_mod(&a, &a, &self.m);
+            let b = sub_mod(&x2, &z2, &self.m);
+            let bb = mul_mod(&b, &b, &self.m);
+            let e = sub_mod(&aa, &bb, &self.m);
+            let c = add_mod(&x3, &z3, &self.m);
+            let d = sub_mod(&x3, &z3, &self.m);
+            let da = mul_mod(&d, &a, &self.m);
+            let cb = mul_mod(&c, &b, &self.m);
+
+            let s = add_mod(&da, &cb, &self.m);
+            x3 = mul_mod(&s, &s, &self.m);
+            let s = sub_mod(&da, &cb, &self.m);
+            z3 = mul_mod(u, &mul_mod(&s, &s, &self.m), &self.m);
+            x2 = mul_mod(&aa, &bb, &self.m);
+            z2 = mul_mod(
+                &e, &add_mod(&aa, &mul_mod(&a24, &e, &self.m), &self.m),
+                &self.m
+            );
+        }
+
+        if swap {
+            std::mem::swap(&mut x2, &mut x3);
+            std::mem::swap(&mut z2, &mut z3);
+        }
+
+        if z2.is_zero() {
+            Bigi::<N>::from(0)
+        } else {
+            div_mod(&x2, &z2, &self.m)
+        }
+    }
+
+    /// Elligator 2 map of the field element `r` to a point of the curve as
+    /// in RFC 9380, `z` is a non-square (`2` for Curve25519). The points of
+    /// the first candidate `x` have odd `y / B`, of the second one even.
+    pub fn elligator_map(&self, r: &Bigi<N>, z: &Bigi<N>) -> Point<N> {
+        let m = &self.m;
+        let zero = Bigi::<N>::from(0);
+        let one = Bigi::<N>::from(1);
+
+        // The curve y^2 = x^3 + c1 x^2 + c2 x with c1 = A / B, c2 = 1 / B^2
+        let c1 = div_mod(&self.a, &self.b, m);
+        let c2 = div_mod(&one, &mul_mod(&self.b, &self.b, m), m);
+
+        // x1 = -c1 / (1 + Z r^2), or -c1 if the denominator is zero
+        let den = add_mod(&one, &mul_mod(z, &mul_mod(r, r, m), m), m);
+        let x1 = if den.is_zero() {
+            sub_mod(&zero, &c1, m)
+        } else {
+            sub_mod(&zero, &div_mod(&c1, &den, m), m)
+        };
+        let g = |x: &Bigi<N>| {
+            mul_mod(&add_mod(&mul_mod(&add_mod(x, &c1, m), x, m), &c2, m), x, m)
+        };
+
+        let (x, y, odd) = match sqrt(&g(&x1), m) {
+            Some(y) => (x1, y, true),
+            None => {
+                let x2 = sub_mod(&sub_mod(&zero, &x1, m), &c1, m);
+                let y = sqrt(&g(&x2), m).unwrap();
+                (x2, y, false)
+            }
+        };
+        let y = if y.get_bit(0) != odd { sub_mod(&zero, &y, m) } else { y };
+        point!(mul_mod(&x, &self.b, m), mul_mod(&y, &self.b, m))
+    }
+
+    /// Inverse Elligator 2 map: finds the representative `r <= (m - 1) / 2`
+    /// such that `elligator_map(r, z)` is the point. About a half of
+    /// the points are representable.
+    pub fn elligator_inverse(&self, p: &Point<N>,
+                             z: &Bigi<N>) -> Result<Bigi<N>, &'static str> {
+        let m = &self.m;
+        let c1 = div_mod(&self.a, &self.b, m);
+        let x = div_mod(&p.x, &self.b, m);
+        let y = div_mod(&p.y, &self.b, m);
+        let x_plus_c1 = add_mod(&x, &c1, m);
+        if p.is_zero || y.is_zero() || x.is_zero() || x_plus_c1.is_zero() {
+            return Err("point is not representable");
+        }
+
+        // r^2 = -(x + c1) / (Z x) for the first candidate,
+        // r^2 = -x / (Z (x + c1)) for the second one
+        let (num, den) = if y.get_bit(0) {
+            (x_plus_c1, mul_mod(z, &x, m))
+        } else {
+            (x, mul_mod(z, &x_plus_c1, m))
+        };
+        let r2 = sub_mod(&Bigi::<N>::from(0), &div_mod(&num, &den, m), m);
+        let roots = sqrt_mod(&r2, m).map_err(|_| "point is not representable")?;
+        Ok(if roots.0 < roots.1 { roots.0 } else { roots.1 })
+    }
 }
 
 
@@ -55,6 +172,12 @@ impl<const N: usize> CurveTrait<N> for MontgomeryCurve<N> {
         }
     }
 
+    fn is_singular(&self) -> bool {
+        // B (A^2 - 4) = 0
+        self.b.is_zero() ||
+            mul_mod(&self.a, &self.a, &self.m) == Bigi::<N>::from(4) % &self.m
+    }
+
     fn find_y(&self, x: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
         let right = self.right(&x);
         let y2 = div_mod(&right, &self.b, &self.m);
@@ -133,12 +256,21 @@ impl<const N: usize> CurveTrait<N> for MontgomeryCurve<N> {
 }
 
 
+/// Gets a square root of `x` if it exists.
+fn sqrt<const N: usize>(x: &Bigi<N>, m: &Bigi<N>) -> Option<Bigi<N>> {
+    if x.is_zero() {
+        return Some(*x);
+    }
+    sqrt_mod(x, m).ok().map(|roots| roots.0)
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use bigi::bigi;
     use crate::point_simple;
-    use crate::schemas::load_curve25519;
+    use crate::schemas::{load_curve25519, load_curve448};
     use test::Bencher;
 
     #[test]
@@ -152,6 +284,12 @@ mod tests {
         assert_eq!(curve.check(&point_simple!(4; 0, 0)), true);
         assert_eq!(curve.check(&point_zero!(4)), true);
         assert_eq!(curve.check(&point_simple!(4; 65, 81)), false);
+        assert!(!curve.is_singular());
+        assert!(MontgomeryCurve {
+            a: bigi![4; 95],
+            b: bigi![4; 2],
+            m: bigi![4; 97]
+        }.is_singular());
     }
 
     #[test]
@@ -229,6 +367,67 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ladder() {
+        let curve = MontgomeryCurve {
+            a: bigi![4; 5],
+            b: bigi![4; 2],
+            m: bigi![4; 97]
+        };
+        let p = point_simple!(4; 12, 39);
+
+        for k in 1..11u64 {
+            assert_eq!(
+                curve.ladder(&Bigi::<4>::from(k), &p.x, 4),
+                curve.mul(&p, &Bigi::<4>::from(k)).x
+            );
+        }
+        assert_eq!(curve.ladder(&bigi![4; 11], &p.x, 4), bigi![4; 0]);
+        assert_eq!(curve.ladder(&bigi![4; 3], &p.x, 64), bigi![4; 18]);
+    }
+
+    #[test]
+    fn test_elligator() {
+        let curve = MontgomeryCurve {
+            a: bigi![4; 5],
+            b: bigi![4; 2],
+            m: bigi![4; 97]
+        };
+        let z = bigi![4; 5];
+        let mut representable = 0;
+        for r in 0..49u64 {
+            let p = curve.elligator_map(&Bigi::<4>::from(r), &z);
+            assert!(curve.check(&p));
+            match curve.elligator_inverse(&p, &z) {
+                Ok(s) => {
+                    assert_eq!(s, Bigi::<4>::from(r));
+                    representable += 1;
+                },
+                Err(_) => assert!(r == 0 || p.y.is_zero())
+            }
+        }
+        assert!(representable > 40);
+        assert!(curve.elligator_inverse(&point_zero!(4), &z).is_err());
+        assert!(curve.elligator_inverse(&point_simple!(4; 0, 0), &z).is_err());
+    }
+
+    #[test]
+    fn test_elligator_curve25519() {
+        let schema = load_curve25519();
+        let z = bigi![4; 2];
+        let mut rng = rand::thread_rng();
+        let mut representable = 0;
+        for _ in 0..20 {
+            let (_, p) = schema.generate_pair(&mut rng);
+            if let Ok(r) = schema.curve.elligator_inverse(&p, &z) {
+                assert!(r.bit_length() < 254);
+                assert_eq!(schema.curve.elligator_map(&r, &z), p);
+                representable += 1;
+            }
+        }
+        assert!(representable > 0);
+    }
+
     #[test]
     fn test_curve25519() {
         let schema = load_curve25519();
@@ -237,6 +436,14 @@ mod tests {
         assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
     }
 
+    #[test]
+    fn test_curve448() {
+        let schema = load_curve448();
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&bigi![7; 25])));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
     #[bench]
     fn bench_curve25519_generate_pair(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -279,6 +486,18 @@ mod tests {
         bencher.iter(|| schema.curve.mul(&p, &l));
     }
 
+    #[bench]
+    fn bench_curve25519_ladder(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = load_curve25519();
+        let k = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let l = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let p = schema.get_point(&k);
+        bencher.iter(|| schema.curve.ladder(&l, &p.x, schema.bits));
+    }
+
     #[bench]
     fn bench_curve25519_check(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -0,0 +1,250 @@
+//! This module contains number-theoretic helpers for big integers: modular
+//! powers, the probabilistic primality test, integer square roots and
+//! division by small numbers that is used for trial factorization.
+use bigi::Bigi;
+use bigi::prime::mul_mod;
+
+
+/// Bases of Miller-Rabin test.
+const MILLER_RABIN_BASES: [u64; 16] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53
+];
+
+
+/// Calculates `x^e` modulo `m`.
+pub fn pow_mod<const N: usize>(x: &Bigi<N>, e: &Bigi<N>,
+                               m: &Bigi<N>) -> Bigi<N> {
+    let mut res = Bigi::<N>::from(1) % m;
+    for i in (0..e.bit_length()).rev() {
+        res = mul_mod(&res, &res, m);
+        if e.get_bit(i) {
+            res = mul_mod(&res, x, m);
+        }
+    }
+    res
+}
+
+
+/// Returns true if `n` is a probable prime. Small factors are found by trial
+/// division, then Miller-Rabin test is run with 16 prime bases.
+pub fn is_prime<const N: usize>(n: &Bigi<N>) -> bool {
+    let one = Bigi::<N>::from(1);
+    if *n <= one {
+        return false;
+    }
+    for &q in small_primes(MILLER_RABIN_BASES[15]).iter() {
+        if *n == Bigi::<N>::from(q) {
+            return true;
+        }
+        if div_small(n, q).1 == 0 {
+            return false;
+        }
+    }
+
+    // n - 1 = d 2^s
+    let n1 = *n - &one;
+    let mut d = n1;
+    let mut s = 0;
+    while !d.get_bit(0) {
+        d = d >> 1;
+        s += 1;
+    }
+
+    'bases: for &a in MILLER_RABIN_BASES.iter() {
+        let mut x = pow_mod(&Bigi::<N>::from(a), &d, n);
+        if x == one || x == n1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(&x, &x, n);
+            if x == n1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+
+/// Gets all primes up to `bound` inclusive with the sieve of Eratosthenes.
+pub fn small_primes(bound: u64) -> Vec<u64> {
+    let bound = bound as usize;
+    let mut sieve = vec![true; bound + 1];
+    let mut primes = Vec::new();
+    for i in 2..=bound {
+        if sieve[i] {
+            primes.push(i as u64);
+            for j in (i * i..=bound).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+    primes
+}
+
+
+/// Divides `x` by a small nonzero number `d`, returns the quotient and
+/// the remainder.
+pub fn div_small<const N: usize>(x: &Bigi<N>, d: u64) -> (Bigi<N>, u64) {
+    assert!(d != 0);
+    let mut bytes = x.to_bytes();
+    let mut r: u128 = 0;
+    for byte in bytes.iter_mut().rev() {
+        let cur = (r << 8) | (*byte as u128);
+        *byte = (cur / d as u128) as u8;
+        r = cur % d as u128;
+    }
+    (Bigi::<N>::from_bytes(&bytes), r as u64)
+}
+
+
+/// Factorizes `n` by trial division with primes up to `bound`, the rest
+/// must be 1 or a (probable) prime. Returns the primes with their exponents
+/// in ascending order, `None` if `n` is zero or the rest is composite.
+pub fn factorize<const N: usize>(n: &Bigi<N>,
+                                 bound: u64) -> Option<Vec<(Bigi<N>, usize)>> {
+    if n.is_zero() {
+        return None;
+    }
+    let one = Bigi::<N>::from(1);
+    let mut rest = *n;
+    let mut factors = Vec::new();
+    for &q in small_primes(bound).iter() {
+        if rest == one {
+            break;
+        }
+        let mut e = 0;
+        loop {
+            let (d, r) = div_small(&rest, q);
+            if r != 0 {
+                break;
+            }
+            rest = d;
+            e += 1;
+        }
+        if e > 0 {
+            factors.push((Bigi::<N>::from(q), e));
+        }
+    }
+    if rest != one {
+        if !is_prime(&rest) {
+            return None;
+        }
+        factors.push((rest, 1));
+    }
+    Some(factors)
+}
+
+
+/// Calculates the integer square root `floor(sqrt(x))` digit by digit.
+pub fn isqrt<const N: usize>(x: &Bigi<N>) -> Bigi<N> {
+    let zero = Bigi::<N>::from(0);
+    if x.is_zero() {
+        return zero;
+    }
+    let mut rest = *x;
+    let mut res = zero;
+    let mut bit = Bigi::<N>::from(1) << ((x.bit_length() - 1) & !1);
+    while !bit.is_zero() {
+        let t = res + &bit;
+        if rest >= t {
+            rest = rest - &t;
+            res = (res >> 1) + &bit;
+        } else {
+            res = res >> 1;
+        }
+        bit = bit >> 2;
+    }
+    res
+}
+
+
+/// Returns true if `x` is a perfect square.
+pub fn is_square<const N: usize>(x: &Bigi<N>) -> bool {
+    let r = isqrt(x);
+    r * &r == *x
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use bigi::bigi;
+
+    #[test]
+    fn test_pow_mod() {
+        assert_eq!(pow_mod(&bigi![4; 3], &bigi![4; 5], &bigi![4; 7]),
+                   bigi![4; 5]);
+        assert_eq!(pow_mod(&bigi![4; 3], &bigi![4; 0], &bigi![4; 7]),
+                   bigi![4; 1]);
+        assert_eq!(pow_mod(&bigi![4; 3], &bigi![4; 96], &bigi![4; 97]),
+                   bigi![4; 1]);
+    }
+
+    #[test]
+    fn test_is_prime() {
+        let primes: Vec<u64> = (0..100).filter(
+            |&n| is_prime(&Bigi::<4>::from(n))
+        ).collect();
+        assert_eq!(primes, small_primes(100));
+
+        // Carmichael numbers and strong pseudoprimes to base 2
+        assert!(!is_prime(&bigi![4; 561]));
+        assert!(!is_prime(&bigi![4; 2047]));
+        assert!(!is_prime(&bigi![4; 3215031751]));
+
+        assert!(is_prime(&bigi![4; 2305843009213693951]));
+        assert!(is_prime(&Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F")));
+        assert!(is_prime(&Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141")));
+        assert!(!is_prime(&(bigi![4; 2305843009213693951] *
+                            &bigi![4; 2147483647])));
+    }
+
+    #[test]
+    fn test_small_primes() {
+        assert_eq!(small_primes(20), vec![2, 3, 5, 7, 11, 13, 17, 19]);
+        assert_eq!(small_primes(1), vec![]);
+        assert_eq!(small_primes(1 << 16).len(), 6542);
+    }
+
+    #[test]
+    fn test_div_small() {
+        assert_eq!(div_small(&bigi![4; 1000], 7), (bigi![4; 142], 6));
+        let x = Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF");
+        let (q, r) = div_small(&x, 65537);
+        assert_eq!(q * &bigi![4; 65537] + &Bigi::<4>::from(r), x);
+    }
+
+    #[test]
+    fn test_factorize() {
+        assert_eq!(factorize(&bigi![4; 1068], 100),
+                   Some(vec![(bigi![4; 2], 2), (bigi![4; 3], 1),
+                             (bigi![4; 89], 1)]));
+        assert_eq!(factorize(&bigi![4; 1], 100), Some(vec![]));
+        let q = bigi![4; 2305843009213693951];
+        assert_eq!(factorize(&(q * &bigi![4; 8]), 100),
+                   Some(vec![(bigi![4; 2], 3), (q, 1)]));
+        assert_eq!(factorize(&(q * &q), 100), None);
+        assert_eq!(factorize(&bigi![4; 0], 100), None);
+    }
+
+    #[test]
+    fn test_isqrt() {
+        for n in 0..200u64 {
+            let r = isqrt(&Bigi::<4>::from(n));
+            assert_eq!(r, Bigi::<4>::from((n as f64).sqrt() as u64));
+        }
+        let x = bigi![4; 2305843009213693951];
+        assert_eq!(isqrt(&(x * &x)), x);
+        assert!(is_square(&(x * &x)));
+        assert!(!is_square(&(x * &x + &bigi![4; 1])));
+    }
+
+    #[bench]
+    fn bench_is_prime_256(bencher: &mut Bencher) {
+        let p = Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFC2F");
+        bencher.iter(|| is_prime(&p));
+    }
+}
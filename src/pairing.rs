@@ -0,0 +1,670 @@
+//! This module implements the optimal Ate pairing on Barreto-Naehrig (BN)
+//! and Barreto-Lynn-Scott (BLS12) curves `y^2 = x^3 + b` with the embedding
+//! degree 12. The group G1 is the curve over `Fp`, G2 is the order `r`
+//! subgroup of the sextic twist over `Fp2` (D-type `y^2 = x^3 + b / xi` for
+//! Fp254BNb, M-type `y^2 = x^3 + b xi` for BLS12-381) and the result belongs
+//! to the group of `r`-th roots of unity in `Fp12` (see `fields` for
+//! the tower).
+//!
+//! Example:
+//! ```rust
+//! use bigi::Bigi;
+//! use bigi_ecc::pairing::{load_fp254bnb_pairing, PairingTrait};
+//!
+//! let pairing = load_fp254bnb_pairing();
+//! let m = &pairing.schema.curve.m;
+//!
+//! let a = Bigi::<4>::from(5);
+//! let b = Bigi::<4>::from(7);
+//! let p = pairing.schema.get_point(&a);
+//! let q = pairing.get_point2(&b);
+//!
+//! // e(a G1, b G2) = e(G1, G2)^(a b)
+//! let e = pairing.pairing(&pairing.schema.generator, &pairing.g2);
+//! assert_eq!(pairing.pairing(&p, &q), e.pow(&Bigi::<4>::from(35), m));
+//! ```
+use sha2::Sha256;
+use bigi::Bigi;
+use crate::base::{Point, GenericPoint, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::schemas::{Schema, load_fp254bnb, load_bls12_381};
+use crate::fields::{Fp2, Fp6, Fp12, frobenius_coeffs};
+use crate::hash_to_curve::{Suite, load_bls12_381_g1_suite};
+
+
+/// A point on a curve over `Fp2`.
+pub type Point2<const N: usize> = GenericPoint<Fp2<N>>;
+
+
+/// The sextic twist `y^2 = x^3 + b` over `Fp2`.
+pub type TwistCurve<const N: usize> = WeierstrassCurve<N, Fp2<N>>;
+
+
+/// `PairingTrait` is a common interface of the pairings with the embedding
+/// degree 12: G1 is the group of `schema()`, G2 is the subgroup of
+/// `twist()` generated by `g2()` of the same order.
+pub trait PairingTrait<const N: usize> {
+    /// Gets the schema of G1.
+    fn schema(&self) -> &Schema<WeierstrassCurve<N>, N>;
+
+    /// Gets the twist curve containing G2.
+    fn twist(&self) -> &TwistCurve<N>;
+
+    /// Gets the generator of G2.
+    fn g2(&self) -> &Point2<N>;
+
+    /// Calculates the Miller function of the pairing.
+    fn miller_loop(&self, p: &Point<N>, q: &Point2<N>) -> Fp12<N>;
+
+    /// Raises `f` to the power `(p^12 - 1) / r`.
+    fn final_exponentiation(&self, f: &Fp12<N>) -> Fp12<N>;
+
+    /// Gets the hash-to-curve suite of G1 if RFC 9380 defines one.
+    fn g1_suite(&self) -> Option<&Suite<Sha256, N>> {
+        None
+    }
+
+    /// Gets point `k * G2`.
+    fn get_point2(&self, k: &Bigi<N>) -> Point2<N> {
+        self.twist().mul(self.g2(), k)
+    }
+
+    /// Calculates the optimal Ate pairing `e(p, q)` for `p` in G1 and `q`
+    /// in G2.
+    fn pairing(&self, p: &Point<N>, q: &Point2<N>) -> Fp12<N> {
+        self.final_exponentiation(&self.miller_loop(p, q))
+    }
+
+    /// Calculates the product `e(P1, Q1) e(P2, Q2) ...` with only one final
+    /// exponentiation.
+    fn pairing_product(&self, pairs: &[(Point<N>, Point2<N>)]) -> Fp12<N> {
+        let m = &self.schema().curve.m;
+        let f = pairs.iter().fold(Fp12::one(), |f, (p, q)| {
+            f.mul(&self.miller_loop(p, q), m)
+        });
+        self.final_exponentiation(&f)
+    }
+
+    /// Returns true if the point is on the curve and belongs to G1.
+    fn is_in_g1(&self, p: &Point<N>) -> bool {
+        let schema = self.schema();
+        schema.curve.check(p) && schema.curve.mul(p, &schema.order).is_zero
+    }
+
+    /// Returns true if the point is on the twist and belongs to G2.
+    fn is_in_g2(&self, q: &Point2<N>) -> bool {
+        self.twist().check(q) &&
+            self.twist().mul(q, &self.schema().order).is_zero
+    }
+}
+
+
+/// Parameters of the optimal Ate pairing on a BN curve with the parameter
+/// `u`, so `p = 36 u^4 + 36 u^3 + 24 u^2 + 6 u + 1` and
+/// `r = 36 u^4 + 36 u^3 + 18 u^2 + 6 u + 1`.
+pub struct BnPairing<const N: usize> {
+    pub schema: Schema<WeierstrassCurve<N>, N>,
+    pub twist: TwistCurve<N>,
+    /// The generator of G2.
+    pub g2: Point2<N>,
+    /// The absolute value of `u`.
+    pub u: Bigi<N>,
+    pub u_is_negative: bool,
+    gamma: [Fp2<N>; 6]
+}
+
+
+impl<const N: usize> BnPairing<N> {
+    /// Applies the Frobenius endomorphism `psi^-1 pi_p psi` to a point of
+    /// the twist, it acts on G2 as the multiplication by `p`.
+    pub fn frobenius(&self, q: &Point2<N>) -> Point2<N> {
+        if q.is_zero {
+            return *q;
+        }
+        let m = &self.schema.curve.m;
+        Point2::new(q.x.conjugate(m).mul(&self.gamma[2], m),
+                    q.y.conjugate(m).mul(&self.gamma[3], m))
+    }
+
+    /// Evaluates at `p` the line through `t` and `q` on the curve untwisted
+    /// by `(x, y) -> (x w^2, y w^3)` and returns it with `t + q`.
+    fn line(&self, t: &Point2<N>, q: &Point2<N>,
+            p: &Point<N>) -> (Fp12<N>, Point2<N>) {
+        let m = &self.schema.curve.m;
+        match slope(t, q, m) {
+            Some(alpha) => {
+                // l = yP - alpha xP w + (alpha xT - yT) w^3
+                let l = Fp12::new(
+                    Fp6::new(Fp2::from_fp(p.y), Fp2::zero(), Fp2::zero()),
+                    Fp6::new(
+                        alpha.mul_fp(&p.x, m).neg(m),
+                        alpha.mul(&t.x, m).sub(&t.y, m),
+                        Fp2::zero()
+                    )
+                );
+                (l, add_with_slope(t, q, &alpha, m))
+            },
+            // The vertical line is in Fp6 and vanishes after the final
+            // exponentiation
+            None => (Fp12::one(), self.twist.add(t, q))
+        }
+    }
+}
+
+
+impl<const N: usize> PairingTrait<N> for BnPairing<N> {
+    fn schema(&self) -> &Schema<WeierstrassCurve<N>, N> {
+        &self.schema
+    }
+
+    fn twist(&self) -> &TwistCurve<N> {
+        &self.twist
+    }
+
+    fn g2(&self) -> &Point2<N> {
+        &self.g2
+    }
+
+    /// Calculates the Miller function `f_{6u+2,Q}(P)` together with the two
+    /// lines through the Frobenius images of `Q`.
+    fn miller_loop(&self, p: &Point<N>, q: &Point2<N>) -> Fp12<N> {
+        let m = &self.schema.curve.m;
+        if p.is_zero || q.is_zero {
+            return Fp12::one();
+        }
+
+        // |6 u + 2|
+        let six_u = self.u * &Bigi::<N>::from(6);
+        let n = if self.u_is_negative {
+            six_u - &Bigi::<N>::from(2)
+        } else {
+            six_u + &Bigi::<N>::from(2)
+        };
+
+        let mut f = Fp12::<N>::one();
+        let mut t = *q;
+        for i in (0..(n.bit_length() - 1)).rev() {
+            let (l, t2) = self.line(&t, &t, p);
+            f = f.square(m).mul(&l, m);
+            t = t2;
+            if n.get_bit(i) {
+                let (l, tq) = self.line(&t, q, p);
+                f = f.mul(&l, m);
+                t = tq;
+            }
+        }
+
+        if self.u_is_negative {
+            f = f.conjugate(m);
+            t = self.twist.inv(&t);
+        }
+
+        let q1 = self.frobenius(q);
+        let q2 = self.twist.inv(&self.frobenius(&q1));
+        let (l, t) = self.line(&t, &q1, p);
+        f = f.mul(&l, m);
+        let (l, _) = self.line(&t, &q2, p);
+        f.mul(&l, m)
+    }
+
+    /// Raises `f` to the power `(p^12 - 1) / r`.
+    fn final_exponentiation(&self, f: &Fp12<N>) -> Fp12<N> {
+        let m = &self.schema.curve.m;
+        let frob = |x: &Fp12<N>| x.frobenius(&self.gamma, m);
+
+        // The easy part: f^((p^6 - 1)(p^2 + 1)), the result has norm 1,
+        // so its inverse is the conjugate
+        let f = f.conjugate(m).mul(&f.inv(m), m);
+        let f = frob(&frob(&f)).mul(&f, m);
+
+        // The hard part: (p^4 - p^2 + 1) / r = l0 + l1 p + l2 p^2 + p^3,
+        // l0 = -36 u^3 - 30 u^2 - 18 u - 2, l1 = -36 u^3 - 18 u^2 - 12 u + 1,
+        // l2 = 6 u^2 + 1
+        let pow_u = |x: &Fp12<N>| {
+            let y = x.pow(&self.u, m);
+            if self.u_is_negative { y.conjugate(m) } else { y }
+        };
+        let pow_k = |x: &Fp12<N>, k: u64| x.pow(&Bigi::<N>::from(k), m);
+
+        let fu = pow_u(&f);
+        let fu2 = pow_u(&fu);
+        let fu3 = pow_u(&fu2);
+
+        let fu3_36 = pow_k(&fu3, 36);
+        let l0 = fu3_36.mul(&pow_k(&fu2, 30), m).mul(&pow_k(&fu, 18), m)
+            .mul(&f.square(m), m).conjugate(m);
+        let l1 = fu3_36.mul(&pow_k(&fu2, 18), m).mul(&pow_k(&fu, 12), m)
+            .conjugate(m).mul(&f, m);
+        let l2 = pow_k(&fu2, 6).mul(&f, m);
+
+        l0.mul(&frob(&l1), m)
+            .mul(&frob(&frob(&l2)), m)
+            .mul(&frob(&frob(&frob(&f))), m)
+    }
+}
+
+
+/// Parameters of the optimal Ate pairing on a BLS12 curve with the parameter
+/// `u`, so `p = (u - 1)^2 (u^4 - u^2 + 1) / 3 + u` and `r = u^4 - u^2 + 1`.
+/// G2 is not stored as a `Schema` because its cofactor does not fit into
+/// `Bigi<N>`, so `clear_cofactor_g2` uses the endomorphism instead.
+pub struct Bls12Pairing<const N: usize> {
+    pub schema: Schema<WeierstrassCurve<N>, N>,
+    pub twist: TwistCurve<N>,
+    /// The generator of G2.
+    pub g2: Point2<N>,
+    /// The absolute value of `u`.
+    pub u: Bigi<N>,
+    pub u_is_negative: bool,
+    /// The hash-to-curve suite of G1.
+    pub g1_suite: Option<Suite<Sha256, N>>,
+    gamma: [Fp2<N>; 6],
+    gamma_inv: [Fp2<N>; 2]
+}
+
+
+impl<const N: usize> Bls12Pairing<N> {
+    /// Applies the endomorphism `psi^-1 pi_p psi` to a point of the twist,
+    /// it acts on G2 as the multiplication by `p` (and by `u` as well).
+    pub fn frobenius(&self, q: &Point2<N>) -> Point2<N> {
+        if q.is_zero {
+            return *q;
+        }
+        let m = &self.schema.curve.m;
+        Point2::new(q.x.conjugate(m).mul(&self.gamma_inv[0], m),
+                    q.y.conjugate(m).mul(&self.gamma_inv[1], m))
+    }
+
+    /// Maps a point of the curve into G1 multiplying it by `1 - u`.
+    pub fn clear_cofactor_g1(&self, p: &Point<N>) -> Point<N> {
+        let one = Bigi::<N>::from(1);
+        if self.u_is_negative {
+            self.schema.curve.mul(p, &(self.u + &one))
+        } else {
+            self.schema.curve.inv(&self.schema.curve.mul(p, &(self.u - &one)))
+        }
+    }
+
+    /// Maps a point of the twist into G2 by the method of Budroni and
+    /// Pintore: `(u^2 - u - 1) Q + (u - 1) psi(Q) + psi^2(2 Q)`, that is
+    /// the multiplication by `h_eff` from RFC 9380.
+    pub fn clear_cofactor_g2(&self, q: &Point2<N>) -> Point2<N> {
+        let one = Bigi::<N>::from(1);
+        let uu = self.u * &self.u;
+        let psi = self.frobenius(q);
+        let (t1, t2) = if self.u_is_negative {
+            (self.twist.mul(q, &(uu + &self.u - &one)),
+             self.twist.inv(&self.twist.mul(&psi, &(self.u + &one))))
+        } else {
+            (self.twist.mul(q, &(uu - &self.u - &one)),
+             self.twist.mul(&psi, &(self.u - &one)))
+        };
+        let t3 = self.frobenius(&self.frobenius(&self.twist.double(q)));
+        self.twist.add(&self.twist.add(&t1, &t2), &t3)
+    }
+
+    /// Multiplies the point of the twist by `u`.
+    fn mul_u(&self, q: &Point2<N>) -> Point2<N> {
+        let uq = self.twist.mul(q, &self.u);
+        if self.u_is_negative { self.twist.inv(&uq) } else { uq }
+    }
+
+    /// Evaluates at `p` the line through `t` and `q` on the curve untwisted
+    /// by `(x, y) -> (x / w^2, y / w^3)` multiplied by `w^3` and returns it
+    /// with `t + q`.
+    fn line(&self, t: &Point2<N>, q: &Point2<N>,
+            p: &Point<N>) -> (Fp12<N>, Point2<N>) {
+        let m = &self.schema.curve.m;
+        match slope(t, q, m) {
+            Some(alpha) => {
+                // l = (alpha xT - yT) - alpha xP w^2 + yP w^3
+                let l = Fp12::new(
+                    Fp6::new(
+                        alpha.mul(&t.x, m).sub(&t.y, m),
+                        alpha.mul_fp(&p.x, m).neg(m),
+                        Fp2::zero()
+                    ),
+                    Fp6::new(Fp2::zero(), Fp2::from_fp(p.y), Fp2::zero())
+                );
+                (l, add_with_slope(t, q, &alpha, m))
+            },
+            // The vertical line is in Fp6 and vanishes after the final
+            // exponentiation
+            None => (Fp12::one(), self.twist.add(t, q))
+        }
+    }
+}
+
+
+impl<const N: usize> PairingTrait<N> for Bls12Pairing<N> {
+    fn schema(&self) -> &Schema<WeierstrassCurve<N>, N> {
+        &self.schema
+    }
+
+    fn twist(&self) -> &TwistCurve<N> {
+        &self.twist
+    }
+
+    fn g2(&self) -> &Point2<N> {
+        &self.g2
+    }
+
+    fn g1_suite(&self) -> Option<&Suite<Sha256, N>> {
+        self.g1_suite.as_ref()
+    }
+
+    /// Calculates the Miller function `f_{u,Q}(P)`.
+    fn miller_loop(&self, p: &Point<N>, q: &Point2<N>) -> Fp12<N> {
+        let m = &self.schema.curve.m;
+        if p.is_zero || q.is_zero {
+            return Fp12::one();
+        }
+
+        let mut f = Fp12::<N>::one();
+        let mut t = *q;
+        for i in (0..(self.u.bit_length() - 1)).rev() {
+            let (l, t2) = self.line(&t, &t, p);
+            f = f.square(m).mul(&l, m);
+            t = t2;
+            if self.u.get_bit(i) {
+                let (l, tq) = self.line(&t, q, p);
+                f = f.mul(&l, m);
+                t = tq;
+            }
+        }
+
+        if self.u_is_negative {
+            f = f.conjugate(m);
+        }
+        f
+    }
+
+    /// Raises `f` to the power `(p^12 - 1) / r`.
+    fn final_exponentiation(&self, f: &Fp12<N>) -> Fp12<N> {
+        let m = &self.schema.curve.m;
+        let frob = |x: &Fp12<N>| x.frobenius(&self.gamma, m);
+
+        // The easy part: f^((p^6 - 1)(p^2 + 1))
+        let f = f.conjugate(m).mul(&f.inv(m), m);
+        let f = frob(&frob(&f)).mul(&f, m);
+
+        // The hard part: (p^4 - p^2 + 1) / r = l0 + l1 p + l2 p^2 + l3 p^3,
+        // l3 = (u - 1)^2 / 3, l2 = l3 u, l1 = l2 u - l3, l0 = l1 u + 1
+        let pow_u = |x: &Fp12<N>| {
+            let y = x.pow(&self.u, m);
+            if self.u_is_negative { y.conjugate(m) } else { y }
+        };
+        let one = Bigi::<N>::from(1);
+        let u_minus_1 = if self.u_is_negative {
+            self.u + &one
+        } else {
+            self.u - &one
+        };
+
+        let a3 = f.pow(&(u_minus_1 * &u_minus_1 / &Bigi::<N>::from(3)), m);
+        let a2 = pow_u(&a3);
+        let a1 = pow_u(&a2).mul(&a3.conjugate(m), m);
+        let a0 = pow_u(&a1).mul(&f, m);
+
+        a0.mul(&frob(&a1), m)
+            .mul(&frob(&frob(&a2)), m)
+            .mul(&frob(&frob(&frob(&a3))), m)
+    }
+
+    /// The check `psi(Q) = u Q` replaces the multiplication by `r`.
+    fn is_in_g2(&self, q: &Point2<N>) -> bool {
+        self.twist.check(q) && self.frobenius(q) == self.mul_u(q)
+    }
+}
+
+
+/// Returns the optimal Ate pairing on Fp254BNb (`u = -(2^62 + 2^55 + 1)`).
+/// The generator of G2 is the multiple by the cofactor `2 p - r` of the
+/// point of the twist with `x = 4 + i`.
+pub fn load_fp254bnb_pairing() -> BnPairing<4> {
+    let schema = load_fp254bnb();
+    let m = schema.curve.m;
+
+    // b / xi = 2 / (1 + i) = 1 - i
+    let twist = TwistCurve {
+        a: Fp2::zero(),
+        b: Fp2::new(Bigi::<4>::from(1), m - &Bigi::<4>::from(1)),
+        m
+    };
+    let g2 = Point2::new(
+        Fp2::new(
+            Bigi::<4>::from_hex("0x0709C6776299080BA18B8F699E70E6EA3C281D9A853A5C75E1938B07D677F1DE"),
+            Bigi::<4>::from_hex("0x2420E103B8DF886DE081595F9795B931998398A2D278BAD5FB6A35BF403535FB")
+        ),
+        Fp2::new(
+            Bigi::<4>::from_hex("0x1992944021F29B925198DB9E159644B699A2C693CE31CD98D6030FF1ED60BC86"),
+            Bigi::<4>::from_hex("0x1C8ACCE0F670E50C48C233032BBA28DE24EE0057AF696853299833807FE32FB7")
+        )
+    );
+
+    BnPairing {
+        schema,
+        twist,
+        g2,
+        u: Bigi::<4>::from_hex("0x4080000000000001"),
+        u_is_negative: true,
+        gamma: frobenius_coeffs(&m)
+    }
+}
+
+
+/// Returns the optimal Ate pairing on BLS12-381 (`u = -0xD201000000010000`)
+/// with the generator of G2 from the Zcash specification and
+/// BLS12381G1_XMD:SHA-256_SSWU_ suite to hash to G1.
+pub fn load_bls12_381_pairing() -> Bls12Pairing<6> {
+    let schema = load_bls12_381();
+    let m = schema.curve.m;
+    let gamma = frobenius_coeffs(&m);
+
+    // b xi = 4 (1 + i)
+    let twist = TwistCurve {
+        a: Fp2::zero(),
+        b: Fp2::new(Bigi::<6>::from(4), Bigi::<6>::from(4)),
+        m
+    };
+    let g2 = Point2::new(
+        Fp2::new(
+            Bigi::<6>::from_hex("0x24AA2B2F08F0A91260805272DC51051C6E47AD4FA403B02B4510B647AE3D1770BAC0326A805BBEFD48056C8C121BDB8"),
+            Bigi::<6>::from_hex("0x13E02B6052719F607DACD3A088274F65596BD0D09920B61AB5DA61BBDC7F5049334CF11213945D57E5AC7D055D042B7E")
+        ),
+        Fp2::new(
+            Bigi::<6>::from_hex("0xCE5D527727D6E118CC9CDC6DA2E351AADFD9BAA8CBDD3A76D429A695160D12C923AC9CC3BACA289E193548608B82801"),
+            Bigi::<6>::from_hex("0x606C4A02EA734CC32ACD2B02BC28B99CB3E287E85A763AF267492AB572E99AB3F370D275CEC1DA1AAA9075FF05F79BE")
+        )
+    );
+
+    Bls12Pairing {
+        schema,
+        twist,
+        g2,
+        u: Bigi::<6>::from_hex("0xD201000000010000"),
+        u_is_negative: true,
+        g1_suite: Some(load_bls12_381_g1_suite()),
+        gamma,
+        gamma_inv: [gamma[2].inv(&m), gamma[3].inv(&m)]
+    }
+}
+
+
+/// Gets the slope of the line through `p` and `q` (the tangent if they
+/// are equal) on a twist with `A = 0`, `None` if the line is vertical or
+/// a point is zero.
+fn slope<const N: usize>(p: &Point2<N>, q: &Point2<N>,
+                         m: &Bigi<N>) -> Option<Fp2<N>> {
+    if p.is_zero || q.is_zero {
+        return None;
+    }
+    if p.x == q.x {
+        if p.y != q.y || p.y.is_zero() {
+            return None;
+        }
+        // alpha = 3 x^2 / (2 y)
+        let x2 = p.x.square(m);
+        Some(x2.add(&x2, m).add(&x2, m).mul(&p.y.add(&p.y, m).inv(m), m))
+    } else {
+        // alpha = (Py - Qy) / (Px - Qx)
+        Some(p.y.sub(&q.y, m).mul(&p.x.sub(&q.x, m).inv(m), m))
+    }
+}
+
+
+fn add_with_slope<const N: usize>(p: &Point2<N>, q: &Point2<N>,
+                                  alpha: &Fp2<N>, m: &Bigi<N>) -> Point2<N> {
+    let x = alpha.square(m).sub(&p.x, m).sub(&q.x, m);
+    let y = alpha.mul(&p.x.sub(&x, m), m).sub(&p.y, m);
+    Point2::new(x, y)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use bigi::prime::mul_mod;
+
+    #[test]
+    fn test_twist() {
+        let pairing = load_fp254bnb_pairing();
+        let g2 = pairing.g2;
+        assert!(pairing.twist.check(&g2));
+        assert!(pairing.get_point2(&pairing.schema.order).is_zero);
+
+        let a = pairing.get_point2(&Bigi::<4>::from(5));
+        let b = pairing.get_point2(&Bigi::<4>::from(7));
+        assert!(pairing.twist.check(&a));
+        assert_eq!(pairing.twist.add(&a, &b),
+                   pairing.get_point2(&Bigi::<4>::from(12)));
+        assert!(pairing.twist.add(&a, &pairing.twist.inv(&a)).is_zero);
+
+        // The Frobenius endomorphism is the multiplication by p on G2
+        let p = pairing.schema.curve.m % &pairing.schema.order;
+        assert_eq!(pairing.frobenius(&a), pairing.twist.mul(&a, &p));
+    }
+
+    #[test]
+    fn test_pairing() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_fp254bnb_pairing();
+        let m = &pairing.schema.curve.m;
+        let r = &pairing.schema.order;
+
+        let g1 = pairing.schema.generator;
+        let g2 = pairing.g2;
+        let e = pairing.pairing(&g1, &g2);
+
+        // Non-degenerate and of order r
+        assert!(!e.is_one());
+        assert!(e.pow(r, m).is_one());
+
+        // Bilinearity
+        let a = Bigi::<4>::gen_random(&mut rng, 256, false) % r;
+        let b = Bigi::<4>::gen_random(&mut rng, 256, false) % r;
+        let pa = pairing.schema.get_point(&a);
+        let qb = pairing.get_point2(&b);
+        assert_eq!(pairing.pairing(&pa, &qb), e.pow(&mul_mod(&a, &b, r), m));
+        assert_eq!(pairing.pairing(&pa, &g2),
+                   pairing.pairing(&g1, &pairing.get_point2(&a)));
+
+        // e(P1 + P2, Q) = e(P1, Q) e(P2, Q)
+        let p2 = pairing.schema.get_point(&b);
+        assert_eq!(
+            pairing.pairing(&pairing.schema.curve.add(&pa, &p2), &g2),
+            pairing.pairing(&pa, &g2).mul(&pairing.pairing(&p2, &g2), m)
+        );
+
+        // Zero points give 1
+        assert!(pairing.pairing(&pairing.schema.curve.zero(), &g2).is_one());
+        assert!(pairing.pairing(&g1, &pairing.twist.zero()).is_one());
+    }
+
+    #[test]
+    fn test_bls12_381_subgroups() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_bls12_381_pairing();
+        let m = pairing.schema.curve.m;
+        let r = pairing.schema.order;
+        let g2 = pairing.g2;
+        assert!(pairing.is_in_g1(&pairing.schema.generator));
+        assert!(pairing.is_in_g2(&g2));
+        assert!(pairing.get_point2(&r).is_zero);
+
+        // The endomorphism is the multiplication by p on G2
+        let a = pairing.get_point2(&Bigi::<6>::from(5));
+        assert!(pairing.is_in_g2(&a));
+        assert_eq!(pairing.frobenius(&a), pairing.twist.mul(&a, &(m % &r)));
+
+        // Random points are out of the subgroups until the cofactor is
+        // cleared
+        let p = loop {
+            let x = Bigi::<6>::gen_random(&mut rng, 381, false) % &m;
+            if let Ok((y, _)) = pairing.schema.curve.find_y(&x) {
+                break Point::new(x, y);
+            }
+        };
+        assert!(!pairing.is_in_g1(&p));
+        assert!(pairing.is_in_g1(&pairing.clear_cofactor_g1(&p)));
+
+        let q = loop {
+            let x = Fp2::new(Bigi::<6>::gen_random(&mut rng, 381, false) % &m,
+                             Bigi::<6>::gen_random(&mut rng, 381, false) % &m);
+            if let Ok((y, _)) = pairing.twist.find_y(&x) {
+                break Point2::new(x, y);
+            }
+        };
+        assert!(pairing.twist.check(&q));
+        assert!(!pairing.is_in_g2(&q));
+        let q = pairing.clear_cofactor_g2(&q);
+        assert!(pairing.is_in_g2(&q));
+        assert!(pairing.twist.mul(&q, &r).is_zero);
+    }
+
+    #[test]
+    fn test_bls12_381_pairing() {
+        let mut rng = rand::thread_rng();
+        let pairing = load_bls12_381_pairing();
+        let m = &pairing.schema.curve.m;
+        let r = &pairing.schema.order;
+
+        let g1 = pairing.schema.generator;
+        let g2 = pairing.g2;
+        let e = pairing.pairing(&g1, &g2);
+
+        // Non-degenerate and of order r
+        assert!(!e.is_one());
+        assert!(e.pow(r, m).is_one());
+
+        // Bilinearity
+        let a = Bigi::<6>::gen_random(&mut rng, 256, false) % r;
+        let b = Bigi::<6>::gen_random(&mut rng, 256, false) % r;
+        let pa = pairing.schema.get_point(&a);
+        let qb = pairing.get_point2(&b);
+        assert_eq!(pairing.pairing(&pa, &qb), e.pow(&mul_mod(&a, &b, r), m));
+        assert_eq!(pairing.pairing(&pa, &g2),
+                   pairing.pairing(&g1, &pairing.get_point2(&a)));
+
+        // Zero points give 1
+        assert!(pairing.pairing(&pairing.schema.curve.zero(), &g2).is_one());
+        assert!(pairing.pairing(&g1, &pairing.twist.zero()).is_one());
+    }
+
+    #[bench]
+    fn bench_pairing(bencher: &mut Bencher) {
+        let pairing = load_fp254bnb_pairing();
+        let g1 = pairing.schema.generator;
+        let g2 = pairing.g2;
+        bencher.iter(|| pairing.pairing(&g1, &g2));
+    }
+
+    #[bench]
+    fn bench_bls12_381_pairing(bencher: &mut Bencher) {
+        let pairing = load_bls12_381_pairing();
+        let g1 = pairing.schema.generator;
+        let g2 = pairing.g2;
+        bencher.iter(|| pairing.pairing(&g1, &g2));
+    }
+}
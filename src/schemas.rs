@@ -27,13 +27,171 @@ impl<T, const N: usize> Schema<T, N> where T: CurveTrait<N> {
         self.curve.mul(&self.generator, k)
     }
 
-    /// Gets a random point on the curve.
+    /// Gets a random point on the curve. The private key is sampled
+    /// uniformly from `[1, order)` with as many bits as the order has, that
+    /// may differ from `bits` of the field (e.g. for secp160k1).
     pub fn generate_pair<R: Rng + ?Sized>(&self, rng: &mut R
                 ) -> (Bigi<N>, Point<N>) {
-        let x = Bigi::<N>::gen_random(rng, self.bits, false) % &self.order;
+        let bits = self.order.bit_length();
+        let x = loop {
+            let x = Bigi::<N>::gen_random(rng, bits, false);
+            if !x.is_zero() && x < self.order {
+                break x;
+            }
+        };
         let h = self.get_point(&x);
         (x, h)
     }
+
+    /// Checks a received point: it must lie on the curve, differ from
+    /// the zero point and belong to the subgroup of the generator, so
+    /// `order * P = O`. The last check is skipped for the cofactor `1`.
+    pub fn validate_point(&self, p: &Point<N>) -> Result<(), &'static str> {
+        let zero = self.curve.zero();
+        if *p == zero {
+            return Err("point is the zero point");
+        }
+        if !self.curve.check(p) {
+            return Err("point is not on the curve");
+        }
+        if self.cofactor != Bigi::<N>::from(1) &&
+                self.curve.mul(p, &self.order) != zero {
+            return Err("point is not in the subgroup");
+        }
+        Ok(())
+    }
+
+    /// Multiplies the point by the cofactor, that maps it to the subgroup.
+    /// A cofactor that is a power of two (like `8` for Curve25519) takes
+    /// several doublings only.
+    pub fn clear_cofactor(&self, p: &Point<N>) -> Point<N> {
+        let bits = self.cofactor.bit_length();
+        if bits > 0 && (Bigi::<N>::from(1) << (bits - 1)) == self.cofactor {
+            (1..bits).fold(*p, |q, _| self.curve.double(&q))
+        } else {
+            self.curve.mul(p, &self.cofactor)
+        }
+    }
+}
+
+
+/// Returns SECP160K1 schema.
+pub fn load_secp160k1() -> Schema<WeierstrassCurve<3>, 3> {
+    Schema {
+        bits: 160,
+        title: "secp160k1",
+        curve: WeierstrassCurve::<3> {
+            a: Bigi::<3>::from_hex("0x0"),
+            b: Bigi::<3>::from_hex("0x7"),
+            m: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFAC73")
+        },
+        order: Bigi::<3>::from_hex("0x100000000000000000001B8FA16DFAB9ACA16B6B3"),
+        cofactor: Bigi::<3>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<3>::from_hex("0x3B4C382CE37AA192A4019E763036F4F5DD4D7EBB"),
+            Bigi::<3>::from_hex("0x938CF935318FDCED6BC28286531733C3F03C4FEE")
+        )
+    }
+}
+
+
+/// Returns SECP160R1 schema.
+pub fn load_secp160r1() -> Schema<WeierstrassCurve<3>, 3> {
+    Schema {
+        bits: 160,
+        title: "secp160r1",
+        curve: WeierstrassCurve::<3> {
+            a: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7FFFFFFC"),
+            b: Bigi::<3>::from_hex("0x1C97BEFC54BD7A8B65ACF89F81D4D4ADC565FA45"),
+            m: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7FFFFFFF")
+        },
+        order: Bigi::<3>::from_hex("0x100000000000000000001F4C8F927AED3CA752257"),
+        cofactor: Bigi::<3>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<3>::from_hex("0x4A96B5688EF573284664698968C38BB913CBFC82"),
+            Bigi::<3>::from_hex("0x23A628553168947D59DCC912042351377AC5FB32")
+        )
+    }
+}
+
+
+/// Returns SECP192K1 schema.
+pub fn load_secp192k1() -> Schema<WeierstrassCurve<3>, 3> {
+    Schema {
+        bits: 192,
+        title: "secp192k1",
+        curve: WeierstrassCurve::<3> {
+            a: Bigi::<3>::from_hex("0x0"),
+            b: Bigi::<3>::from_hex("0x3"),
+            m: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFEE37")
+        },
+        order: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFE26F2FC170F69466A74DEFD8D"),
+        cofactor: Bigi::<3>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<3>::from_hex("0xDB4FF10EC057E9AE26B07D0280B7F4341DA5D1B1EAE06C7D"),
+            Bigi::<3>::from_hex("0x9B2F2F6D9C5628A7844163D015BE86344082AA88D95E2F9D")
+        )
+    }
+}
+
+
+/// Returns SECP192R1 schema (NIST P-192).
+pub fn load_secp192r1() -> Schema<WeierstrassCurve<3>, 3> {
+    Schema {
+        bits: 192,
+        title: "secp192r1",
+        curve: WeierstrassCurve::<3> {
+            a: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFC"),
+            b: Bigi::<3>::from_hex("0x64210519E59C80E70FA7E9AB72243049FEB8DEECC146B9B1"),
+            m: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<3>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFF99DEF836146BC9B1B4D22831"),
+        cofactor: Bigi::<3>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<3>::from_hex("0x188DA80EB03090F67CBF20EB43A18800F4FF0AFD82FF1012"),
+            Bigi::<3>::from_hex("0x07192B95FFC8DA78631011ED6B24CDD573F977A11E794811")
+        )
+    }
+}
+
+
+/// Returns SECP224K1 schema.
+pub fn load_secp224k1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 224,
+        title: "secp224k1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0x0"),
+            b: Bigi::<4>::from_hex("0x5"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFE56D")
+        },
+        order: Bigi::<4>::from_hex("0x10000000000000000000000000001DCE8D2EC6184CAF0A971769FB1F7"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0xA1455B334DF099DF30FC28A169A467E9E47075A90F7E650EB6B7A45C"),
+            Bigi::<4>::from_hex("0x7E089FED7FBA344282CAFBD6F7E319F7C0B0BD59E2CA4BDB556D61A5")
+        )
+    }
+}
+
+
+/// Returns SECP224R1 schema (NIST P-224).
+pub fn load_secp224r1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 224,
+        title: "secp224r1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFE"),
+            b: Bigi::<4>::from_hex("0xB4050A850C04B3ABF54132565044B0B7D7BFD8BA270B39432355FFB4"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF000000000000000000000001")
+        },
+        order: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFF16A2E0B8F03E13DD29455C5C2A3D"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0xB70E0CBD6BB4BF7F321390B94A03C1D356C21122343280D6115C1D21"),
+            Bigi::<4>::from_hex("0xBD376388B5F723FB4C22DFE6CD4375A05A07476444D5819985007E34")
+        )
+    }
 }
 
 
@@ -57,6 +215,149 @@ pub fn load_secp256k1() -> Schema<WeierstrassCurve<4>, 4> {
 }
 
 
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-256-paramSetA
+/// in the twisted Edwards form with `e = 1` (RFC 7836).
+pub fn load_gost256a() -> Schema<EdwardsCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "tc26-gost-3410-2012-256-paramSetA",
+        curve: EdwardsCurve::<4> {
+            d: Bigi::<4>::from_hex("0x0605F6B7C183FA81578BC39CFAD518132B9DF62897009AF7E522C32D6DC7BFFB"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFD97")
+        },
+        order: Bigi::<4>::from_hex("0x400000000000000000000000000000000FD8CDDFC87B6635C115AF556C360C67"),
+        cofactor: Bigi::<4>::from_hex("0x4"),
+        generator: point!(
+            Bigi::<4>::from_hex("0xD"),
+            Bigi::<4>::from_hex("0x60CA1E32AA475B348488C38FAB07649CE7EF8DBE87F22E81F92B2592DBA300E7")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-256-paramSetB
+/// (CryptoPro-A).
+pub fn load_gost256b() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "tc26-gost-3410-2012-256-paramSetB",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFD94"),
+            b: Bigi::<4>::from_hex("0xA6"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFD97")
+        },
+        order: Bigi::<4>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF6C611070995AD10045841B09B761B893"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x1"),
+            Bigi::<4>::from_hex("0x8D91E471E0989CDA27DF505A453F2B7635294F2DDF23E3B122ACC99C9E9F1E14")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-256-paramSetC
+/// (CryptoPro-B).
+pub fn load_gost256c() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "tc26-gost-3410-2012-256-paramSetC",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0x8000000000000000000000000000000000000000000000000000000000000C96"),
+            b: Bigi::<4>::from_hex("0x3E1AF419A269A5F866A7D3C25C3DF80AE979259373FF2B182F49D4CE7E1BBC8B"),
+            m: Bigi::<4>::from_hex("0x8000000000000000000000000000000000000000000000000000000000000C99")
+        },
+        order: Bigi::<4>::from_hex("0x800000000000000000000000000000015F700CFFF1A624E5E497161BCC8A198F"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x1"),
+            Bigi::<4>::from_hex("0x3FA8124359F96680B83D1C3EB2C070E5C545C9858D03ECFB744BF8D717717EFC")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-256-paramSetD
+/// (CryptoPro-C).
+pub fn load_gost256d() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "tc26-gost-3410-2012-256-paramSetD",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0x9B9F605F5A858107AB1EC85E6B41C8AACF846E86789051D37998F7B9022D7598"),
+            b: Bigi::<4>::from_hex("0x805A"),
+            m: Bigi::<4>::from_hex("0x9B9F605F5A858107AB1EC85E6B41C8AACF846E86789051D37998F7B9022D759B")
+        },
+        order: Bigi::<4>::from_hex("0x9B9F605F5A858107AB1EC85E6B41C8AA582CA3511EDDFB74F02F3A6598980BB9"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x0"),
+            Bigi::<4>::from_hex("0x41ECE55743711A8C3CBF3783CD08C0EE4D4DC440D4641A8F366E550DFDB3BB67")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-512-paramSetA.
+pub fn load_gost512a() -> Schema<WeierstrassCurve<8>, 8> {
+    Schema {
+        bits: 512,
+        title: "tc26-gost-3410-2012-512-paramSetA",
+        curve: WeierstrassCurve::<8> {
+            a: Bigi::<8>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFDC4"),
+            b: Bigi::<8>::from_hex("0xE8C2505DEDFC86DDC1BD0B2B6667F1DA34B82574761CB0E879BD081CFD0B6265EE3CB090F30D27614CB4574010DA90DD862EF9D4EBEE4761503190785A71C760"),
+            m: Bigi::<8>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFDC7")
+        },
+        order: Bigi::<8>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF27E69532F48D89116FF22B8D4E0560609B4B38ABFAD2B85DCACDB1411F10B275"),
+        cofactor: Bigi::<8>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<8>::from_hex("0x3"),
+            Bigi::<8>::from_hex("0x7503CFE87A836AE3A61B8816E25450E6CE5E1C93ACF1ABC1778064FDCBEFA921DF1626BE4FD036E93D75E6A50E3A41E98028FE5FC235F5B889A589CB5215F2A4")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-512-paramSetB.
+pub fn load_gost512b() -> Schema<WeierstrassCurve<8>, 8> {
+    Schema {
+        bits: 512,
+        title: "tc26-gost-3410-2012-512-paramSetB",
+        curve: WeierstrassCurve::<8> {
+            a: Bigi::<8>::from_hex("0x8000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006C"),
+            b: Bigi::<8>::from_hex("0x687D1B459DC841457E3E06CF6F5E2517B97C7D614AF138BCBF85DC806C4B289F3E965D2DB1416D217F8B276FAD1AB69C50F78BEE1FA3106EFB8CCBC7C5140116"),
+            m: Bigi::<8>::from_hex("0x8000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000006F")
+        },
+        order: Bigi::<8>::from_hex("0x800000000000000000000000000000000000000000000000000000000000000149A1EC142565A545ACFDB77BD9D40CFA8B996712101BEA0EC6346C54374F25BD"),
+        cofactor: Bigi::<8>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<8>::from_hex("0x2"),
+            Bigi::<8>::from_hex("0x1A8F7EDA389B094C2C071E3647A8940F3C123B697578C213BE6DD9E6C8EC7335DCB228FD1EDF4A39152CBCAAF8C0398828041055F94CEEEC7E21340780FE41BD")
+        )
+    }
+}
+
+
+/// Returns GOST R 34.10-2012 schema tc26-gost-3410-2012-512-paramSetC
+/// in the twisted Edwards form with `e = 1` (RFC 7836).
+pub fn load_gost512c() -> Schema<EdwardsCurve<8>, 8> {
+    Schema {
+        bits: 512,
+        title: "tc26-gost-3410-2012-512-paramSetC",
+        curve: EdwardsCurve::<8> {
+            d: Bigi::<8>::from_hex("0x9E4F5D8C017D8D9F13A5CF3CDF5BFE4DAB402D54198E31EBDE28A0621050439CA6B39E0A515C06B304E2CE43E79E369E91A0CFC2BC2A22B4CA302DBB33EE7550"),
+            m: Bigi::<8>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFDC7")
+        },
+        order: Bigi::<8>::from_hex("0x3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC98CDBA46506AB004C33A9FF5147502CC8EDA9E7A769A12694623CEF47F023ED"),
+        cofactor: Bigi::<8>::from_hex("0x4"),
+        generator: point!(
+            Bigi::<8>::from_hex("0x12"),
+            Bigi::<8>::from_hex("0x469AF79D1FB1F5E16B99592B77A01E2A0FDFB0D01794368D9A56117F7B38669522DD4B650CF789EEBF068C5D139732F0905622C04B2BAAE7600303EE73001A3D")
+        )
+    }
+}
+
+
 /// Returns FP254BNB schema.
 pub fn load_fp254bnb() -> Schema<WeierstrassCurve<4>, 4> {
     Schema {
@@ -77,6 +378,227 @@ pub fn load_fp254bnb() -> Schema<WeierstrassCurve<4>, 4> {
 }
 
 
+/// Returns BLS12-381 schema, that is the group G1 of the pairing (see
+/// `pairing::load_bls12_381_pairing` for G2).
+pub fn load_bls12_381() -> Schema<WeierstrassCurve<6>, 6> {
+    Schema {
+        bits: 381,
+        title: "bls12_381",
+        curve: WeierstrassCurve::<6> {
+            a: Bigi::<6>::from_hex("0x0"),
+            b: Bigi::<6>::from_hex("0x4"),
+            m: Bigi::<6>::from_hex("0x1A0111EA397FE69A4B1BA7B6434BACD764774B84F38512BF6730D2A0F6B0F6241EABFFFEB153FFFFB9FEFFFFFFFFAAAB")
+        },
+        order: Bigi::<6>::from_hex("0x73EDA753299D7D483339D80809A1D80553BDA402FFFE5BFEFFFFFFFF00000001"),
+        cofactor: Bigi::<6>::from_hex("0x396C8C005555E1568C00AAAB0000AAAB"),
+        generator: point!(
+            Bigi::<6>::from_hex("0x17F1D3A73197D7942695638C4FA9AC0FC3688C4F9774B905A14E3A3F171BAC586C55E83FF97A1AEFFB3AF00ADB22C6BB"),
+            Bigi::<6>::from_hex("0x8B3F481E3AAA0F1A09E30ED741D8AE4FCF5E095D5D00AF600DB18CB2C04B3EDD03CC744A2888AE40CAA232946C5E7E1")
+        )
+    }
+}
+
+
+/// Returns SECP256R1 schema (NIST P-256).
+pub fn load_secp256r1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "secp256r1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0xFFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFC"),
+            b: Bigi::<4>::from_hex("0x5AC635D8AA3A93E7B3EBBD55769886BC651D06B0CC53B0F63BCE3C3E27D2604B"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFF00000001000000000000000000000000FFFFFFFFFFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<4>::from_hex("0xFFFFFFFF00000000FFFFFFFFFFFFFFFFBCE6FAADA7179E84F3B9CAC2FC632551"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x6B17D1F2E12C4247F8BCE6E563A440F277037D812DEB33A0F4A13945D898C296"),
+            Bigi::<4>::from_hex("0x4FE342E2FE1A7F9B8EE7EB4A7C0F9E162BCE33576B315ECECBB6406837BF51F5")
+        )
+    }
+}
+
+
+/// Returns SECP384R1 schema (NIST P-384).
+pub fn load_secp384r1() -> Schema<WeierstrassCurve<6>, 6> {
+    Schema {
+        bits: 384,
+        title: "secp384r1",
+        curve: WeierstrassCurve::<6> {
+            a: Bigi::<6>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFC"),
+            b: Bigi::<6>::from_hex("0xB3312FA7E23EE7E4988E056BE3F82D19181D9C6EFE8141120314088F5013875AC656398D8A2ED19D2A85C8EDD3EC2AEF"),
+            m: Bigi::<6>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFF0000000000000000FFFFFFFF")
+        },
+        order: Bigi::<6>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC7634D81F4372DDF581A0DB248B0A77AECEC196ACCC52973"),
+        cofactor: Bigi::<6>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<6>::from_hex("0xAA87CA22BE8B05378EB1C71EF320AD746E1D3B628BA79B9859F741E082542A385502F25DBF55296C3A545E3872760AB7"),
+            Bigi::<6>::from_hex("0x3617DE4A96262C6F5D9E98BF9292DC29F8F41DBD289A147CE9DA3113B5F0B8C00A60B1CE1D7E819D7A431D7C90EA0E5F")
+        )
+    }
+}
+
+
+/// Returns SECP521R1 schema (NIST P-521).
+pub fn load_secp521r1() -> Schema<WeierstrassCurve<9>, 9> {
+    Schema {
+        bits: 521,
+        title: "secp521r1",
+        curve: WeierstrassCurve::<9> {
+            a: Bigi::<9>::from_hex("0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFC"),
+            b: Bigi::<9>::from_hex("0x51953EB9618E1C9A1F929A21A0B68540EEA2DA725B99B315F3B8B489918EF109E156193951EC7E937B1652C0BD3BB1BF073573DF883D2C34F1EF451FD46B503F00"),
+            m: Bigi::<9>::from_hex("0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<9>::from_hex("0x1FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFA51868783BF2F966B7FCC0148F709A5D03BB5C9B8899C47AEBB6FB71E91386409"),
+        cofactor: Bigi::<9>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<9>::from_hex("0xC6858E06B70404E9CD9E3ECB662395B4429C648139053FB521F828AF606B4D3DBAA14B5E77EFE75928FE1DC127A2FFA8DE3348B3C1856A429BF97E7E31C2E5BD66"),
+            Bigi::<9>::from_hex("0x11839296A789A3BC0045C8A5FB42C7D1BD998F54449579B446817AFBD17273E662C97EE72995EF42640C550B9013FAD0761353C7086A272C24088BE94769FD16650")
+        )
+    }
+}
+
+
+/// Returns SM2 schema (sm2p256v1 of GB/T 32918.5).
+pub fn load_sm2p256v1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "sm2p256v1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0xFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00000000FFFFFFFFFFFFFFFC"),
+            b: Bigi::<4>::from_hex("0x28E9FA9E9D9F5E344D5A9E4BCF6509A7F39789F515AB8F92DDBCBD414D940E93"),
+            m: Bigi::<4>::from_hex("0xFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF00000000FFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<4>::from_hex("0xFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFF7203DF6B21C6052B53BBF40939D54123"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x32C4AE2C1F1981195F9904466A39C9948FE30BBFF2660BE1715A4589334C74C7"),
+            Bigi::<4>::from_hex("0xBC3736A2F4F6779C59BDCEE36B692153D0A9877CC62A474002DF32E52139F0A0")
+        )
+    }
+}
+
+
+/// Returns brainpoolP256r1 schema (RFC 5639).
+pub fn load_brainpoolp256r1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "brainpoolP256r1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0x7D5A0975FC2C3057EEF67530417AFFE7FB8055C126DC5C6CE94A4B44F330B5D9"),
+            b: Bigi::<4>::from_hex("0x26DC5C6CE94A4B44F330B5D9BBD77CBF958416295CF7E1CE6BCCDC18FF8C07B6"),
+            m: Bigi::<4>::from_hex("0xA9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5377")
+        },
+        order: Bigi::<4>::from_hex("0xA9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0x8BD2AEB9CB7E57CB2C4B482FFC81B7AFB9DE27E1E3BD23C23A4453BD9ACE3262"),
+            Bigi::<4>::from_hex("0x547EF835C3DAC4FD97F8461A14611DC9C27745132DED8E545C1D54C72F046997")
+        )
+    }
+}
+
+
+/// Returns brainpoolP256t1 schema (RFC 5639).
+pub fn load_brainpoolp256t1() -> Schema<WeierstrassCurve<4>, 4> {
+    Schema {
+        bits: 256,
+        title: "brainpoolP256t1",
+        curve: WeierstrassCurve::<4> {
+            a: Bigi::<4>::from_hex("0xA9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5374"),
+            b: Bigi::<4>::from_hex("0x662C61C430D84EA4FE66A7733D0B76B7BF93EBC4AF2F49256AE58101FEE92B04"),
+            m: Bigi::<4>::from_hex("0xA9FB57DBA1EEA9BC3E660A909D838D726E3BF623D52620282013481D1F6E5377")
+        },
+        order: Bigi::<4>::from_hex("0xA9FB57DBA1EEA9BC3E660A909D838D718C397AA3B561A6F7901E0E82974856A7"),
+        cofactor: Bigi::<4>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<4>::from_hex("0xA3E8EB3CC1CFE7B7732213B23A656149AFA142C47AAFBC2B79A191562E1305F4"),
+            Bigi::<4>::from_hex("0x2D996C823439C56D7F7B22E14644417E69BCB6DE39D027001DABE8F35B25C9BE")
+        )
+    }
+}
+
+
+/// Returns brainpoolP384r1 schema (RFC 5639).
+pub fn load_brainpoolp384r1() -> Schema<WeierstrassCurve<6>, 6> {
+    Schema {
+        bits: 384,
+        title: "brainpoolP384r1",
+        curve: WeierstrassCurve::<6> {
+            a: Bigi::<6>::from_hex("0x7BC382C63D8C150C3C72080ACE05AFA0C2BEA28E4FB22787139165EFBA91F90F8AA5814A503AD4EB04A8C7DD22CE2826"),
+            b: Bigi::<6>::from_hex("0x4A8C7DD22CE28268B39B55416F0447C2FB77DE107DCD2A62E880EA53EEB62D57CB4390295DBC9943AB78696FA504C11"),
+            m: Bigi::<6>::from_hex("0x8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B412B1DA197FB71123ACD3A729901D1A71874700133107EC53")
+        },
+        order: Bigi::<6>::from_hex("0x8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B31F166E6CAC0425A7CF3AB6AF6B7FC3103B883202E9046565"),
+        cofactor: Bigi::<6>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<6>::from_hex("0x1D1C64F068CF45FFA2A63A81B7C13F6B8847A3E77EF14FE3DB7FCAFE0CBD10E8E826E03436D646AAEF87B2E247D4AF1E"),
+            Bigi::<6>::from_hex("0x8ABE1D7520F9C2A45CB1EB8E95CFD55262B70B29FEEC5864E19C054FF99129280E4646217791811142820341263C5315")
+        )
+    }
+}
+
+
+/// Returns brainpoolP384t1 schema (RFC 5639).
+pub fn load_brainpoolp384t1() -> Schema<WeierstrassCurve<6>, 6> {
+    Schema {
+        bits: 384,
+        title: "brainpoolP384t1",
+        curve: WeierstrassCurve::<6> {
+            a: Bigi::<6>::from_hex("0x8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B412B1DA197FB71123ACD3A729901D1A71874700133107EC50"),
+            b: Bigi::<6>::from_hex("0x7F519EADA7BDA81BD826DBA647910F8C4B9346ED8CCDC64E4B1ABD11756DCE1D2074AA263B88805CED70355A33B471EE"),
+            m: Bigi::<6>::from_hex("0x8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B412B1DA197FB71123ACD3A729901D1A71874700133107EC53")
+        },
+        order: Bigi::<6>::from_hex("0x8CB91E82A3386D280F5D6F7E50E641DF152F7109ED5456B31F166E6CAC0425A7CF3AB6AF6B7FC3103B883202E9046565"),
+        cofactor: Bigi::<6>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<6>::from_hex("0x18DE98B02DB9A306F2AFCD7235F72A819B80AB12EBD653172476FECD462AABFFC4FF191B946A5F54D8D0AA2F418808CC"),
+            Bigi::<6>::from_hex("0x25AB056962D30651A114AFD2755AD336747F93475B7A1FCA3B88F2B6A208CCFE469408584DC2B2912675BF5B9E582928")
+        )
+    }
+}
+
+
+/// Returns brainpoolP512r1 schema (RFC 5639).
+pub fn load_brainpoolp512r1() -> Schema<WeierstrassCurve<8>, 8> {
+    Schema {
+        bits: 512,
+        title: "brainpoolP512r1",
+        curve: WeierstrassCurve::<8> {
+            a: Bigi::<8>::from_hex("0x7830A3318B603B89E2327145AC234CC594CBDD8D3DF91610A83441CAEA9863BC2DED5D5AA8253AA10A2EF1C98B9AC8B57F1117A72BF2C7B9E7C1AC4D77FC94CA"),
+            b: Bigi::<8>::from_hex("0x3DF91610A83441CAEA9863BC2DED5D5AA8253AA10A2EF1C98B9AC8B57F1117A72BF2C7B9E7C1AC4D77FC94CADC083E67984050B75EBAE5DD2809BD638016F723"),
+            m: Bigi::<8>::from_hex("0xAADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA703308717D4D9B009BC66842AECDA12AE6A380E62881FF2F2D82C68528AA6056583A48F3")
+        },
+        order: Bigi::<8>::from_hex("0xAADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA70330870553E5C414CA92619418661197FAC10471DB1D381085DDADDB58796829CA90069"),
+        cofactor: Bigi::<8>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<8>::from_hex("0x81AEE4BDD82ED9645A21322E9C4C6A9385ED9F70B5D916C1B43B62EEF4D0098EFF3B1F78E2D0D48D50D1687B93B97D5F7C6D5047406A5E688B352209BCB9F822"),
+            Bigi::<8>::from_hex("0x7DDE385D566332ECC0EABFA9CF7822FDF209F70024A57B1AA000C55B881F8111B2DCDE494A5F485E5BCA4BD88A2763AED1CA2B2FA8F0540678CD1E0F3AD80892")
+        )
+    }
+}
+
+
+/// Returns brainpoolP512t1 schema (RFC 5639).
+pub fn load_brainpoolp512t1() -> Schema<WeierstrassCurve<8>, 8> {
+    Schema {
+        bits: 512,
+        title: "brainpoolP512t1",
+        curve: WeierstrassCurve::<8> {
+            a: Bigi::<8>::from_hex("0xAADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA703308717D4D9B009BC66842AECDA12AE6A380E62881FF2F2D82C68528AA6056583A48F0"),
+            b: Bigi::<8>::from_hex("0x7CBBBCF9441CFAB76E1890E46884EAE321F70C0BCB4981527897504BEC3E36A62BCDFA2304976540F6450085F2DAE145C22553B465763689180EA2571867423E"),
+            m: Bigi::<8>::from_hex("0xAADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA703308717D4D9B009BC66842AECDA12AE6A380E62881FF2F2D82C68528AA6056583A48F3")
+        },
+        order: Bigi::<8>::from_hex("0xAADD9DB8DBE9C48B3FD4E6AE33C9FC07CB308DB3B3C9D20ED6639CCA70330870553E5C414CA92619418661197FAC10471DB1D381085DDADDB58796829CA90069"),
+        cofactor: Bigi::<8>::from_hex("0x1"),
+        generator: point!(
+            Bigi::<8>::from_hex("0x640ECE5C12788717B9C1BA06CBC2A6FEBA85842458C56DDE9DB1758D39C0313D82BA51735CDB3EA499AA77A7D6943A64F7A3F25FE26F06B51BAA2696FA9035DA"),
+            Bigi::<8>::from_hex("0x5B534BD595F5AF0FA2C892376C84ACE1BB4E3019B71634C01131159CAE03CEE9D9932184BEEF216BD71DF2DADF86A627306ECFF96DBB8BACE198B61E00F8B332")
+        )
+    }
+}
+
+
 /// Returns Curve25519 schema.
 pub fn load_curve25519() -> Schema<MontgomeryCurve<4>, 4> {
     Schema {
@@ -115,21 +637,226 @@ pub fn load_curve1174() -> Schema<EdwardsCurve<4>, 4> {
 }
 
 
+/// Returns Curve448 schema.
+pub fn load_curve448() -> Schema<MontgomeryCurve<7>, 7> {
+    Schema {
+        bits: 448,
+        title: "curve448",
+        curve: MontgomeryCurve::<7> {
+            a: Bigi::<7>::from_hex("0x262A6"),
+            b: Bigi::<7>::from_hex("0x1"),
+            m: Bigi::<7>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<7>::from_hex("0x3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7CCA23E9C44EDB49AED63690216CC2728DC58F552378C292AB5844F3"),
+        cofactor: Bigi::<7>::from_hex("0x4"),
+        generator: point!(
+            Bigi::<7>::from_hex("0x5"),
+            Bigi::<7>::from_hex("0x7D235D1295F5B1F66C98AB6E58326FCECBAE5D34F55545D060F75DC28DF3F6EDB8027E2346430D211312C4B150677AF76FD7223D457B5B1A")
+        )
+    }
+}
+
+
+/// Returns Ed448-Goldilocks schema.
+pub fn load_ed448() -> Schema<EdwardsCurve<7>, 7> {
+    Schema {
+        bits: 448,
+        title: "ed448",
+        curve: EdwardsCurve::<7> {
+            d: Bigi::<7>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF6756"),
+            m: Bigi::<7>::from_hex("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF")
+        },
+        order: Bigi::<7>::from_hex("0x3FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF7CCA23E9C44EDB49AED63690216CC2728DC58F552378C292AB5844F3"),
+        cofactor: Bigi::<7>::from_hex("0x4"),
+        generator: point!(
+            Bigi::<7>::from_hex("0x4F1970C66BED0DED221D15A622BF36DA9E146570470F1767EA6DE324A3D3A46412AE1AF72AB66511433B80E18B00938E2626A82BC70CC05E"),
+            Bigi::<7>::from_hex("0x693F46716EB6BC248876203756C9C7624BEA73736CA3984087789C1E05A0C2D73AD3FF1CE67C39C4FDBD132C4ED7C8AD9808795BF230FA14")
+        )
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use test::Bencher;
 
+    #[test]
+    fn test_validate_point() {
+        let mut rng = rand::thread_rng();
+
+        let schema = load_secp256k1();
+        let (_, p) = schema.generate_pair(&mut rng);
+        assert_eq!(schema.validate_point(&p), Ok(()));
+        assert_eq!(schema.validate_point(&schema.curve.zero()),
+                   Err("point is the zero point"));
+        assert_eq!(schema.validate_point(&point!(p.x, p.x)),
+                   Err("point is not on the curve"));
+
+        // The points of order 2 on Curve25519 and Curve1174
+        let schema = load_curve25519();
+        let (_, p) = schema.generate_pair(&mut rng);
+        let low_order = point!(Bigi::<4>::from(0), Bigi::<4>::from(0));
+        assert_eq!(schema.validate_point(&p), Ok(()));
+        assert_eq!(schema.validate_point(&low_order),
+                   Err("point is not in the subgroup"));
+        assert!(schema.validate_point(&schema.curve.add(&p, &low_order))
+                .is_err());
+
+        let schema = load_curve1174();
+        let (_, p) = schema.generate_pair(&mut rng);
+        let low_order = point!(Bigi::<4>::from(0),
+                               schema.curve.m - &Bigi::<4>::from(1));
+        assert_eq!(schema.validate_point(&p), Ok(()));
+        assert_eq!(schema.validate_point(&schema.curve.zero()),
+                   Err("point is the zero point"));
+        assert_eq!(schema.validate_point(&low_order),
+                   Err("point is not in the subgroup"));
+    }
+
+    #[test]
+    fn test_clear_cofactor() {
+        let mut rng = rand::thread_rng();
+
+        let schema = load_curve1174();
+        let (_, p) = schema.generate_pair(&mut rng);
+        let low_order = point!(Bigi::<4>::from(0),
+                               schema.curve.m - &Bigi::<4>::from(1));
+        let mixed = schema.curve.add(&p, &low_order);
+        assert_eq!(schema.clear_cofactor(&mixed),
+                   schema.curve.mul(&p, &schema.cofactor));
+        assert_eq!(schema.clear_cofactor(&low_order), schema.curve.zero());
+
+        let schema = load_secp256k1();
+        let (_, p) = schema.generate_pair(&mut rng);
+        assert_eq!(schema.clear_cofactor(&p), p);
+    }
+
+    #[bench]
+    fn bench_load_secp160k1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp160k1());
+    }
+
+    #[bench]
+    fn bench_load_secp160r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp160r1());
+    }
+
+    #[bench]
+    fn bench_load_secp192k1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp192k1());
+    }
+
+    #[bench]
+    fn bench_load_secp192r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp192r1());
+    }
+
+    #[bench]
+    fn bench_load_secp224k1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp224k1());
+    }
+
+    #[bench]
+    fn bench_load_secp224r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp224r1());
+    }
+
     #[bench]
     fn bench_load_secp256k1(bencher: &mut Bencher) {
         bencher.iter(|| load_secp256k1());
     }
 
+    #[bench]
+    fn bench_load_gost256a(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost256a());
+    }
+
+    #[bench]
+    fn bench_load_gost256b(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost256b());
+    }
+
+    #[bench]
+    fn bench_load_gost256c(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost256c());
+    }
+
+    #[bench]
+    fn bench_load_gost256d(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost256d());
+    }
+
+    #[bench]
+    fn bench_load_gost512a(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost512a());
+    }
+
+    #[bench]
+    fn bench_load_gost512b(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost512b());
+    }
+
+    #[bench]
+    fn bench_load_gost512c(bencher: &mut Bencher) {
+        bencher.iter(|| load_gost512c());
+    }
+
     #[bench]
     fn bench_load_fp254bnb(bencher: &mut Bencher) {
         bencher.iter(|| load_fp254bnb());
     }
 
+    #[bench]
+    fn bench_load_secp256r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp256r1());
+    }
+
+    #[bench]
+    fn bench_load_secp384r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp384r1());
+    }
+
+    #[bench]
+    fn bench_load_secp521r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_secp521r1());
+    }
+
+    #[bench]
+    fn bench_load_sm2p256v1(bencher: &mut Bencher) {
+        bencher.iter(|| load_sm2p256v1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp256r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp256r1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp256t1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp256t1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp384r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp384r1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp384t1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp384t1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp512r1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp512r1());
+    }
+
+    #[bench]
+    fn bench_load_brainpoolp512t1(bencher: &mut Bencher) {
+        bencher.iter(|| load_brainpoolp512t1());
+    }
+
     #[bench]
     fn bench_load_curve25519(bencher: &mut Bencher) {
         bencher.iter(|| load_curve25519());
@@ -139,4 +866,14 @@ mod tests {
     fn bench_load_curve1174(bencher: &mut Bencher) {
         bencher.iter(|| load_curve1174());
     }
+
+    #[bench]
+    fn bench_load_curve448(bencher: &mut Bencher) {
+        bencher.iter(|| load_curve448());
+    }
+
+    #[bench]
+    fn bench_load_ed448(bencher: &mut Bencher) {
+        bencher.iter(|| load_ed448());
+    }
 }
@@ -0,0 +1,571 @@
+//! This module implements [Schnorr signatures](https://en.wikipedia.org/wiki/Schnorr_signature).
+//! There are two flavours: `sign` and `verify` implement the scheme for
+//! secp256k1 according to [BIP-340](https://github.com/bitcoin/bips/blob/master/bip-0340.mediawiki)
+//! (x-only public keys, even `y` convention and tagged hashes), while
+//! `build_signature` and `check_signature` implement the plain scheme
+//! `R = k G`, `e = H(R || P || m)`, `s = k + e x` for any schema with
+//! configurable hash function and point encoding.
+//!
+//! BIP-340 example:
+//! ```rust
+//! use bigi_ecc::schemas;
+//! use bigi_ecc::schnorr::{get_public_key, sign, verify};
+//!
+//! let message = b"a test phrase";
+//!
+//! // Load secp256k1 schema
+//! let schema = schemas::load_secp256k1();
+//!
+//! // Private key and auxiliary random data are 32 bytes each
+//! let private_key = [7u8; 32];
+//! let aux_rand = [0u8; 32];
+//! let public_key = get_public_key(&schema, &private_key).unwrap();
+//!
+//! // Build signature
+//! let signature = sign(&schema, &private_key, &message[..], &aux_rand)
+//!     .unwrap();
+//!
+//! // Check the signature
+//! assert!(verify(&schema, &public_key, &message[..], &signature));
+//! ```
+//!
+//! Plain Schnorr example:
+//! ```rust
+//! use sha2::Sha256;
+//! use bigi_ecc::{schemas, Point};
+//! use bigi_ecc::schnorr::{build_signature, check_signature};
+//!
+//! let message = b"a test phrase";
+//!
+//! // Load a crypto schema
+//! let schema = schemas::load_curve25519();
+//!
+//! // Generate a key pair
+//! let mut rng = rand::thread_rng();
+//! let (private_key, public_key) = schema.generate_pair(&mut rng);
+//!
+//! // Build signature, points are encoded with `Point::to_bytes`
+//! let signature = build_signature::<_, Sha256, _, 4>(
+//!     &mut rng, &schema, &private_key, &message[..], Point::to_bytes
+//! );
+//!
+//! // Check the signature
+//! assert!(check_signature::<Sha256, _, 4>(
+//!     &schema, &public_key, &message[..], &signature, Point::to_bytes
+//! ));
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use sha2::{Sha256, Digest};
+use bigi::Bigi;
+use bigi::prime::{add_mod, mul_mod};
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::schemas::Schema;
+use crate::utils::{gen_random_weight, bigi_from_be_bytes, bigi_to_be_bytes,
+                   bigi_from_be_bytes_mod};
+
+
+/// Calculates tagged hash `SHA256(SHA256(tag) || SHA256(tag) || data)`.
+pub fn tagged_hash(tag: &str, data: &[u8]) -> Vec<u8> {
+    let tag_hash = Sha256::digest(tag.as_bytes());
+    let mut hasher = Sha256::new();
+    hasher.update(&tag_hash);
+    hasher.update(&tag_hash);
+    hasher.update(data);
+    hasher.finalize().to_vec()
+}
+
+
+/// Finds the point with even `y` by given `x`.
+pub fn lift_x(schema: &Schema<WeierstrassCurve<4>, 4>,
+              x: &Bigi<4>) -> Result<Point<4>, &'static str> {
+    if x >= &schema.curve.m {
+        return Err("x is out of range");
+    }
+    let (y1, y2) = schema.curve.find_y(x)?;
+    let y = if y1.get_bit(0) { y2 } else { y1 };
+    Ok(point!(*x, y))
+}
+
+
+/// Gets the x-only public key (32 bytes) for the private key (32 bytes).
+pub fn get_public_key(schema: &Schema<WeierstrassCurve<4>, 4>,
+                      private_key: &[u8]) -> Result<Vec<u8>, &'static str> {
+    let d = private_key_to_bigi(schema, private_key)?;
+    Ok(bigi_to_be_bytes(&schema.get_point(&d).x, 32))
+}
+
+
+/// Builds a signature (64 bytes) for the private key, the message and
+/// auxiliary random data (32 bytes).
+pub fn sign(schema: &Schema<WeierstrassCurve<4>, 4>,
+            private_key: &[u8],
+            message: &[u8],
+            aux_rand: &[u8]) -> Result<Vec<u8>, &'static str> {
+    if aux_rand.len() != 32 {
+        return Err("wrong length of auxiliary random data");
+    }
+
+    let d0 = private_key_to_bigi(schema, private_key)?;
+    let p = schema.get_point(&d0);
+    let d = if has_even_y(&p) { d0 } else { schema.order - &d0 };
+    let p_bytes = bigi_to_be_bytes(&p.x, 32);
+
+    let t: Vec<u8> = bigi_to_be_bytes(&d, 32).iter()
+        .zip(tagged_hash("BIP0340/aux", aux_rand).iter())
+        .map(|(a, b)| a ^ b)
+        .collect();
+    let rand = tagged_hash(
+        "BIP0340/nonce", &[&t[..], &p_bytes, message].concat()
+    );
+
+    let k0 = bigi_from_be_bytes::<4>(&rand) % &schema.order;
+    if k0.is_zero() {
+        return Err("nonce is zero");
+    }
+    let r = schema.get_point(&k0);
+    let k = if has_even_y(&r) { k0 } else { schema.order - &k0 };
+    let r_bytes = bigi_to_be_bytes(&r.x, 32);
+
+    let e = challenge(schema, &r_bytes, &p_bytes, message);
+    let s = add_mod(&k, &mul_mod(&e, &d, &schema.order), &schema.order);
+
+    let mut signature = r_bytes;
+    signature.extend(bigi_to_be_bytes(&s, 32));
+
+    if !verify(schema, &p_bytes, message, &signature) {
+        return Err("created signature does not pass verification");
+    }
+
+    Ok(signature)
+}
+
+
+/// Checks the signature (64 bytes) for the x-only public key (32 bytes)
+/// and the message.
+pub fn verify(schema: &Schema<WeierstrassCurve<4>, 4>,
+              public_key: &[u8],
+              message: &[u8],
+              signature: &[u8]) -> bool {
+    if (public_key.len() != 32) || (signature.len() != 64) {
+        return false;
+    }
+
+    let p = match lift_x(schema, &bigi_from_be_bytes(public_key)) {
+        Ok(p) => p,
+        Err(_) => return false,
+    };
+
+    let r = bigi_from_be_bytes::<4>(&signature[..32]);
+    let s = bigi_from_be_bytes::<4>(&signature[32..]);
+    if (r >= schema.curve.m) || (s >= schema.order) {
+        return false;
+    }
+
+    let e = challenge(schema, &signature[..32], public_key, message);
+
+    // R = s G - e P
+    let point = schema.curve.add(
+        &schema.get_point(&s),
+        &schema.curve.mul(&p, &(schema.order - &e))
+    );
+
+    !point.is_zero && has_even_y(&point) && (point.x == r)
+}
+
+
+/// Builds a plain Schnorr signature `(R, s)` for given schema, private key
+/// and message. The challenge `e` is the hash `D` of the encoded points
+/// `R`, `P` and the message.
+pub fn build_signature<R: Rng + ?Sized, D: Digest, T: CurveTrait<N>,
+                       const N: usize> (
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            private_key: &Bigi<N>,
+            message: &[u8],
+            encode: fn(&Point<N>) -> Vec<u8>
+        ) -> (Point<N>, Bigi<N>) {
+    let public_key = schema.get_point(private_key);
+
+    let (k, r) = {
+        let mut pair;
+        loop {
+            pair = schema.generate_pair(rng);
+            if !pair.0.is_zero() {
+                break;
+            }
+        }
+        pair
+    };
+
+    let e = plain_challenge::<D, T, N>(
+        schema, &encode(&r), &encode(&public_key), message
+    );
+    let s = add_mod(
+        &k, &mul_mod(&e, private_key, &schema.order), &schema.order
+    );
+
+    (r, s)
+}
+
+
+/// Checks the plain Schnorr signature for given schema, public key and
+/// message: `[c] s G == [c] (R + e P)` where `c` is the cofactor, so
+/// the answer is the same as in `check_signatures_batch`.
+pub fn check_signature<D: Digest, T: CurveTrait<N>, const N: usize> (
+            schema: &Schema<T, N>,
+            public_key: &Point<N>,
+            message: &[u8],
+            signature: &(Point<N>, Bigi<N>),
+            encode: fn(&Point<N>) -> Vec<u8>
+        ) -> bool {
+    let (r, s) = signature;
+
+    if (s >= &schema.order) || !schema.curve.check(r) {
+        return false;
+    }
+
+    let e = plain_challenge::<D, T, N>(
+        schema, &encode(r), &encode(public_key), message
+    );
+    let left = schema.curve.mul(&schema.get_point(s), &schema.cofactor);
+    let right = schema.curve.mul(
+        &schema.curve.add(r, &schema.curve.mul(public_key, &e)),
+        &schema.cofactor
+    );
+    left == right
+}
+
+
+/// Checks many plain Schnorr signatures at once. Each item is a tuple of
+/// the public key, the message and the signature. The signatures are
+/// combined with random weights into one multi-scalar multiplication check
+/// (multiplied by the cofactor), if it fails every signature is checked
+/// individually and the indices of the invalid ones are returned.
+pub fn check_signatures_batch<R: Rng + ?Sized, D: Digest, T: CurveTrait<N>,
+                              const N: usize> (
+            rng: &mut R,
+            schema: &Schema<T, N>,
+            items: &[(&Point<N>, &[u8], &(Point<N>, Bigi<N>))],
+            encode: fn(&Point<N>) -> Vec<u8>
+        ) -> Result<(), Vec<usize>> {
+    let mut valid = true;
+    let mut sum = Bigi::<N>::from(0);
+    let mut points = Vec::with_capacity(items.len() << 1);
+    let mut scalars = Vec::with_capacity(items.len() << 1);
+
+    for (public_key, message, (r, s)) in items.iter() {
+        if (s >= &schema.order) || !schema.curve.check(r) {
+            valid = false;
+            break;
+        }
+
+        let e = plain_challenge::<D, T, N>(
+            schema, &encode(r), &encode(public_key), message
+        );
+        let z = gen_random_weight(rng, &schema.order);
+
+        sum = add_mod(&sum, &mul_mod(&z, s, &schema.order), &schema.order);
+        points.push(*r);
+        scalars.push(z);
+        points.push(**public_key);
+        scalars.push(mul_mod(&z, &e, &schema.order));
+    }
+
+    if valid {
+        let left = schema.curve.mul(&schema.get_point(&sum), &schema.cofactor);
+        let right = schema.curve.mul(
+            &schema.curve.mul_sum(&points, &scalars), &schema.cofactor
+        );
+        valid = left == right;
+    }
+
+    if valid {
+        return Ok(());
+    }
+
+    let invalid: Vec<usize> = items.iter().enumerate()
+        .filter(|(_, (public_key, message, signature))| {
+            !check_signature::<D, T, N>(
+                schema, public_key, message, signature, encode
+            )
+        })
+        .map(|(idx, _)| idx)
+        .collect();
+
+    if invalid.is_empty() {
+        Ok(())
+    } else {
+        Err(invalid)
+    }
+}
+
+
+fn plain_challenge<D: Digest, T: CurveTrait<N>, const N: usize>(
+            schema: &Schema<T, N>,
+            r_bytes: &[u8], p_bytes: &[u8], message: &[u8]
+        ) -> Bigi<N> {
+    let mut hasher = D::new();
+    hasher.update(r_bytes);
+    hasher.update(p_bytes);
+    hasher.update(message);
+    bigi_from_be_bytes_mod(&hasher.finalize(), &schema.order)
+}
+
+
+fn has_even_y(p: &Point<4>) -> bool {
+    !p.y.get_bit(0)
+}
+
+
+fn private_key_to_bigi(schema: &Schema<WeierstrassCurve<4>, 4>,
+                       private_key: &[u8]) -> Result<Bigi<4>, &'static str> {
+    if private_key.len() != 32 {
+        return Err("wrong length of private key");
+    }
+    let d = bigi_from_be_bytes::<4>(private_key);
+    if d.is_zero() || (d >= schema.order) {
+        return Err("private key is out of range");
+    }
+    Ok(d)
+}
+
+
+fn challenge(schema: &Schema<WeierstrassCurve<4>, 4>,
+             r_bytes: &[u8], p_bytes: &[u8], message: &[u8]) -> Bigi<4> {
+    let h = tagged_hash(
+        "BIP0340/challenge", &[r_bytes, p_bytes, message].concat()
+    );
+    bigi_from_be_bytes::<4>(&h) % &schema.order
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::schemas;
+    use crate::utils::bytes_from_hex;
+
+    #[test]
+    fn test_vectors() {
+        let schema = schemas::load_secp256k1();
+        let vectors = include_str!("../data/bip340-test-vectors.csv");
+        assert_eq!(vectors.lines().skip(1).count(), 19);
+
+        for line in vectors.lines().skip(1) {
+            let fields: Vec<&str> = line.split(',').collect();
+            let public_key = bytes_from_hex(fields[2]).unwrap();
+            let message = bytes_from_hex(fields[4]).unwrap();
+            let signature = bytes_from_hex(fields[5]).unwrap();
+            let result = fields[6] == "TRUE";
+
+            if !fields[1].is_empty() {
+                let private_key = bytes_from_hex(fields[1]).unwrap();
+                let aux_rand = bytes_from_hex(fields[3]).unwrap();
+                assert_eq!(
+                    get_public_key(&schema, &private_key), Ok(public_key.clone()),
+                    "vector {}", fields[0]
+                );
+                assert_eq!(
+                    sign(&schema, &private_key, &message, &aux_rand),
+                    Ok(signature.clone()),
+                    "vector {}", fields[0]
+                );
+            }
+
+            assert_eq!(
+                verify(&schema, &public_key, &message, &signature), result,
+                "vector {}", fields[0]
+            );
+        }
+    }
+
+    #[test]
+    fn test_schnorr() {
+        let message = b"a test phrase";
+        let schema = schemas::load_secp256k1();
+        let private_key = [0xAB; 32];
+        let public_key = get_public_key(&schema, &private_key).unwrap();
+        let signature = sign(
+            &schema, &private_key, &message[..], &[1u8; 32]
+        ).unwrap();
+
+        assert!(verify(&schema, &public_key, &message[..], &signature));
+        assert!(!verify(&schema, &public_key, b"another phrase", &signature));
+        assert!(!verify(&schema, &public_key, &message[..], &signature[1..]));
+        assert!(get_public_key(&schema, &[0u8; 32]).is_err());
+        assert!(get_public_key(&schema, &[0xFF; 32]).is_err());
+    }
+
+    #[test]
+    fn test_plain_schnorr() {
+        let message = b"a test phrase";
+        let mut rng = rand::thread_rng();
+
+        let schema = schemas::load_secp256k1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature::<_, Sha256, _, 4>(
+            &mut rng, &schema, &private_key, &message[..], Point::to_bytes
+        );
+        assert!(check_signature::<Sha256, _, 4>(
+            &schema, &public_key, &message[..], &signature, Point::to_bytes
+        ));
+        assert!(!check_signature::<Sha256, _, 4>(
+            &schema, &public_key, b"another phrase", &signature,
+            Point::to_bytes
+        ));
+        assert!(!check_signature::<Sha256, _, 4>(
+            &schema, &public_key, &message[..], &signature,
+            |p| p.to_hex().into_bytes()
+        ));
+
+        let schema = schemas::load_curve25519();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature::<_, Sha256, _, 4>(
+            &mut rng, &schema, &private_key, &message[..], Point::to_bytes
+        );
+        assert!(check_signature::<Sha256, _, 4>(
+            &schema, &public_key, &message[..], &signature, Point::to_bytes
+        ));
+        assert!(!check_signature::<Sha256, _, 4>(
+            &schema, &schema.generator, &message[..], &signature,
+            Point::to_bytes
+        ));
+
+        let schema = schemas::load_curve1174();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature::<_, sha2::Sha512, _, 4>(
+            &mut rng, &schema, &private_key, &message[..], Point::to_bytes
+        );
+        assert!(check_signature::<sha2::Sha512, _, 4>(
+            &schema, &public_key, &message[..], &signature, Point::to_bytes
+        ));
+        assert!(!check_signature::<Sha256, _, 4>(
+            &schema, &public_key, &message[..], &signature, Point::to_bytes
+        ));
+    }
+
+    #[test]
+    fn test_plain_schnorr_batch() {
+        let mut rng = rand::thread_rng();
+
+        let schema = schemas::load_curve25519();
+        let messages: Vec<Vec<u8>> = (0..5).map(
+            |i| format!("message {}", i).into_bytes()
+        ).collect();
+        let pairs: Vec<(Bigi<4>, Point<4>)> = (0..5).map(
+            |_| schema.generate_pair(&mut rng)
+        ).collect();
+        let mut signatures: Vec<(Point<4>, Bigi<4>)> = pairs.iter()
+            .zip(messages.iter())
+            .map(|((private_key, _), message)| {
+                build_signature::<_, Sha256, _, 4>(
+                    &mut rng, &schema, private_key, message, Point::to_bytes
+                )
+            }).collect();
+
+        let items: Vec<(&Point<4>, &[u8], &(Point<4>, Bigi<4>))> =
+            (0..5).map(|i| (
+                &pairs[i].1, &messages[i][..], &signatures[i]
+            )).collect();
+        assert_eq!(check_signatures_batch::<_, Sha256, _, 4>(
+            &mut rng, &schema, &items, Point::to_bytes
+        ), Ok(()));
+        assert_eq!(check_signatures_batch::<_, Sha256, _, 4>(
+            &mut rng, &schema, &[], Point::to_bytes
+        ), Ok(()));
+
+        signatures[0].1 = signatures[2].1;
+        signatures[4].0 = schema.generator;
+        let items: Vec<(&Point<4>, &[u8], &(Point<4>, Bigi<4>))> =
+            (0..5).map(|i| (
+                &pairs[i].1, &messages[i][..], &signatures[i]
+            )).collect();
+        assert_eq!(check_signatures_batch::<_, Sha256, _, 4>(
+            &mut rng, &schema, &items, Point::to_bytes
+        ), Err(vec![0, 4]));
+
+        // R with a component of order 2 is accepted by both checks
+        let (private_key, public_key) = &pairs[0];
+        let (k, r) = schema.generate_pair(&mut rng);
+        let r = schema.curve.add(
+            &r, &point!(Bigi::<4>::from(0), Bigi::<4>::from(0))
+        );
+        let e = plain_challenge::<Sha256, _, 4>(
+            &schema, &r.to_bytes(), &public_key.to_bytes(), &messages[0]
+        );
+        let signature = (r, add_mod(
+            &k, &mul_mod(&e, private_key, &schema.order), &schema.order
+        ));
+        assert!(check_signature::<Sha256, _, 4>(
+            &schema, public_key, &messages[0], &signature, Point::to_bytes
+        ));
+        for _ in 0..10 {
+            assert_eq!(check_signatures_batch::<_, Sha256, _, 4>(
+                &mut rng, &schema,
+                &[(public_key, &messages[0][..], &signature)],
+                Point::to_bytes
+            ), Ok(()));
+        }
+    }
+
+    #[bench]
+    fn bench_sign(b: &mut Bencher) {
+        let schema = schemas::load_secp256k1();
+        b.iter(|| sign(&schema, &[0xAB; 32], b"a test phrase", &[1u8; 32]));
+    }
+
+    #[bench]
+    fn bench_verify(b: &mut Bencher) {
+        let schema = schemas::load_secp256k1();
+        let public_key = get_public_key(&schema, &[0xAB; 32]).unwrap();
+        let signature = sign(
+            &schema, &[0xAB; 32], b"a test phrase", &[1u8; 32]
+        ).unwrap();
+        b.iter(|| verify(&schema, &public_key, b"a test phrase", &signature));
+    }
+
+    #[bench]
+    fn bench_build_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve25519();
+        let (private_key, _public_key) = schema.generate_pair(&mut rng);
+        b.iter(|| build_signature::<_, Sha256, _, 4>(
+            &mut rng, &schema, &private_key, b"a test phrase", Point::to_bytes
+        ));
+    }
+
+    #[bench]
+    fn bench_check_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve25519();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature::<_, Sha256, _, 4>(
+            &mut rng, &schema, &private_key, b"a test phrase", Point::to_bytes
+        );
+        b.iter(|| check_signature::<Sha256, _, 4>(
+            &schema, &public_key, b"a test phrase", &signature, Point::to_bytes
+        ));
+    }
+
+    #[bench]
+    fn bench_check_signatures_batch(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_curve25519();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature::<_, Sha256, _, 4>(
+            &mut rng, &schema, &private_key, b"a test phrase", Point::to_bytes
+        );
+        let items: Vec<(&Point<4>, &[u8], &(Point<4>, Bigi<4>))> =
+            (0..16).map(|_| (
+                &public_key, &b"a test phrase"[..], &signature
+            )).collect();
+        b.iter(|| check_signatures_batch::<_, Sha256, _, 4>(
+            &mut rng, &schema, &items, Point::to_bytes
+        ));
+    }
+}
@@ -0,0 +1,515 @@
+//! This module implements SM2 digital signatures (GB/T 32918.2) with
+//! the user identity hash `Z_A` and SM2 public key encryption
+//! (GB/T 32918.4) with the ciphertext layout `C1 || C3 || C2`, where `C1`
+//! is the uncompressed point `04 || x || y`, `C3` is SM3 hash of the
+//! message and `C2` is the masked message. The hash function is SM3.
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::schemas;
+//! use bigi_ecc::sm2::{build_signature, check_signature, encrypt, decrypt,
+//!                     DEFAULT_ID};
+//!
+//! let message = b"a test phrase";
+//!
+//! // Load schema
+//! let schema = schemas::load_sm2p256v1();
+//!
+//! // Generate a key pair
+//! let mut rng = rand::thread_rng();
+//! let (private_key, public_key) = schema.generate_pair(&mut rng);
+//!
+//! // Build and check a signature
+//! let signature = build_signature(
+//!     &mut rng, &schema, &private_key, DEFAULT_ID, message
+//! ).unwrap();
+//! assert!(check_signature(
+//!     &schema, &public_key, DEFAULT_ID, message, &signature
+//! ));
+//!
+//! // Encrypt and decrypt the message
+//! let encrypted = encrypt(&mut rng, &schema, &public_key, message).unwrap();
+//! assert_eq!(decrypt(&schema, &private_key, &encrypted).unwrap(), message);
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use bigi::Bigi;
+use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod};
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::weierstrass::WeierstrassCurve;
+use crate::schemas::Schema;
+use crate::sm3::{self, Sm3};
+use crate::utils::{bigi_from_be_bytes, bigi_from_be_bytes_mod,
+                   bigi_to_be_bytes};
+
+
+/// The default user identity of the standard.
+pub const DEFAULT_ID: &[u8] = b"1234567812345678";
+
+
+/// Calculates the user identity hash
+/// `Z_A = SM3(ENTL_A || ID_A || a || b || x_G || y_G || x_A || y_A)`.
+/// The identity must be shorter than 8192 bytes.
+pub fn get_za<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            id: &[u8],
+            public_key: &Point<N>
+        ) -> Result<[u8; sm3::SIZE], &'static str> {
+    if id.len() >= 8192 {
+        return Err("too long identity");
+    }
+
+    let size = field_size(schema);
+    let mut hasher = Sm3::new();
+    hasher.update(&((id.len() << 3) as u16).to_be_bytes());
+    hasher.update(id);
+    for x in [&schema.curve.a, &schema.curve.b,
+              &schema.generator.x, &schema.generator.y,
+              &public_key.x, &public_key.y].iter() {
+        hasher.update(&bigi_to_be_bytes(x, size));
+    }
+    Ok(hasher.finalize())
+}
+
+
+/// Builds a signature of the message for the user identity `id`.
+pub fn build_signature<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R,
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            private_key: &Bigi<N>,
+            id: &[u8],
+            message: &[u8]
+        ) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
+    if private_key.is_zero() ||
+            (*private_key + &Bigi::<N>::from(1) >= schema.order) {
+        return Err("invalid private key");
+    }
+
+    let public_key = schema.get_point(private_key);
+    let e = hash_message(schema, id, &public_key, message)?;
+
+    loop {
+        let (k, _) = schema.generate_pair(rng);
+        if let Some(signature) = sign_with_nonce(schema, private_key, &e, &k) {
+            return Ok(signature);
+        }
+    }
+}
+
+
+/// Checks the signature of the message for the user identity `id`.
+pub fn check_signature<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            public_key: &Point<N>,
+            id: &[u8],
+            message: &[u8],
+            signature: &(Bigi<N>, Bigi<N>)
+        ) -> bool {
+    let (r, s) = signature;
+    if r.is_zero() || (r >= &schema.order) ||
+            s.is_zero() || (s >= &schema.order) {
+        return false;
+    }
+
+    let e = match hash_message(schema, id, public_key, message) {
+        Ok(e) => e,
+        Err(_) => return false
+    };
+
+    // t = r + s, (x1, y1) = s G + t P, R = e + x1
+    let t = add_mod(r, s, &schema.order);
+    if t.is_zero() {
+        return false;
+    }
+    let p = schema.curve.mul_sum(
+        &[schema.generator, *public_key], &[*s, t]
+    );
+    if p.is_zero {
+        return false;
+    }
+    add_mod(&e, &(p.x % &schema.order), &schema.order) == *r
+}
+
+
+/// Encrypts the message with the public key, the result is
+/// `C1 || C3 || C2`.
+pub fn encrypt<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R,
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            public_key: &Point<N>,
+            message: &[u8]
+        ) -> Result<Vec<u8>, &'static str> {
+    if public_key.is_zero || !schema.curve.check(public_key) {
+        return Err("invalid public key");
+    }
+    if schema.curve.mul(public_key, &schema.cofactor).is_zero {
+        return Err("public key is of small order");
+    }
+
+    loop {
+        let (k, _) = schema.generate_pair(rng);
+        if let Some(encrypted) = encrypt_with_nonce(
+                schema, public_key, message, &k) {
+            return Ok(encrypted);
+        }
+    }
+}
+
+
+/// Decrypts `C1 || C3 || C2` with the private key.
+pub fn decrypt<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            private_key: &Bigi<N>,
+            encrypted: &[u8]
+        ) -> Result<Vec<u8>, &'static str> {
+    let size = field_size(schema);
+    if encrypted.len() < 1 + 2 * size + sm3::SIZE {
+        return Err("too short ciphertext");
+    }
+
+    let c1 = decode_point(schema, &encrypted[..(1 + 2 * size)])?;
+    if schema.curve.mul(&c1, &schema.cofactor).is_zero {
+        return Err("C1 is of small order");
+    }
+    let c3 = &encrypted[(1 + 2 * size)..(1 + 2 * size + sm3::SIZE)];
+    let c2 = &encrypted[(1 + 2 * size + sm3::SIZE)..];
+
+    let s = schema.curve.mul(&c1, private_key);
+    let x2 = bigi_to_be_bytes(&s.x, size);
+    let y2 = bigi_to_be_bytes(&s.y, size);
+
+    let mask = kdf(&[&x2[..], &y2[..]].concat(), c2.len());
+    if !c2.is_empty() && mask.iter().all(|&b| b == 0) {
+        return Err("zero mask");
+    }
+    let message: Vec<u8> = c2.iter().zip(mask.iter())
+        .map(|(c, t)| c ^ t).collect();
+
+    if hash_c3(&x2, &message, &y2)[..] != *c3 {
+        return Err("hash mismatch");
+    }
+    Ok(message)
+}
+
+
+fn field_size<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>) -> usize {
+    (schema.curve.m.bit_length() + 7) >> 3
+}
+
+
+fn hash_message<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            id: &[u8],
+            public_key: &Point<N>,
+            message: &[u8]
+        ) -> Result<Bigi<N>, &'static str> {
+    let za = get_za(schema, id, public_key)?;
+    let mut hasher = Sm3::new();
+    hasher.update(&za);
+    hasher.update(message);
+    Ok(bigi_from_be_bytes_mod(&hasher.finalize(), &schema.order))
+}
+
+
+fn sign_with_nonce<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            private_key: &Bigi<N>,
+            e: &Bigi<N>,
+            k: &Bigi<N>
+        ) -> Option<(Bigi<N>, Bigi<N>)> {
+    let n = &schema.order;
+
+    // r = e + x1, it must not be 0 and r + k must not be n
+    let x1 = schema.get_point(k).x % n;
+    let r = add_mod(e, &x1, n);
+    if r.is_zero() || add_mod(&r, k, n).is_zero() {
+        return None;
+    }
+
+    // s = (k - r d) / (1 + d)
+    let s = div_mod(
+        &sub_mod(k, &mul_mod(&r, private_key, n), n),
+        &add_mod(private_key, &Bigi::<N>::from(1), n), n
+    );
+    if s.is_zero() {
+        return None;
+    }
+    Some((r, s))
+}
+
+
+fn encrypt_with_nonce<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            public_key: &Point<N>,
+            message: &[u8],
+            k: &Bigi<N>
+        ) -> Option<Vec<u8>> {
+    let size = field_size(schema);
+    let c1 = schema.get_point(k);
+    let s = schema.curve.mul(public_key, k);
+    let x2 = bigi_to_be_bytes(&s.x, size);
+    let y2 = bigi_to_be_bytes(&s.y, size);
+
+    let mask = kdf(&[&x2[..], &y2[..]].concat(), message.len());
+    if !message.is_empty() && mask.iter().all(|&b| b == 0) {
+        return None;
+    }
+
+    let mut res = encode_point(&c1, size);
+    res.extend_from_slice(&hash_c3(&x2, message, &y2));
+    res.extend(message.iter().zip(mask.iter()).map(|(m, t)| m ^ t));
+    Some(res)
+}
+
+
+fn hash_c3(x2: &[u8], message: &[u8], y2: &[u8]) -> [u8; sm3::SIZE] {
+    let mut hasher = Sm3::new();
+    hasher.update(x2);
+    hasher.update(message);
+    hasher.update(y2);
+    hasher.finalize()
+}
+
+
+/// The key derivation function of the standard:
+/// `SM3(z || 1) || SM3(z || 2) || ...` truncated to `len` bytes.
+fn kdf(z: &[u8], len: usize) -> Vec<u8> {
+    let mut res = Vec::with_capacity(len + sm3::SIZE);
+    let mut counter = 1u32;
+    while res.len() < len {
+        let mut hasher = Sm3::new();
+        hasher.update(z);
+        hasher.update(&counter.to_be_bytes());
+        res.extend_from_slice(&hasher.finalize());
+        counter += 1;
+    }
+    res.truncate(len);
+    res
+}
+
+
+fn encode_point<const N: usize>(p: &Point<N>, size: usize) -> Vec<u8> {
+    let mut res = vec![4u8];
+    res.extend_from_slice(&bigi_to_be_bytes(&p.x, size));
+    res.extend_from_slice(&bigi_to_be_bytes(&p.y, size));
+    res
+}
+
+
+fn decode_point<const N: usize>(
+            schema: &Schema<WeierstrassCurve<N>, N>,
+            bytes: &[u8]
+        ) -> Result<Point<N>, &'static str> {
+    let size = field_size(schema);
+    if bytes[0] != 4 {
+        return Err("unsupported point encoding");
+    }
+    let x = bigi_from_be_bytes::<N>(&bytes[1..(1 + size)]);
+    let y = bigi_from_be_bytes::<N>(&bytes[(1 + size)..(1 + 2 * size)]);
+    if x >= schema.curve.m || y >= schema.curve.m {
+        return Err("invalid point");
+    }
+    let p = point!(x, y);
+    if !schema.curve.check(&p) {
+        return Err("point is not on the curve");
+    }
+    Ok(p)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::schemas;
+    use crate::utils::{bytes_from_hex, bytes_to_hex};
+
+    // The example curve of GB/T 32918 used in its worked examples
+    fn load_example_curve() -> Schema<WeierstrassCurve<4>, 4> {
+        Schema {
+            bits: 256,
+            title: "sm2-example",
+            curve: WeierstrassCurve::<4> {
+                a: Bigi::<4>::from_hex("0x787968B4FA32C3FD2417842E73BBFEFF2F3C848B6831D7E0EC65228B3937E498"),
+                b: Bigi::<4>::from_hex("0x63E4C6D3B23B0C849CF84241484BFE48F61D59A5B16BA06E6E12D1DA27C5249A"),
+                m: Bigi::<4>::from_hex("0x8542D69E4C044F18E8B92435BF6FF7DE457283915C45517D722EDB8B08F1DFC3")
+            },
+            order: Bigi::<4>::from_hex("0x8542D69E4C044F18E8B92435BF6FF7DD297720630485628D5AE74EE7C32E79B7"),
+            cofactor: Bigi::<4>::from_hex("0x1"),
+            generator: point!(
+                Bigi::<4>::from_hex("0x421DEBD61B62EAB6746434EBC3CC315E32220B3BADD50BDC4C4E6C147FEDD43D"),
+                Bigi::<4>::from_hex("0x0680512BCBB42C07D47349D2153B70C4E5D7FDFCBFA36EA1A85841B9E46E09A2")
+            )
+        }
+    }
+
+    #[test]
+    fn test_signature_example() {
+        let schema = load_example_curve();
+        let id = b"ALICE123@YAHOO.COM";
+        let private_key = Bigi::<4>::from_hex(
+            "0x128B2FA8BD433C6C068C8D803DFF79792A519A55171B1B650C23661D15897263"
+        );
+        let public_key = schema.get_point(&private_key);
+        assert_eq!(
+            public_key,
+            point!(
+                Bigi::<4>::from_hex("0x0AE4C7798AA0F119471BEE11825BE46202BB79E2A5844495E97C04FF4DF2548A"),
+                Bigi::<4>::from_hex("0x7C0240F88F1CD4E16352A73C17B7F16F07353E53A176D684A9FE0C6BB798E857")
+            )
+        );
+        assert_eq!(
+            bytes_to_hex(&get_za(&schema, id, &public_key).unwrap()),
+            "f4a38489e32b45b6f876e3ac2168ca392362dc8f23459c1d1146fc3dbfb7bc9a"
+        );
+
+        let signature = (
+            Bigi::<4>::from_hex("0x40F1EC59F793D9F49E09DCEF49130D4194F79FB1EED2CAA55BACDB49C4E755D1"),
+            Bigi::<4>::from_hex("0x6FC6DAC32C5D5CF10C77DFB20F7C2EB667A457872FB09EC56327A67EC7DEEBE7")
+        );
+        assert!(check_signature(
+            &schema, &public_key, id, b"message digest", &signature
+        ));
+        assert!(!check_signature(
+            &schema, &public_key, id, b"message digesT", &signature
+        ));
+        assert!(!check_signature(
+            &schema, &public_key, DEFAULT_ID, b"message digest", &signature
+        ));
+    }
+
+    #[test]
+    fn test_encryption_example() {
+        let schema = load_example_curve();
+        let private_key = Bigi::<4>::from_hex(
+            "0x1649AB77A00637BD5E2EFE283FBF353534AA7F7CB89463F208DDBC2920BB0DA0"
+        );
+        let public_key = schema.get_point(&private_key);
+        let k = Bigi::<4>::from_hex(
+            "0x4C62EEFD6ECFC2B95B92FD6C3D9575148AFA17425546D49018E5388D49DD7B4F"
+        );
+        let encrypted = encrypt_with_nonce(
+            &schema, &public_key, b"encryption standard", &k
+        ).unwrap();
+        assert_eq!(
+            bytes_to_hex(&encrypted),
+            "04245c26fb68b1ddddb12c4b6bf9f2b6d5fe60a383b0d18d1c4144abf17f6252e7\
+             76cb9264c2a7e88e52b19903fdc47378f605e36811f5c07423a24b84400f01b8\
+             9c3d7360c30156fab7c80a0276712da9d8094a634b766d3a285e07480653426d\
+             650053a89b41c418b0c3aad00d886c00286467"
+        );
+        assert_eq!(
+            decrypt(&schema, &private_key, &encrypted).unwrap(),
+            b"encryption standard"
+        );
+    }
+
+    #[test]
+    fn test_openssl_interop() {
+        // Generated by OpenSSL 3 with the default identity
+        let schema = schemas::load_sm2p256v1();
+        let private_key = Bigi::<4>::from_hex(
+            "0x843B5E97ABA041B159783987CDE10FC8524A5331C2B9B635AFCC0684715279C5"
+        );
+        let public_key = schema.get_point(&private_key);
+        assert_eq!(
+            public_key,
+            point!(
+                Bigi::<4>::from_hex("0x0601B4465DA195571475879A99738E4B1730A5F523D9AA77AADA3E4B95A89EBA"),
+                Bigi::<4>::from_hex("0x016AF6FE32A576D9A806BDE07E711A2DE4000F084BB6C27BEEAD88BBE6593F25")
+            )
+        );
+
+        let signature = (
+            Bigi::<4>::from_hex("0xFBAEB5466F7C0901C353ADEFA2F29DC16A8192B73C8F998537FBC840246436EE"),
+            Bigi::<4>::from_hex("0xC4CAC78A03243DDCB6880D962D0131D58C61EA27684219D95857A377132155B3")
+        );
+        assert!(check_signature(
+            &schema, &public_key, DEFAULT_ID, b"message digest", &signature
+        ));
+
+        let encrypted = bytes_from_hex(
+            "04b5996338d48463cac2a459689cde36d720e0de2264e30b8bcb84b58eb7beb4a7\
+             a45b91212dc00e72c8ec30c0d20b5d00851136ca0e4917928eba6b48a1a8a52c\
+             2f3ec838018482e631461b5dc69d42d07aaaa203a71198a69992f82a05487d70\
+             754ff83437ce857a4e8b703383f1"
+        ).unwrap();
+        assert_eq!(
+            decrypt(&schema, &private_key, &encrypted).unwrap(),
+            b"message digest"
+        );
+    }
+
+    #[test]
+    fn test_sm2() {
+        let message = b"a test phrase";
+
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_sm2p256v1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+
+        let signature = build_signature(
+            &mut rng, &schema, &private_key, DEFAULT_ID, message
+        ).unwrap();
+        assert!(check_signature(
+            &schema, &public_key, DEFAULT_ID, message, &signature
+        ));
+        assert!(!check_signature(
+            &schema, &public_key, b"another id", message, &signature
+        ));
+        assert!(build_signature(
+            &mut rng, &schema, &(schema.order - &Bigi::<4>::from(1)),
+            DEFAULT_ID, message
+        ).is_err());
+
+        let mut encrypted = encrypt(
+            &mut rng, &schema, &public_key, message
+        ).unwrap();
+        assert_eq!(encrypted.len(), 1 + 64 + 32 + message.len());
+        assert_eq!(decrypt(&schema, &private_key, &encrypted).unwrap(),
+                   message);
+
+        let last = encrypted.len() - 1;
+        encrypted[last] ^= 1;
+        assert_eq!(decrypt(&schema, &private_key, &encrypted),
+                   Err("hash mismatch"));
+        assert!(decrypt(&schema, &private_key, &encrypted[..90]).is_err());
+    }
+
+    #[bench]
+    fn bench_build_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_sm2p256v1();
+        let (private_key, _public_key) = schema.generate_pair(&mut rng);
+        b.iter(|| build_signature(
+            &mut rng, &schema, &private_key, DEFAULT_ID, b"a test phrase"
+        ));
+    }
+
+    #[bench]
+    fn bench_check_signature(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_sm2p256v1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let signature = build_signature(
+            &mut rng, &schema, &private_key, DEFAULT_ID, b"a test phrase"
+        ).unwrap();
+        b.iter(|| check_signature(
+            &schema, &public_key, DEFAULT_ID, b"a test phrase", &signature
+        ));
+    }
+
+    #[bench]
+    fn bench_decrypt(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = schemas::load_sm2p256v1();
+        let (private_key, public_key) = schema.generate_pair(&mut rng);
+        let encrypted = encrypt(
+            &mut rng, &schema, &public_key, b"a test phrase"
+        ).unwrap();
+        b.iter(|| decrypt(&schema, &private_key, &encrypted));
+    }
+}
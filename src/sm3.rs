@@ -0,0 +1,176 @@
+//! This module implements SM3 hash function (GB/T 32905-2016) that is used
+//! by SM2 signatures and encryption. The digest is 32 bytes.
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::sm3::{sm3, Sm3};
+//!
+//! let mut hasher = Sm3::new();
+//! hasher.update(b"a test ");
+//! hasher.update(b"phrase");
+//!
+//! assert_eq!(hasher.finalize(), sm3(b"a test phrase"));
+//! ```
+
+/// The size of the digest in bytes.
+pub const SIZE: usize = 32;
+
+const BLOCK_SIZE: usize = 64;
+
+const IV: [u32; 8] = [
+    0x7380166F, 0x4914B2B9, 0x172442D7, 0xDA8A0600,
+    0xA96F30BC, 0x163138AA, 0xE38DEE4D, 0xB0FB0E4E
+];
+
+
+/// Incremental SM3 hasher.
+#[derive(Clone)]
+pub struct Sm3 {
+    state: [u32; 8],
+    buffer: Vec<u8>,
+    length: u64
+}
+
+
+impl Default for Sm3 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+
+impl Sm3 {
+    /// Creates a hasher with the initial state.
+    pub fn new() -> Self {
+        Self {
+            state: IV,
+            buffer: Vec::with_capacity(BLOCK_SIZE),
+            length: 0
+        }
+    }
+
+    /// Feeds the data to the hasher.
+    pub fn update(&mut self, data: &[u8]) {
+        self.length += data.len() as u64;
+        self.buffer.extend_from_slice(data);
+        if self.buffer.len() >= BLOCK_SIZE {
+            let full = self.buffer.len() - self.buffer.len() % BLOCK_SIZE;
+            for block in self.buffer[..full].chunks(BLOCK_SIZE) {
+                compress(&mut self.state, block);
+            }
+            self.buffer.drain(..full);
+        }
+    }
+
+    /// Pads the message and returns the digest.
+    pub fn finalize(mut self) -> [u8; SIZE] {
+        let bit_length = self.length << 3;
+        self.buffer.push(0x80);
+        while self.buffer.len() % BLOCK_SIZE != BLOCK_SIZE - 8 {
+            self.buffer.push(0);
+        }
+        self.buffer.extend_from_slice(&bit_length.to_be_bytes());
+        for block in self.buffer.chunks(BLOCK_SIZE) {
+            compress(&mut self.state, block);
+        }
+
+        let mut digest = [0u8; SIZE];
+        for (chunk, word) in digest.chunks_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+
+/// Calculates SM3 digest of the data.
+pub fn sm3(data: &[u8]) -> [u8; SIZE] {
+    let mut hasher = Sm3::new();
+    hasher.update(data);
+    hasher.finalize()
+}
+
+
+fn p0(x: u32) -> u32 {
+    x ^ x.rotate_left(9) ^ x.rotate_left(17)
+}
+
+
+fn p1(x: u32) -> u32 {
+    x ^ x.rotate_left(15) ^ x.rotate_left(23)
+}
+
+
+fn compress(state: &mut [u32; 8], block: &[u8]) {
+    // Message expansion
+    let mut w = [0u32; 68];
+    for (i, chunk) in block.chunks(4).enumerate() {
+        w[i] = u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
+    }
+    for j in 16..68 {
+        w[j] = p1(w[j - 16] ^ w[j - 9] ^ w[j - 3].rotate_left(15))
+            ^ w[j - 13].rotate_left(7) ^ w[j - 6];
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+
+    for j in 0..64 {
+        let t: u32 = if j < 16 { 0x79CC4519 } else { 0x7A879D8A };
+        let ss1 = a.rotate_left(12).wrapping_add(e)
+            .wrapping_add(t.rotate_left((j % 32) as u32)).rotate_left(7);
+        let ss2 = ss1 ^ a.rotate_left(12);
+        let (ff, gg) = if j < 16 {
+            (a ^ b ^ c, e ^ f ^ g)
+        } else {
+            ((a & b) | (a & c) | (b & c), (e & f) | (!e & g))
+        };
+        let tt1 = ff.wrapping_add(d).wrapping_add(ss2)
+            .wrapping_add(w[j] ^ w[j + 4]);
+        let tt2 = gg.wrapping_add(h).wrapping_add(ss1).wrapping_add(w[j]);
+        d = c;
+        c = b.rotate_left(9);
+        b = a;
+        a = tt1;
+        h = g;
+        g = f.rotate_left(19);
+        f = e;
+        e = p0(tt2);
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, h].iter()) {
+        *s ^= v;
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::utils::bytes_to_hex;
+
+    #[test]
+    fn test_sm3() {
+        assert_eq!(
+            bytes_to_hex(&sm3(b"abc")),
+            "66c7f0f462eeedd9d1f2d46bdc10e4e24167c4875cf2f7a2297da02b8f4ba8e0"
+        );
+        assert_eq!(
+            bytes_to_hex(&sm3(&b"abcd".repeat(16))),
+            "debe9ff92275b8a138604889c18e5a4d6fdb70e5387e5765293dcba39c0c5732"
+        );
+
+        let data: Vec<u8> = (0..200).map(|i| i as u8).collect();
+        let mut hasher = Sm3::new();
+        for chunk in data.chunks(7) {
+            hasher.update(chunk);
+        }
+        assert_eq!(hasher.finalize(), sm3(&data));
+    }
+
+    #[bench]
+    fn bench_sm3(b: &mut Bencher) {
+        let data = [0u8; 1024];
+        b.iter(|| sm3(&data));
+    }
+}
@@ -0,0 +1,354 @@
+//! This module computes the orders of points and the structure of the group
+//! of points on a curve with the known number of points `n`: the group is
+//! `Z_n1 x Z_n2` where `n2` divides `n1` and `p - 1`. The small torsion
+//! points (the points `P` such that `k P = O` for small `k`) are enumerated
+//! to build blocklists of low order points and to test cofactor handling.
+//!
+//! Usage example:
+//! ```rust
+//! use bigi::Bigi;
+//! use bigi_ecc::schemas::load_curve25519;
+//! use bigi_ecc::torsion::{torsion_points, point_order};
+//!
+//! let mut rng = rand::thread_rng();
+//! let schema = load_curve25519();
+//! let n = schema.order * &schema.cofactor;
+//!
+//! // 8 low order points of Curve25519
+//! let points = torsion_points(&mut rng, &schema.curve, &schema.cofactor, &n)
+//!     .unwrap();
+//! assert_eq!(points.len(), 8);
+//! for p in points.iter() {
+//!     assert!(point_order(&schema.curve, p, &n).unwrap() <= schema.cofactor);
+//! }
+//! ```
+use std::collections::HashSet;
+use rand::Rng;
+use bigi::Bigi;
+use crate::base::{Point, CurveTrait};
+use crate::numbers::factorize;
+use crate::counting::{point_key, random_point};
+
+
+/// The bound of small primes for trial division of the number of points.
+const TRIAL_DIVISION_BOUND: u64 = 1 << 16;
+
+/// The maximum number of random points to find the structure of a primary
+/// part of the group, the error probability is about `2^-32`.
+const STRUCTURE_ROUNDS: usize = 32;
+
+/// The maximum number of torsion points that are enumerated.
+pub const TORSION_POINTS_BOUND: u64 = 1 << 16;
+
+
+/// Returns true if `k P = O`, i.e. the point belongs to the `k`-torsion.
+pub fn is_torsion<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, k: &Bigi<N>
+        ) -> bool {
+    curve.mul(p, k) == curve.zero()
+}
+
+
+/// Gets the order of the point, `n` is the number of points on the curve
+/// (or any multiple of the order of the point). `n` is factorized by trial
+/// division, the rest must be prime.
+pub fn point_order<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, n: &Bigi<N>
+        ) -> Result<Bigi<N>, &'static str> {
+    if !is_torsion(curve, p, n) {
+        return Err("order of the point does not divide n");
+    }
+    let factors = factorize(n, TRIAL_DIVISION_BOUND)
+        .ok_or("n is not factorized")?;
+    let mut order = *n;
+    for (q, e) in factors.iter() {
+        for _ in 0..*e {
+            let d = order / q;
+            if !is_torsion(curve, p, &d) {
+                break;
+            }
+            order = d;
+        }
+    }
+    Ok(order)
+}
+
+
+/// Finds the structure of the group of points `Z_n1 x Z_n2` where `n2`
+/// divides `n1`, `n = n1 n2` is the number of points on the curve.
+/// Returns `(n1, n2)`, the group is cyclic if `n2 = 1`.
+pub fn group_structure<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize>(
+            rng: &mut R, curve: &T, n: &Bigi<N>
+        ) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
+    let one = Bigi::<N>::from(1);
+    let mut n1 = one;
+    let mut n2 = one;
+    for (q, a, b) in primary_parts(rng, curve, n)?.iter() {
+        n1 = n1 * &power(q, *a);
+        n2 = n2 * &power(q, *b);
+    }
+    Ok((n1, n2))
+}
+
+
+/// Enumerates all points `P` on the curve such that `k P = O`, `n` is
+/// the number of points on the curve. The zero point goes first. Returns
+/// an error if there are more than `TORSION_POINTS_BOUND` points.
+pub fn torsion_points<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize>(
+            rng: &mut R, curve: &T, k: &Bigi<N>, n: &Bigi<N>
+        ) -> Result<Vec<Point<N>>, &'static str> {
+    let zero = curve.zero();
+    let parts = primary_parts(rng, curve, n)?;
+
+    // The q-torsion subgroup of Z_{q^a} x Z_{q^b} has q^(min(v, a) +
+    // min(v, b)) points where q^v is the largest power of q dividing k
+    let mut subgroups = Vec::new();
+    let mut count = Bigi::<N>::from(1);
+    for (q, a, b) in parts.iter() {
+        let mut v = 0;
+        let mut rest = *k;
+        while v < *a && (rest % q).is_zero() {
+            rest = rest / q;
+            v += 1;
+        }
+        if v > 0 {
+            let size = power(q, v + v.min(*b));
+            count = count * &size;
+            if count > Bigi::<N>::from(TORSION_POINTS_BOUND) {
+                return Err("too many torsion points");
+            }
+            subgroups.push((*q, *a + *b, v, size));
+        }
+    }
+
+    let mut points = vec![zero];
+    for (q, e, v, size) in subgroups.iter() {
+        // Random points of order dividing q^v generate the subgroup
+        let cofactor = *n / &power(q, *e);
+        let mut subgroup = vec![zero];
+        let mut keys: HashSet<Vec<u8>> = HashSet::new();
+        keys.insert(point_key(&zero, &zero));
+        let mut rounds = 0;
+        while Bigi::<N>::from(subgroup.len() as u64) < *size {
+            if rounds == STRUCTURE_ROUNDS {
+                return Err("torsion points are not found");
+            }
+            rounds += 1;
+            let y = curve.mul(&random_point(rng, curve), &cofactor);
+            let j = primary_exponent(curve, &y, q, *e)?;
+            let mut z = y;
+            for _ in *v..j {
+                z = curve.mul(&z, q);
+            }
+            extend_subgroup(curve, &mut subgroup, &mut keys, &z);
+        }
+
+        // The subgroups for different primes have trivial intersection
+        points = points.iter().flat_map(
+            |p| subgroup.iter().map(move |s| curve.add(p, s))
+        ).collect();
+    }
+    Ok(points)
+}
+
+
+/// Finds the structure `Z_{q^a} x Z_{q^b}` of the part of the group for
+/// each prime `q` dividing `n`, returns `(q, a, b)` with `a >= b`. The part
+/// is cyclic if `q^2` does not divide `n` or `q` does not divide `p - 1`,
+/// otherwise `q^a` is the largest order of the part among random points.
+fn primary_parts<R: Rng + ?Sized, T: CurveTrait<N>, const N: usize>(
+            rng: &mut R, curve: &T, n: &Bigi<N>
+        ) -> Result<Vec<(Bigi<N>, usize, usize)>, &'static str> {
+    let m1 = curve.get_modulo() - &Bigi::<N>::from(1);
+    let factors = factorize(n, TRIAL_DIVISION_BOUND)
+        .ok_or("n is not factorized")?;
+    let mut parts = Vec::new();
+    for (q, e) in factors.iter() {
+        if *e == 1 || !(m1 % q).is_zero() {
+            parts.push((*q, *e, 0));
+            continue;
+        }
+        let cofactor = *n / &power(q, *e);
+        let mut a = 0;
+        for _ in 0..STRUCTURE_ROUNDS {
+            let y = curve.mul(&random_point(rng, curve), &cofactor);
+            a = a.max(primary_exponent(curve, &y, q, *e)?);
+            if a == *e {
+                break;
+            }
+        }
+        if 2 * a < *e {
+            return Err("group structure is not found");
+        }
+        parts.push((*q, a, *e - a));
+    }
+    Ok(parts)
+}
+
+
+/// Gets `j` such that the order of the point is `q^j`, `j <= e`.
+fn primary_exponent<T: CurveTrait<N>, const N: usize>(
+            curve: &T, p: &Point<N>, q: &Bigi<N>, e: usize
+        ) -> Result<usize, &'static str> {
+    let zero = curve.zero();
+    let mut x = *p;
+    let mut j = 0;
+    while x != zero {
+        if j == e {
+            return Err("n is not the number of points");
+        }
+        x = curve.mul(&x, q);
+        j += 1;
+    }
+    Ok(j)
+}
+
+
+/// Extends the subgroup by the point `z`: the cosets `S + i z` are added
+/// until `i z` belongs to `S`.
+fn extend_subgroup<T: CurveTrait<N>, const N: usize>(
+            curve: &T, subgroup: &mut Vec<Point<N>>,
+            keys: &mut HashSet<Vec<u8>>, z: &Point<N>
+        ) {
+    let zero = curve.zero();
+    let base = subgroup.clone();
+    let mut cur = *z;
+    while !keys.contains(&point_key(&cur, &zero)) {
+        for s in base.iter() {
+            let p = curve.add(s, &cur);
+            keys.insert(point_key(&p, &zero));
+            subgroup.push(p);
+        }
+        cur = curve.add(&cur, z);
+    }
+}
+
+
+fn power<const N: usize>(q: &Bigi<N>, e: usize) -> Bigi<N> {
+    (0..e).fold(Bigi::<N>::from(1), |acc, _| acc * q)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::point;
+    use crate::weierstrass::WeierstrassCurve;
+    use crate::schemas::{load_secp256k1, load_curve25519};
+
+    // y^2 = x^3 + 2 x + 4 over F_1048573 has the group Z_174876 x Z_6
+    fn non_cyclic_curve() -> (WeierstrassCurve<1>, Bigi<1>) {
+        let curve = WeierstrassCurve::<1> {
+            a: Bigi::<1>::from(2),
+            b: Bigi::<1>::from(4),
+            m: Bigi::<1>::from(1048573)
+        };
+        (curve, Bigi::<1>::from(1049256))
+    }
+
+    #[test]
+    fn test_point_order() {
+        let schema = load_secp256k1();
+        assert_eq!(point_order(&schema.curve, &schema.generator,
+                               &schema.order),
+                   Ok(schema.order));
+        assert_eq!(point_order(&schema.curve, &schema.curve.zero(),
+                               &schema.order),
+                   Ok(Bigi::<4>::from(1)));
+
+        let (curve, n) = non_cyclic_curve();
+        let p = point!(Bigi::<1>::from(1), Bigi::<1>::from(278441));
+        assert!(curve.check(&p));
+        assert_eq!(point_order(&curve, &p, &n), Ok(Bigi::<1>::from(174876)));
+        assert!(is_torsion(&curve, &p, &Bigi::<1>::from(174876)));
+        assert!(!is_torsion(&curve, &p, &Bigi::<1>::from(87438)));
+        assert_eq!(point_order(&curve, &p, &Bigi::<1>::from(87438)),
+                   Err("order of the point does not divide n"));
+    }
+
+    #[test]
+    fn test_group_structure() {
+        let mut rng = rand::thread_rng();
+        let (curve, n) = non_cyclic_curve();
+        assert_eq!(group_structure(&mut rng, &curve, &n),
+                   Ok((Bigi::<1>::from(174876), Bigi::<1>::from(6))));
+
+        // y^2 = x^3 + 2 x + 11 over F_1048573 is cyclic
+        let curve = WeierstrassCurve::<1> {
+            a: Bigi::<1>::from(2),
+            b: Bigi::<1>::from(11),
+            m: Bigi::<1>::from(1048573)
+        };
+        let n = Bigi::<1>::from(1048736);
+        assert_eq!(group_structure(&mut rng, &curve, &n),
+                   Ok((n, Bigi::<1>::from(1))));
+
+        let schema = load_curve25519();
+        let n = schema.order * &schema.cofactor;
+        assert_eq!(group_structure(&mut rng, &schema.curve, &n),
+                   Ok((n, Bigi::<4>::from(1))));
+    }
+
+    #[test]
+    fn test_torsion_points() {
+        let mut rng = rand::thread_rng();
+        let (curve, n) = non_cyclic_curve();
+        for &(k, count) in [(1, 1), (2, 4), (3, 9), (6, 36), (12, 72),
+                            (7, 1)].iter() {
+            let k = Bigi::<1>::from(k);
+            let points = torsion_points(&mut rng, &curve, &k, &n).unwrap();
+            assert_eq!(points.len(), count);
+            assert_eq!(points[0], curve.zero());
+            let keys: HashSet<Vec<u8>> = points.iter().map(
+                |p| point_key(p, &curve.zero())
+            ).collect();
+            assert_eq!(keys.len(), count);
+            for p in points.iter() {
+                assert!(curve.check(p));
+                assert!(is_torsion(&curve, p, &k));
+            }
+        }
+        assert_eq!(torsion_points(&mut rng, &curve, &n, &n),
+                   Err("too many torsion points"));
+    }
+
+    #[test]
+    fn test_curve25519_low_order_points() {
+        let mut rng = rand::thread_rng();
+        let schema = load_curve25519();
+        let n = schema.order * &schema.cofactor;
+        let points = torsion_points(&mut rng, &schema.curve,
+                                    &schema.cofactor, &n).unwrap();
+        assert_eq!(points.len(), 8);
+
+        // 1 point of order 1 and 2, 2 points of order 4, 4 points of order 8
+        let mut orders = [0; 9];
+        for p in points.iter() {
+            let order = point_order(&schema.curve, p, &n).unwrap();
+            orders[order.to_bytes()[0] as usize] += 1;
+            assert_eq!(schema.clear_cofactor(p), schema.curve.zero());
+            assert!(schema.validate_point(p).is_err());
+        }
+        assert_eq!(orders, [0, 1, 1, 0, 2, 0, 0, 0, 4]);
+
+        // The blocklist of x coordinates
+        let blocklist = [
+            Bigi::<4>::from(0),
+            Bigi::<4>::from(1),
+            Bigi::<4>::from_hex("0xB8495F16056286FDB1329CEB8D09DA6AC49FF1FAE35616AEB8413B7C7AEBE0"),
+            Bigi::<4>::from_hex("0x57119FD0DD4E22D8868E1C58C45C44045BEF839C55B1D0B1248C50A3BC959C5F")
+        ];
+        for x in blocklist.iter() {
+            assert!(points[1..].iter().any(|p| p.x == *x));
+        }
+        assert!(points[1..].iter().all(|p| blocklist.contains(&p.x)));
+    }
+
+    #[bench]
+    fn bench_point_order(bencher: &mut Bencher) {
+        let schema = load_curve25519();
+        let n = schema.order * &schema.cofactor;
+        bencher.iter(|| point_order(&schema.curve, &schema.generator, &n));
+    }
+}
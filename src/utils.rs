@@ -0,0 +1,155 @@
+//! This module contains helper functions to convert integers from and to
+//! byte strings of arbitrary length and to work with hex strings of bytes.
+use rand::Rng;
+use bigi::Bigi;
+use bigi::prime::{add_mod, mul_mod};
+
+
+/// Creates an integer from little-endian bytes of any length. Missing high
+/// bytes are considered as zeros, extra high bytes are ignored.
+pub fn bigi_from_le_bytes<const N: usize>(bytes: &[u8]) -> Bigi<N> {
+    let size = bytes.len().min(N << 3);
+    let mut bytes_aligned = vec![0u8; N << 3];
+    bytes_aligned[..size].copy_from_slice(&bytes[..size]);
+    Bigi::<N>::from_bytes(&bytes_aligned)
+}
+
+
+/// Converts the integer to exactly `len` little-endian bytes.
+pub fn bigi_to_le_bytes<const N: usize>(x: &Bigi<N>, len: usize) -> Vec<u8> {
+    let mut bytes = x.to_bytes();
+    bytes.resize(len, 0);
+    bytes
+}
+
+
+/// Interprets little-endian bytes of any length as an integer modulo `m`.
+pub fn bigi_from_le_bytes_mod<const N: usize>(bytes: &[u8],
+                                              m: &Bigi<N>) -> Bigi<N> {
+    let base = Bigi::<N>::from(256);
+    let mut res = Bigi::<N>::from(0);
+    for byte in bytes.iter().rev() {
+        res = add_mod(
+            &mul_mod(&res, &base, m),
+            &Bigi::<N>::from(*byte as u64), m
+        );
+    }
+    res
+}
+
+
+/// Creates an integer from big-endian bytes of any length. Missing high
+/// bytes are considered as zeros, extra high bytes are ignored.
+pub fn bigi_from_be_bytes<const N: usize>(bytes: &[u8]) -> Bigi<N> {
+    let bytes_le: Vec<u8> = bytes.iter().rev().cloned().collect();
+    bigi_from_le_bytes(&bytes_le)
+}
+
+
+/// Converts the integer to exactly `len` big-endian bytes.
+pub fn bigi_to_be_bytes<const N: usize>(x: &Bigi<N>, len: usize) -> Vec<u8> {
+    let mut bytes = bigi_to_le_bytes(x, len);
+    bytes.reverse();
+    bytes
+}
+
+
+/// Interprets big-endian bytes of any length as an integer modulo `m`.
+pub fn bigi_from_be_bytes_mod<const N: usize>(bytes: &[u8],
+                                              m: &Bigi<N>) -> Bigi<N> {
+    let bytes_le: Vec<u8> = bytes.iter().rev().cloned().collect();
+    bigi_from_le_bytes_mod(&bytes_le, m)
+}
+
+
+/// Converts a hex string like `"0a1B"` to bytes.
+pub fn bytes_from_hex(hex: &str) -> Result<Vec<u8>, &'static str> {
+    if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err("invalid hex string");
+    }
+    if !hex.len().is_multiple_of(2) {
+        return Err("odd length of hex string");
+    }
+    (0..hex.len()).step_by(2).map(|i| {
+        u8::from_str_radix(&hex[i..(i + 2)], 16)
+            .map_err(|_| "invalid hex string")
+    }).collect()
+}
+
+
+/// Converts bytes to a lowercase hex string.
+pub fn bytes_to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+
+/// Generates a random nonzero weight for batch verification. It is 128 bits
+/// long or shorter if the order is smaller.
+pub fn gen_random_weight<R: Rng + ?Sized, const N: usize>(
+            rng: &mut R, order: &Bigi<N>
+        ) -> Bigi<N> {
+    let bits = 128.min(order.bit_length() - 1);
+    loop {
+        let z = Bigi::<N>::gen_random(rng, bits, false);
+        if !z.is_zero() {
+            return z;
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bigi::bigi;
+
+    #[test]
+    fn test_le_bytes() {
+        assert_eq!(bigi_from_le_bytes::<4>(&[2, 1]), bigi![4; 258]);
+        assert_eq!(bigi_to_le_bytes(&bigi![4; 258], 3), vec![2, 1, 0]);
+        assert_eq!(bigi_to_le_bytes(&bigi![4; 258], 40).len(), 40);
+    }
+
+    #[test]
+    fn test_le_bytes_mod() {
+        assert_eq!(bigi_from_le_bytes_mod(&[2, 1], &bigi![4; 97]),
+                   bigi![4; 64]);
+        assert_eq!(bigi_from_le_bytes_mod(&[0u8; 100], &bigi![4; 97]),
+                   bigi![4; 0]);
+        assert_eq!(bigi_from_le_bytes_mod(&[255u8; 64], &bigi![4; 1000003]),
+                   bigi![4; 835586]);
+    }
+
+    #[test]
+    fn test_be_bytes() {
+        assert_eq!(bigi_from_be_bytes::<4>(&[1, 2]), bigi![4; 258]);
+        assert_eq!(bigi_to_be_bytes(&bigi![4; 258], 3), vec![0, 1, 2]);
+        assert_eq!(bigi_from_be_bytes_mod(&[1, 2], &bigi![4; 97]),
+                   bigi![4; 64]);
+    }
+
+    #[test]
+    fn test_hex() {
+        assert_eq!(bytes_from_hex("0a1Bff"), Ok(vec![10, 27, 255]));
+        assert!(bytes_from_hex("0a1").is_err());
+        assert!(bytes_from_hex("0x").is_err());
+        assert_eq!(bytes_from_hex("aé"), Err("invalid hex string"));
+        assert_eq!(bytes_from_hex("aéa"), Err("invalid hex string"));
+        assert_eq!(bytes_from_hex("+f"), Err("invalid hex string"));
+        assert_eq!(bytes_to_hex(&[10, 27, 255]), "0a1bff");
+    }
+
+    #[test]
+    fn test_gen_random_weight() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let z = gen_random_weight(&mut rng, &bigi![4; 97]);
+            assert!(!z.is_zero());
+            assert!(z < bigi![4; 97]);
+        }
+        let z = gen_random_weight(&mut rng, &Bigi::<4>::from_hex(
+            "FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141"
+        ));
+        assert!(z.bit_length() <= 128);
+    }
+}
@@ -0,0 +1,338 @@
+//! This module implements the validation of schema parameters in the spirit
+//! of [SafeCurves](https://safecurves.cr.yp.to): `Schema::validate` checks
+//! that the numbers really define a group of the stated order and reports
+//! the properties that make the discrete logarithm easier (MOV attack,
+//! anomalous curves, weak twist, small CM discriminant).
+//!
+//! Usage example:
+//! ```rust
+//! use bigi_ecc::schemas::{load_curve25519, load_secp256k1};
+//!
+//! let report = load_curve25519().validate();
+//! assert!(report.is_valid());
+//! assert!(report.is_safe());
+//!
+//! // secp256k1 has CM discriminant -3
+//! let report = load_secp256k1().validate();
+//! assert!(report.is_valid());
+//! assert_eq!(report.cm_discriminant_bits, 2);
+//! ```
+use bigi::Bigi;
+use bigi::prime::mul_mod;
+use crate::base::CurveTrait;
+use crate::schemas::Schema;
+use crate::numbers::{is_prime, is_square, small_primes, div_small};
+use crate::utils::bigi_from_le_bytes;
+
+
+/// Integers of this size hold products of two numbers of any schema.
+type Wide = Bigi<20>;
+
+
+/// The maximum embedding degree that is searched for.
+pub const EMBEDDING_DEGREE_BOUND: usize = 100;
+
+/// The bound of small primes for trial division.
+const TRIAL_DIVISION_BOUND: u64 = 1 << 16;
+
+/// The minimum size of the largest prime factor of the twist order and
+/// the CM discriminant in bits for a safe curve.
+const SAFE_TWIST_BITS: usize = 200;
+const SAFE_CM_DISCRIMINANT_BITS: usize = 100;
+
+
+/// A report of the schema validation.
+#[derive(Debug)]
+pub struct ValidationReport<const N: usize> {
+    /// The modulo of the field is a (probable) prime.
+    pub modulo_is_prime: bool,
+    /// The order of the generator is a (probable) prime.
+    pub order_is_prime: bool,
+    /// The discriminant of the curve is nonzero.
+    pub nonsingular: bool,
+    /// The generator lies on the curve, differs from the zero point and
+    /// `order * G = O`.
+    pub generator_is_valid: bool,
+    /// The number of points `order * cofactor` is within Hasse bound
+    /// `|p + 1 - order * cofactor| <= 2 sqrt(p)`.
+    pub within_hasse_bound: bool,
+    /// The absolute value of the trace of Frobenius
+    /// `t = p + 1 - order * cofactor`, it makes sense within Hasse bound.
+    pub trace: Bigi<N>,
+    pub trace_is_negative: bool,
+    /// The smallest `k` such that `order` divides `p^k - 1`, `None` if it
+    /// is greater than `EMBEDDING_DEGREE_BOUND`. A small degree allows
+    /// MOV attack.
+    pub embedding_degree: Option<usize>,
+    /// The order is equal to the modulo, that allows Smart's attack.
+    pub anomalous: bool,
+    /// The bit length of the largest prime factor of the twist order
+    /// `2 p + 2 - order * cofactor`, `None` if it is not found by trial
+    /// division and the primality test of the rest.
+    pub twist_prime_bits: Option<usize>,
+    /// The bit length of the absolute value of CM field discriminant that
+    /// is the square-free part of `t^2 - 4 p` (multiplied by 4 if it is
+    /// not `1` modulo 4). If the square part has large prime factors that
+    /// are not found, it is an upper bound.
+    pub cm_discriminant_bits: usize
+}
+
+
+impl<const N: usize> ValidationReport<N> {
+    /// Returns true if the parameters define a correct schema.
+    pub fn is_valid(&self) -> bool {
+        self.modulo_is_prime && self.order_is_prime && self.nonsingular &&
+            self.generator_is_valid && self.within_hasse_bound
+    }
+
+    /// Returns true if the schema is valid and there are no known weakness:
+    /// no small embedding degree, not anomalous, the twist is secure
+    /// (its largest prime factor has at least 200 bits) and the CM
+    /// discriminant has more than 100 bits.
+    pub fn is_safe(&self) -> bool {
+        self.is_valid() && self.embedding_degree.is_none() &&
+            !self.anomalous &&
+            self.twist_prime_bits.is_some_and(|b| b >= SAFE_TWIST_BITS) &&
+            self.cm_discriminant_bits > SAFE_CM_DISCRIMINANT_BITS
+    }
+}
+
+
+impl<T, const N: usize> Schema<T, N> where T: CurveTrait<N> {
+    /// Validates the parameters of the schema and reports the properties
+    /// that affect the security.
+    pub fn validate(&self) -> ValidationReport<N> {
+        let m = self.curve.get_modulo();
+        let zero = self.curve.zero();
+        let g = &self.generator;
+
+        // t = p + 1 - order * cofactor
+        let p1 = wide(&m) + &Wide::from(1);
+        let size = wide(&self.order) * &wide(&self.cofactor);
+        let (trace, trace_is_negative) = if p1 >= size {
+            (p1 - &size, false)
+        } else {
+            (size - &p1, true)
+        };
+        let trace2 = trace * &trace;
+        let four_p = wide(&m) << 2;
+        let within_hasse_bound = trace2 <= four_p;
+
+        let primes = small_primes(TRIAL_DIVISION_BOUND);
+        let (twist_prime_bits, cm_discriminant_bits) = if within_hasse_bound {
+            let twist = if trace_is_negative {
+                p1 - &trace
+            } else {
+                p1 + &trace
+            };
+            (largest_prime_bits(&twist, &primes),
+             cm_discriminant_bits(&(four_p - &trace2), &primes))
+        } else {
+            (None, 0)
+        };
+
+        ValidationReport {
+            modulo_is_prime: is_prime(&m),
+            order_is_prime: is_prime(&self.order),
+            nonsingular: !self.curve.is_singular(),
+            generator_is_valid: *g != zero && self.curve.check(g) &&
+                self.curve.mul(g, &self.order) == zero,
+            within_hasse_bound,
+            trace: narrow(&trace),
+            trace_is_negative,
+            embedding_degree: embedding_degree(&m, &self.order),
+            anomalous: self.order == m,
+            twist_prime_bits,
+            cm_discriminant_bits
+        }
+    }
+}
+
+
+fn wide<const N: usize>(x: &Bigi<N>) -> Wide {
+    bigi_from_le_bytes(&x.to_bytes())
+}
+
+
+fn narrow<const N: usize>(x: &Wide) -> Bigi<N> {
+    bigi_from_le_bytes(&x.to_bytes())
+}
+
+
+/// Finds the smallest `k <= EMBEDDING_DEGREE_BOUND` such that `p^k = 1`
+/// modulo `n`.
+fn embedding_degree<const N: usize>(p: &Bigi<N>,
+                                    n: &Bigi<N>) -> Option<usize> {
+    let one = Bigi::<N>::from(1);
+    if *n <= one {
+        return None;
+    }
+    let pn = *p % n;
+    let mut x = pn;
+    for k in 1..=EMBEDDING_DEGREE_BOUND {
+        if x == one {
+            return Some(k);
+        }
+        x = mul_mod(&x, &pn, n);
+    }
+    None
+}
+
+
+/// Removes all the factors `q` from `x`, returns the exponent.
+fn remove_factor(x: &mut Wide, q: u64) -> usize {
+    let mut e = 0;
+    loop {
+        let (d, r) = div_small(x, q);
+        if r != 0 {
+            return e;
+        }
+        *x = d;
+        e += 1;
+    }
+}
+
+
+/// Gets the bit length of the largest prime factor of `x`.
+fn largest_prime_bits(x: &Wide, primes: &[u64]) -> Option<usize> {
+    if x.is_zero() {
+        return None;
+    }
+    let mut rest = *x;
+    let mut bits = 0;
+    for &q in primes.iter() {
+        if remove_factor(&mut rest, q) > 0 {
+            bits = 64 - q.leading_zeros() as usize;
+        }
+    }
+    if rest == Wide::from(1) {
+        Some(bits)
+    } else if is_prime(&rest) {
+        Some(rest.bit_length())
+    } else {
+        None
+    }
+}
+
+
+/// Gets the bit length of CM field discriminant by `4 p - t^2`.
+fn cm_discriminant_bits(v: &Wide, primes: &[u64]) -> usize {
+    if v.is_zero() {
+        return 0;
+    }
+
+    // The product of small primes with odd exponents and the rest
+    let mut rest = *v;
+    let mut free = Wide::from(1);
+    for &q in primes.iter() {
+        if remove_factor(&mut rest, q) % 2 == 1 {
+            free = free * &Wide::from(q);
+        }
+    }
+    if !is_square(&rest) {
+        free = free * &rest;
+    }
+
+    if div_small(&free, 4).1 == 3 {
+        free.bit_length()
+    } else {
+        (free << 2).bit_length()
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::point;
+    use crate::base::Point;
+    use crate::weierstrass::WeierstrassCurve;
+    use crate::schemas::{load_secp256k1, load_secp256r1, load_curve25519,
+                         load_curve1174, load_fp254bnb};
+
+    #[test]
+    fn test_secp256k1() {
+        let report = load_secp256k1().validate();
+        assert!(report.is_valid());
+        assert_eq!(report.trace, Bigi::<4>::from_hex("0x14551231950B75FC4402DA1722FC9BAEF"));
+        assert!(!report.trace_is_negative);
+        assert_eq!(report.embedding_degree, None);
+        assert!(!report.anomalous);
+        assert_eq!(report.twist_prime_bits, Some(220));
+        assert_eq!(report.cm_discriminant_bits, 2);
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn test_safe_curves() {
+        let report = load_curve25519().validate();
+        assert!(report.is_valid());
+        assert!(report.trace_is_negative);
+        assert_eq!(report.twist_prime_bits, Some(253));
+        assert_eq!(report.cm_discriminant_bits, 255);
+        assert!(report.is_safe());
+
+        assert!(load_curve1174().validate().is_safe());
+        assert!(load_secp256r1().validate().is_valid());
+    }
+
+    #[test]
+    fn test_pairing_friendly() {
+        let report = load_fp254bnb().validate();
+        assert!(report.is_valid());
+        assert_eq!(report.embedding_degree, Some(12));
+        assert!(!report.is_safe());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut schema = load_secp256k1();
+        schema.generator = point!(schema.generator.x, schema.generator.x);
+        let report = schema.validate();
+        assert!(!report.generator_is_valid);
+        assert!(!report.is_valid());
+
+        let mut schema = load_secp256k1();
+        schema.order = schema.order - &Bigi::<4>::from(1);
+        let report = schema.validate();
+        assert!(!report.order_is_prime);
+        assert!(!report.generator_is_valid);
+
+        let mut schema = load_secp256k1();
+        schema.cofactor = Bigi::<4>::from(2);
+        assert!(!schema.validate().within_hasse_bound);
+
+        let mut schema = load_secp256k1();
+        schema.curve.b = Bigi::<4>::from(0);
+        assert!(!schema.validate().nonsingular);
+    }
+
+    #[test]
+    fn test_anomalous() {
+        // y^2 = x^3 + x + 32 over F_101 has 101 points
+        let schema = Schema {
+            bits: 7,
+            title: "anomalous",
+            curve: WeierstrassCurve::<1> {
+                a: Bigi::<1>::from(1),
+                b: Bigi::<1>::from(32),
+                m: Bigi::<1>::from(101)
+            },
+            order: Bigi::<1>::from(101),
+            cofactor: Bigi::<1>::from(1),
+            generator: point!(Bigi::<1>::from(4), Bigi::<1>::from(10))
+        };
+        let report = schema.validate();
+        assert!(report.is_valid());
+        assert!(report.anomalous);
+        assert_eq!(report.trace, Bigi::<1>::from(1));
+        assert!(!report.is_safe());
+    }
+
+    #[bench]
+    fn bench_validate_secp256k1(bencher: &mut Bencher) {
+        let schema = load_secp256k1();
+        bencher.iter(|| schema.validate());
+    }
+}
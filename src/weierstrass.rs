@@ -1,48 +1,158 @@
 //! This module implements [Weierstrass curve](https://en.wikipedia.org/wiki/Elliptic_curve)
-//! that is defined by the equation `y^2 = x^3 + A x + B`.
+//! that is defined by the equation `y^2 = x^3 + A x + B`. The coordinates
+//! are integers modulo a prime by default, but they can belong to any field
+//! implementing `FieldTrait`, for example `fields::Fp2`.
 use bigi::Bigi;
-use bigi::prime::{add_mod, sub_mod, mul_mod, div_mod, sqrt_mod};
-use crate::{point, point_zero};
-use crate::base::{Point, CurveTrait};
+use crate::base::{GenericPoint, CurveTrait, FieldTrait};
+
+
+/// A point in Jacobian coordinates `(X, Y, Z)` that means `(X/Z^2, Y/Z^3)`,
+/// `Z = 0` for the zero point.
+type Jacobian<F> = (F, F, F);
 
 
 /// Weierstrass curve type.
 #[derive(Copy, Clone)]
-pub struct WeierstrassCurve<const N: usize> {
-    pub a: Bigi<N>,
-    pub b: Bigi<N>,
+pub struct WeierstrassCurve<const N: usize, F = Bigi<N>> {
+    pub a: F,
+    pub b: F,
     pub m: Bigi<N>
 }
 
 
-impl<const N: usize> WeierstrassCurve<N> {
-    fn left(&self, y: &Bigi<N>) -> Bigi<N> {
-        mul_mod(&y, &y, &self.m)
+impl<const N: usize, F: FieldTrait<N>> WeierstrassCurve<N, F> {
+    fn left(&self, y: &F) -> F {
+        y.square(&self.m)
+    }
+
+    fn right(&self, x: &F) -> F {
+        let m = &self.m;
+        x.square(m).add(&self.a, m).mul(x, m).add(&self.b, m)
+    }
+
+    fn constant(&self, k: u64) -> F {
+        F::from_bigi(&Bigi::<N>::from(k))
+    }
+
+    /// Returns true if `A = -3` (like in NIST curves), so the doubling can
+    /// use the faster formula.
+    pub fn is_a_minus_3(&self) -> bool {
+        self.a.add(&self.constant(3), &self.m).is_zero()
     }
 
-    fn right(&self, x: &Bigi<N>) -> Bigi<N> {
-        add_mod(
-            &mul_mod(
-                &add_mod(
-                    &mul_mod(&x, &x, &self.m),
-                    &self.a, &self.m
-                ), &x, &self.m
-            ), &self.b, &self.m
-        )
+    fn affine_to_jacobian(&self, p: &GenericPoint<F>) -> Jacobian<F> {
+        if p.is_zero {
+            (self.constant(1), self.constant(1), F::zero())
+        } else {
+            (p.x, p.y, self.constant(1))
+        }
+    }
+
+    fn jacobian_to_affine(&self, p: &Jacobian<F>) -> GenericPoint<F> {
+        let (x, y, z) = p;
+        if z.is_zero() {
+            return self.zero();
+        }
+        let m = &self.m;
+        let zi = z.inv(m);
+        let zi2 = zi.square(m);
+        GenericPoint::new(x.mul(&zi2, m), y.mul(&zi2.mul(&zi, m), m))
+    }
+
+    fn double_jacobian(&self, p: &Jacobian<F>, a_minus_3: bool) -> Jacobian<F> {
+        let (x, y, z) = p;
+        if z.is_zero() || y.is_zero() {
+            return self.affine_to_jacobian(&self.zero());
+        }
+
+        let m = &self.m;
+        let two = self.constant(2);
+        let three = self.constant(3);
+        let eight = self.constant(8);
+
+        let zz = z.square(m);
+        let yy = y.square(m);
+
+        // S = 4 X Y^2
+        let s = x.mul(&yy, m).mul(&self.constant(4), m);
+
+        let alpha = if a_minus_3 {
+            // alpha = 3 X^2 - 3 Z^4 = 3 (X - Z^2) (X + Z^2)
+            x.sub(&zz, m).mul(&x.add(&zz, m), m).mul(&three, m)
+        } else {
+            // alpha = 3 X^2 + A Z^4
+            x.square(m).mul(&three, m).add(&self.a.mul(&zz.square(m), m), m)
+        };
+
+        // X3 = alpha^2 - 2 S
+        let x3 = alpha.square(m).sub(&s.mul(&two, m), m);
+
+        // Y3 = alpha (S - X3) - 8 Y^4
+        let y3 = alpha.mul(&s.sub(&x3, m), m)
+            .sub(&yy.square(m).mul(&eight, m), m);
+
+        // Z3 = 2 Y Z
+        let z3 = y.mul(z, m).mul(&two, m);
+
+        (x3, y3, z3)
+    }
+
+    fn add_jacobian_affine(&self, p: &Jacobian<F>, q: &GenericPoint<F>,
+                           a_minus_3: bool) -> Jacobian<F> {
+        if q.is_zero {
+            return *p;
+        }
+        let (x1, y1, z1) = p;
+        if z1.is_zero() {
+            return self.affine_to_jacobian(q);
+        }
+
+        let m = &self.m;
+
+        // U2 = Qx Z^2, S2 = Qy Z^3
+        let zz = z1.square(m);
+        let u2 = q.x.mul(&zz, m);
+        let s2 = q.y.mul(&zz.mul(z1, m), m);
+
+        let h = u2.sub(x1, m);
+        let r = s2.sub(y1, m);
+
+        if h.is_zero() {
+            if r.is_zero() {
+                return self.double_jacobian(p, a_minus_3);
+            }
+            return self.affine_to_jacobian(&self.zero());
+        }
+
+        let hh = h.square(m);
+        let hhh = hh.mul(&h, m);
+        let v = x1.mul(&hh, m);
+
+        // X3 = r^2 - H^3 - 2 V
+        let x3 = r.square(m).sub(&hhh, m).sub(&v.mul(&self.constant(2), m), m);
+
+        // Y3 = r (V - X3) - Y1 H^3
+        let y3 = r.mul(&v.sub(&x3, m), m).sub(&y1.mul(&hhh, m), m);
+
+        // Z3 = Z1 H
+        let z3 = z1.mul(&h, m);
+
+        (x3, y3, z3)
     }
 }
 
 
-impl<const N: usize> CurveTrait<N> for WeierstrassCurve<N> {
+impl<const N: usize, F: FieldTrait<N>> CurveTrait<N, F>
+        for WeierstrassCurve<N, F> {
     fn get_modulo(&self) -> Bigi<N> {
         self.m
     }
 
-    fn zero(&self) -> Point<N> {
-        point_zero!(N)
+    fn zero(&self) -> GenericPoint<F> {
+        GenericPoint { x: F::zero(), y: F::zero(), is_zero: true }
     }
 
-    fn check(&self, p: &Point<N>) -> bool {
+    fn check(&self, p: &GenericPoint<F>) -> bool {
         if p.is_zero {
             true
         } else {
@@ -50,17 +160,28 @@ impl<const N: usize> CurveTrait<N> for WeierstrassCurve<N> {
         }
     }
 
-    fn find_y(&self, x: &Bigi<N>) -> Result<(Bigi<N>, Bigi<N>), &'static str> {
-        let y2 = self.right(&x);
-        let roots = sqrt_mod(&y2, &self.m)?;
+    fn is_singular(&self) -> bool {
+        // 4 A^3 + 27 B^2 = 0
+        let m = &self.m;
+        self.a.square(m).mul(&self.a, m).mul(&self.constant(4), m)
+            .add(&self.b.square(m).mul(&self.constant(27), m), m)
+            .is_zero()
+    }
+
+    fn find_y(&self, x: &F) -> Result<(F, F), &'static str> {
+        let y2 = self.right(x);
+        let roots = y2.sqrt(&self.m)?;
         Ok(roots)
     }
 
-    fn inv(&self, p: &Point<N>) -> Point<N> {
-        point!(p.x, self.m - &p.y)
+    fn inv(&self, p: &GenericPoint<F>) -> GenericPoint<F> {
+        if p.is_zero {
+            return *p;
+        }
+        GenericPoint::new(p.x, p.y.neg(&self.m))
     }
 
-    fn add(&self, p: &Point<N>, q: &Point<N>) -> Point<N> {
+    fn add(&self, p: &GenericPoint<F>, q: &GenericPoint<F>) -> GenericPoint<F> {
         if q.is_zero {
             return *p;
         }
@@ -68,48 +189,47 @@ impl<const N: usize> CurveTrait<N> for WeierstrassCurve<N> {
             return *q;
         }
         if (p.x == q.x) && ((p.y != q.y) || p.y.is_zero()) {
-            return point_zero!(N);
+            return self.zero();
         }
 
+        let m = &self.m;
         let alpha = {
             if p.x == q.x {
                 // alpha = (3 * x^2 + a) / (2y)
-                div_mod(
-                    &add_mod(
-                        &mul_mod(
-                            &mul_mod(&p.x, &p.x, &self.m),
-                            &Bigi::<N>::from(3), &self.m
-                        ), &self.a, &self.m
-                    ),
-                    &mul_mod(&p.y, &Bigi::<N>::from(2), &self.m),
-                    &self.m
-                )
+                p.x.square(m).mul(&self.constant(3), m).add(&self.a, m)
+                    .mul(&p.y.mul(&self.constant(2), m).inv(m), m)
             } else {
                 // alpha = (Py - Qy) / (Px - Qx)
-                div_mod(
-                    &sub_mod(&p.y, &q.y, &self.m),
-                    &sub_mod(&p.x, &q.x, &self.m),
-                    &self.m
-                )
+                p.y.sub(&q.y, m).mul(&p.x.sub(&q.x, m).inv(m), m)
             }
         };
 
         // Rx := alpha^2 - (Px + Qx)
-        let x = sub_mod(
-            &mul_mod(&alpha, &alpha, &self.m),
-            &add_mod(&p.x, &q.x, &self.m),
-            &self.m
-        );
+        let x = alpha.square(m).sub(&p.x.add(&q.x, m), m);
 
         // Ry := (Qx - Rx) * alpha - Qy
-        let y = sub_mod(
-            &mul_mod(
-                &sub_mod(&q.x, &x, &self.m),
-                &alpha, &self.m
-            ), &q.y, &self.m
-        );
+        let y = q.x.sub(&x, m).mul(&alpha, m).sub(&q.y, m);
 
-        point!(x, y)
+        GenericPoint::new(x, y)
+    }
+
+    fn double(&self, p: &GenericPoint<F>) -> GenericPoint<F> {
+        let p = self.affine_to_jacobian(p);
+        self.jacobian_to_affine(&self.double_jacobian(&p, self.is_a_minus_3()))
+    }
+
+    fn mul(&self, p: &GenericPoint<F>, k: &Bigi<N>) -> GenericPoint<F> {
+        // Double-and-add in Jacobian coordinates, so only one inversion
+        // is needed at the end.
+        let a_minus_3 = self.is_a_minus_3();
+        let mut res = self.affine_to_jacobian(&self.zero());
+        for i in (0..k.bit_length()).rev() {
+            res = self.double_jacobian(&res, a_minus_3);
+            if k.get_bit(i) {
+                res = self.add_jacobian_affine(&res, p, a_minus_3);
+            }
+        }
+        self.jacobian_to_affine(&res)
     }
 }
 
@@ -118,8 +238,13 @@ impl<const N: usize> CurveTrait<N> for WeierstrassCurve<N> {
 mod tests {
     use super::*;
     use bigi::bigi;
+    use bigi::prime::mul_mod;
+    use crate::{point, point_zero};
+    use crate::base::Point;
+    use crate::fields::Fp2;
     use crate::point_simple;
-    use crate::schemas::{load_secp256k1, load_fp254bnb};
+    use crate::schemas::{self, Schema, load_secp256k1, load_fp254bnb,
+                         load_secp256r1, load_secp384r1, load_secp521r1};
     use test::Bencher;
 
     #[test]
@@ -134,6 +259,15 @@ mod tests {
         assert_eq!(curve.check(&point_zero!(4)), true);
         assert_eq!(curve.check(&point_simple!(4; 80, 86)), false);
         assert_eq!(curve.check(&point_simple!(4; 30, 0)), true);
+        assert!(!curve.is_singular());
+
+        // y^2 = x^3 - 3 x + 2 = (x - 1)^2 (x + 2)
+        let curve = WeierstrassCurve {
+            a: bigi![4; 94],
+            b: bigi![4; 2],
+            m: bigi![4; 97]
+        };
+        assert!(curve.is_singular());
     }
 
     #[test]
@@ -206,6 +340,178 @@ mod tests {
             &point_simple!(4; 3, 6), &bigi![4; 5]), point_zero!(4));
     }
 
+    #[test]
+    fn test_mul_sum() {
+        let curve = WeierstrassCurve {
+            a: bigi![4; 2],
+            b: bigi![4; 3],
+            m: bigi![4; 97]
+        };
+
+        assert_eq!(curve.mul_sum(&[], &[]), point_zero!(4));
+        assert_eq!(
+            curve.mul_sum(
+                &[point_simple!(4; 3, 6), point_simple!(4; 80, 10)],
+                &[bigi![4; 1], bigi![4; 1]]
+            ),
+            point_simple!(4; 80, 87)
+        );
+        assert_eq!(
+            curve.mul_sum(
+                &[point_simple!(4; 3, 6), point_simple!(4; 80, 10)],
+                &[bigi![4; 3], bigi![4; 0]]
+            ),
+            point_simple!(4; 80, 87)
+        );
+        assert_eq!(
+            curve.mul_sum(
+                &[point_simple!(4; 3, 6), point_simple!(4; 3, 91)],
+                &[bigi![4; 7], bigi![4; 7]]
+            ),
+            point_zero!(4)
+        );
+    }
+
+    #[test]
+    fn test_secp256r1() {
+        let schema = load_secp256r1();
+        assert!(schema.curve.is_a_minus_3());
+        assert!(schema.curve.check(&schema.generator));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+
+        // The key pair from RFC 6979, appendix A.2.5
+        let p = schema.get_point(&Bigi::<4>::from_hex(
+            "0xC9AFA9D845BA75166B5C215767B1D6934E50C3DB36E89B127B8A622B120F6721"
+        ));
+        assert_eq!(p, point!(
+            Bigi::<4>::from_hex("0x60FED4BA255A9D31C961EB74C6356D68C049B8923B61FA6CE669622E60F29FB6"),
+            Bigi::<4>::from_hex("0x7903FE1008B8BC99A41AE9E95628BC64F2F1B20C2D7E9F5177A3C294D4462299")
+        ));
+        assert_eq!(
+            schema.curve.double(&schema.generator),
+            schema.curve.add(&schema.generator, &schema.generator)
+        );
+    }
+
+    #[test]
+    fn test_secp384r1() {
+        let schema = load_secp384r1();
+        assert!(schema.curve.is_a_minus_3());
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&Bigi::<6>::from(25))));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
+    #[test]
+    fn test_secp521r1() {
+        let schema = load_secp521r1();
+        assert!(schema.curve.is_a_minus_3());
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&Bigi::<9>::from(25))));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
+    #[test]
+    fn test_bls12_381() {
+        let schema = schemas::load_bls12_381();
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&Bigi::<6>::from(25))));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
+    #[test]
+    fn test_sm2p256v1() {
+        let schema = schemas::load_sm2p256v1();
+        assert!(schema.curve.is_a_minus_3());
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&Bigi::<4>::from(25))));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
+    fn check_sec<const N: usize>(schema: &Schema<WeierstrassCurve<N>, N>,
+                                 a_minus_3: bool) {
+        assert_eq!(schema.curve.is_a_minus_3(), a_minus_3);
+        assert!(schema.curve.check(&schema.generator));
+        assert!(schema.curve.check(&schema.get_point(&Bigi::<N>::from(25))));
+        assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+    }
+
+    #[test]
+    fn test_sec_legacy() {
+        check_sec(&schemas::load_secp160k1(), false);
+        check_sec(&schemas::load_secp160r1(), true);
+        check_sec(&schemas::load_secp192k1(), false);
+        check_sec(&schemas::load_secp192r1(), true);
+        check_sec(&schemas::load_secp224k1(), false);
+        check_sec(&schemas::load_secp224r1(), true);
+
+        // Public keys of RFC 6979, appendix A.2.3 and A.2.4
+        let schema = schemas::load_secp192r1();
+        assert_eq!(
+            schema.get_point(&Bigi::<3>::from_hex(
+                "0x6FAB034934E4C0FC9AE67F5B5659A9D7D1FEFD187EE09FD4"
+            )),
+            point!(
+                Bigi::<3>::from_hex("0xAC2C77F529F91689FEA0EA5EFEC7F210D8EEA0B9E047ED56"),
+                Bigi::<3>::from_hex("0x3BC723E57670BD4887EBC732C523063D0A7C957BC97C1C43")
+            )
+        );
+        let schema = schemas::load_secp224r1();
+        assert_eq!(
+            schema.get_point(&Bigi::<4>::from_hex(
+                "0xF220266E1105BFE3083E03EC7A3A654651F45E37167E88600BF257C1"
+            )),
+            point!(
+                Bigi::<4>::from_hex("0x00CF08DA5AD719E42707FA431292DEA11244D64FC51610D94B130D6C"),
+                Bigi::<4>::from_hex("0xEEAB6F3DEBE455E3DBF85416F7030CBD94F34F2D6F232C69F3C1385A")
+            )
+        );
+    }
+
+    fn check_brainpool<const N: usize>(
+                r1: &Schema<WeierstrassCurve<N>, N>,
+                t1: &Schema<WeierstrassCurve<N>, N>,
+                z: &str
+            ) {
+        for schema in [r1, t1].iter() {
+            assert!(schema.curve.check(&schema.generator));
+            assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
+        }
+        assert!(t1.curve.is_a_minus_3());
+
+        // t1 is the twist of r1 by Z: A' = A Z^4, B' = B Z^6 and
+        // (x, y) -> (x Z^2, y Z^3)
+        let m = &r1.curve.m;
+        let z = Bigi::<N>::from_hex(z);
+        let z2 = mul_mod(&z, &z, m);
+        let z3 = mul_mod(&z2, &z, m);
+        assert_eq!(mul_mod(&r1.curve.a, &mul_mod(&z2, &z2, m), m), t1.curve.a);
+        assert_eq!(mul_mod(&r1.curve.b, &mul_mod(&z3, &z3, m), m), t1.curve.b);
+        for k in [1u64, 25, 12345].iter() {
+            let p = r1.get_point(&Bigi::<N>::from(*k));
+            assert_eq!(
+                t1.get_point(&Bigi::<N>::from(*k)),
+                point!(mul_mod(&p.x, &z2, m), mul_mod(&p.y, &z3, m))
+            );
+        }
+    }
+
+    #[test]
+    fn test_brainpool() {
+        check_brainpool(
+            &schemas::load_brainpoolp256r1(), &schemas::load_brainpoolp256t1(),
+            "0x3E2D4BD9597B58639AE7AA669CAB9837CF5CF20A2C852D10F655668DFC150EF0"
+        );
+        check_brainpool(
+            &schemas::load_brainpoolp384r1(), &schemas::load_brainpoolp384t1(),
+            "0x41DFE8DD399331F7166A66076734A89CD0D2BCDB7D068E44E1F378F41ECBAE97D2D63DBC87BCCDDCCC5DA39E8589291C"
+        );
+        check_brainpool(
+            &schemas::load_brainpoolp512r1(), &schemas::load_brainpoolp512t1(),
+            "0x12EE58E6764838B69782136F0F2D3BA06E27695716054092E60A80BEDB212B64E585D90BCE13761F85C3F1D2A64E3BE8FEA2220F01EBA5EEB0F35DBD29D922AB"
+        );
+    }
+
     #[test]
     fn test_secp256k1() {
         let schema = load_secp256k1();
@@ -224,6 +530,34 @@ mod tests {
         assert_eq!(schema.get_point(&schema.order), schema.curve.zero());
     }
 
+    #[test]
+    fn test_fp2_supersingular() {
+        // y^2 = x^3 + x over Fp2 with p = 2^127 - 1 is supersingular, so
+        // the group is Z_(p+1) x Z_(p+1)
+        let mut rng = rand::thread_rng();
+        let m = bigi![4; 1] << 127;
+        let m = m - &bigi![4; 1];
+        let one = Fp2::from_fp(bigi![4; 1]);
+        let curve = WeierstrassCurve { a: one, b: Fp2::zero(), m };
+
+        let p = loop {
+            let x = Fp2::new(Bigi::<4>::gen_random(&mut rng, 127, false) % &m,
+                             Bigi::<4>::gen_random(&mut rng, 127, false) % &m);
+            if let Ok((y, _)) = curve.find_y(&x) {
+                break GenericPoint::new(x, y);
+            }
+        };
+        assert!(curve.check(&p));
+        assert!(!curve.check(&GenericPoint::new(p.x, p.y.add(&one, &m))));
+
+        let p2 = curve.double(&p);
+        assert!(curve.check(&p2));
+        assert_eq!(curve.add(&p, &p), p2);
+        assert_eq!(curve.mul(&p, &bigi![4; 3]), curve.add(&p2, &p));
+        assert!(curve.add(&p, &curve.inv(&p)).is_zero);
+        assert!(curve.mul(&p, &(m + &bigi![4; 1])).is_zero);
+    }
+
     #[bench]
     fn bench_secp256k1_generate_pair(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
@@ -266,6 +600,34 @@ mod tests {
         bencher.iter(|| schema.curve.mul(&p, &l));
     }
 
+    #[bench]
+    fn bench_secp256k1_mul_sum(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = load_secp256k1();
+        let k = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let l1 = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let l2 = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let p = schema.get_point(&k);
+        bencher.iter(|| schema.curve.mul_sum(
+            &[p, schema.generator], &[l1, l2]
+        ));
+    }
+
+    #[bench]
+    fn bench_secp256r1_mul(bencher: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let schema = load_secp256r1();
+        let k = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let l = Bigi::<4>::gen_random(
+            &mut rng, schema.bits, false) % &schema.order;
+        let p = schema.get_point(&k);
+        bencher.iter(|| schema.curve.mul(&p, &l));
+    }
+
     #[bench]
     fn bench_secp256k1_check(bencher: &mut Bencher) {
         let mut rng = rand::thread_rng();
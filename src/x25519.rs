@@ -0,0 +1,266 @@
+//! This module implements X25519 key agreement compatible with
+//! [RFC 7748](https://www.rfc-editor.org/rfc/rfc7748). Scalars and
+//! u-coordinates are 32 bytes little-endian strings, the scalars are clamped
+//! and the top bit of u-coordinates is masked.
+//!
+//! `generate_representable_pair` gives public keys that are encoded with
+//! Elligator 2 as 32 bytes indistinguishable from random ones, the other
+//! side restores the public key with `representative_to_public_key`.
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::x25519::{x25519, generate_pair};
+//!
+//! // Generate key pairs of both sides
+//! let mut rng = rand::thread_rng();
+//! let (private_key_a, public_key_a) = generate_pair(&mut rng);
+//! let (private_key_b, public_key_b) = generate_pair(&mut rng);
+//!
+//! // Calculate the shared secret on both sides
+//! let shared_a = x25519(private_key_a, public_key_b);
+//! let shared_b = x25519(private_key_b, public_key_a);
+//!
+//! assert_eq!(shared_a, shared_b);
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use bigi::Bigi;
+use crate::point;
+use crate::base::{Point, CurveTrait};
+use crate::montgomery::MontgomeryCurve;
+use crate::schemas::{Schema, load_curve25519};
+use crate::utils::{bigi_from_le_bytes, bigi_to_le_bytes};
+
+
+/// The u-coordinate of the base point (`9`).
+pub const BASEPOINT: [u8; 32] = [
+    9, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0
+];
+
+
+/// Calculates X25519 function of the scalar and the u-coordinate.
+pub fn x25519(scalar: [u8; 32], u: [u8; 32]) -> [u8; 32] {
+    let curve = load_curve25519().curve;
+    let k = clamp(scalar);
+
+    let mut u = u;
+    u[31] &= 127;
+    let u = bigi_from_le_bytes::<4>(&u) % &curve.m;
+
+    bigi_to_array(&curve.ladder(&k, &u, 255))
+}
+
+
+/// Gets the public key for the private key: X25519 of the base point.
+pub fn get_public_key(private_key: [u8; 32]) -> [u8; 32] {
+    x25519(private_key, BASEPOINT)
+}
+
+
+/// Generates a random private key and the public key.
+pub fn generate_pair<R: Rng + ?Sized>(rng: &mut R) -> ([u8; 32], [u8; 32]) {
+    let mut private_key = [0u8; 32];
+    rng.fill(&mut private_key[..]);
+    (private_key, get_public_key(private_key))
+}
+
+
+/// Generates a random private key, the public key and its 32 bytes
+/// representative that looks like a uniformly random string. A random point
+/// of small order is added to the public key, so it covers the whole curve,
+/// the point is eliminated in `x25519` due to the clamping. So the public
+/// key differs from `get_public_key(private_key)`, but the shared secrets
+/// are the same. About a half of the keys are representable, so the key is
+/// generated several times in average.
+pub fn generate_representable_pair<R: Rng + ?Sized>(
+            rng: &mut R
+        ) -> ([u8; 32], [u8; 32], [u8; 32]) {
+    let schema = load_curve25519();
+    let z = Bigi::<4>::from(2);
+    loop {
+        let mut private_key = [0u8; 32];
+        rng.fill(&mut private_key[..]);
+        let p = schema.curve.add(
+            &schema.get_point(&clamp(private_key)),
+            &gen_low_order_point(rng, &schema)
+        );
+        if let Ok(r) = schema.curve.elligator_inverse(&p, &z) {
+            // The representative is below 2^254, the top bits are random
+            let mut representative = bigi_to_array(&r);
+            representative[31] |= rng.gen::<u8>() & 192;
+            return (private_key, bigi_to_array(&p.x), representative);
+        }
+    }
+}
+
+
+/// Restores the public key from the representative.
+pub fn representative_to_public_key(representative: [u8; 32]) -> [u8; 32] {
+    let curve = load_curve25519().curve;
+    let mut representative = representative;
+    representative[31] &= 63;
+    let r = bigi_from_le_bytes::<4>(&representative);
+    bigi_to_array(&curve.elligator_map(&r, &Bigi::<4>::from(2)).x)
+}
+
+
+/// Checks whether the shared secret is all zeros, that happens for points
+/// of small order.
+pub fn is_zero_secret(shared: &[u8; 32]) -> bool {
+    shared.iter().all(|&b| b == 0)
+}
+
+
+fn clamp(scalar: [u8; 32]) -> Bigi<4> {
+    let mut scalar = scalar;
+    scalar[0] &= 248;
+    scalar[31] &= 127;
+    scalar[31] |= 64;
+    bigi_from_le_bytes::<4>(&scalar)
+}
+
+
+/// Gets a random point of the subgroup of order 8 as `order * Q` for
+/// a random point `Q`.
+fn gen_low_order_point<R: Rng + ?Sized>(
+            rng: &mut R, schema: &Schema<MontgomeryCurve<4>, 4>
+        ) -> Point<4> {
+    loop {
+        let x = Bigi::<4>::gen_random(rng, schema.bits, false) % &schema.curve.m;
+        if let Ok(roots) = schema.curve.find_y(&x) {
+            let y = if rng.gen::<bool>() { roots.0 } else { roots.1 };
+            return schema.curve.mul(&point!(x, y), &schema.order);
+        }
+    }
+}
+
+
+fn bigi_to_array(x: &Bigi<4>) -> [u8; 32] {
+    let mut bytes = [0u8; 32];
+    bytes.copy_from_slice(&bigi_to_le_bytes(x, 32));
+    bytes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::utils::{bytes_from_hex, bytes_to_hex};
+
+    fn from_hex(hex: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        bytes.copy_from_slice(&bytes_from_hex(hex).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn test_x25519() {
+        assert_eq!(
+            bytes_to_hex(&x25519(
+                from_hex("a546e36bf0527c9d3b16154b82465edd62144c0ac1fc5a18506a2244ba449ac4"),
+                from_hex("e6db6867583030db3594c1a424b15f7c726624ec26b3353b10a903a6d0ab1c4c")
+            )),
+            "c3da55379de9c6908e94ea4df28d084f32eccf03491c71f754b4075577a28552"
+        );
+        assert_eq!(
+            bytes_to_hex(&x25519(
+                from_hex("4b66e9d4d1b4673c5ad22691957d6af5c11b6421e0ea01d42ca4169e7918ba0d"),
+                from_hex("e5210f12786811d3f4b7959d0538ae2c31dbe7106fc03c3efc4cd549c715a493")
+            )),
+            "95cbde9476e8907d7aade45cb4b873f88b595a68799fa152e6f8f7647aac7957"
+        );
+    }
+
+    #[test]
+    fn test_x25519_diffie_hellman() {
+        let private_key_a = from_hex(
+            "77076d0a7318a57d3c16c17251b26645df4c2f87ebc0992ab177fba51db92c2a"
+        );
+        let private_key_b = from_hex(
+            "5dab087e624a8a4b79e17f8b83800ee66f3bb1292618b6fd1c2f8b27ff88e0eb"
+        );
+        let public_key_a = get_public_key(private_key_a);
+        let public_key_b = get_public_key(private_key_b);
+        assert_eq!(
+            bytes_to_hex(&public_key_a),
+            "8520f0098930a754748b7ddcb43ef75a0dbf3a0d26381af4eba4a98eaa9b4e6a"
+        );
+        assert_eq!(
+            bytes_to_hex(&public_key_b),
+            "de9edb7d7b7dc1b4d35b61c2ece435373f8343c85b78674dadfc7e146f882b4f"
+        );
+
+        let shared = x25519(private_key_a, public_key_b);
+        assert_eq!(shared, x25519(private_key_b, public_key_a));
+        assert_eq!(
+            bytes_to_hex(&shared),
+            "4a5d9d5ba4ce2de1728e3bf480350f25e07e21c947d19e3376f09b3c1e161742"
+        );
+        assert!(!is_zero_secret(&shared));
+
+        // u = 0 is a point of small order
+        assert!(is_zero_secret(&x25519(private_key_a, [0u8; 32])));
+    }
+
+    #[test]
+    fn test_x25519_iterated() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for i in 1..=1000 {
+            let res = x25519(k, u);
+            u = k;
+            k = res;
+            if i == 1 {
+                assert_eq!(
+                    bytes_to_hex(&k),
+                    "422c8e7a6227d7bca1350b3e2bb7279f7897b87bb6854b783c60e80311ae3079"
+                );
+            }
+        }
+        assert_eq!(
+            bytes_to_hex(&k),
+            "684cf59ba83309552800ef566f2f4d3c1c3887c49360e3875f2eb94d99532c51"
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn test_x25519_iterated_million() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for _ in 0..1000000 {
+            let res = x25519(k, u);
+            u = k;
+            k = res;
+        }
+        assert_eq!(
+            bytes_to_hex(&k),
+            "7c3911e0ab2586fd864497297e575e6f3bc601c0883c30df5f4dd2d24f665424"
+        );
+    }
+
+    #[test]
+    fn test_representable_pair() {
+        let mut rng = rand::thread_rng();
+        let (private_key_b, public_key_b) = generate_pair(&mut rng);
+        for _ in 0..5 {
+            let (private_key_a, public_key_a, representative) =
+                generate_representable_pair(&mut rng);
+            assert_eq!(representative_to_public_key(representative),
+                       public_key_a);
+            assert_eq!(x25519(private_key_a, public_key_b),
+                       x25519(private_key_b, public_key_a));
+        }
+    }
+
+    #[bench]
+    fn bench_x25519(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let (private_key, _public_key) = generate_pair(&mut rng);
+        let (_private_key, public_key) = generate_pair(&mut rng);
+        b.iter(|| x25519(private_key, public_key));
+    }
+}
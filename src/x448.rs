@@ -0,0 +1,159 @@
+//! This module implements X448 key agreement compatible with
+//! [RFC 7748](https://www.rfc-editor.org/rfc/rfc7748). Scalars and
+//! u-coordinates are 56 bytes little-endian strings, the scalars are clamped.
+//!
+//! Example:
+//! ```rust
+//! use bigi_ecc::x448::{x448, generate_pair};
+//!
+//! // Generate key pairs of both sides
+//! let mut rng = rand::thread_rng();
+//! let (private_key_a, public_key_a) = generate_pair(&mut rng);
+//! let (private_key_b, public_key_b) = generate_pair(&mut rng);
+//!
+//! // Calculate the shared secret on both sides
+//! let shared_a = x448(private_key_a, public_key_b);
+//! let shared_b = x448(private_key_b, public_key_a);
+//!
+//! assert_eq!(shared_a, shared_b);
+//! ```
+extern crate rand;
+
+use rand::Rng;
+use bigi::Bigi;
+use crate::schemas::load_curve448;
+use crate::utils::{bigi_from_le_bytes, bigi_to_le_bytes};
+
+
+/// The u-coordinate of the base point (`5`).
+pub const BASEPOINT: [u8; 56] = [
+    5, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+    0, 0, 0, 0, 0, 0, 0, 0
+];
+
+
+/// Calculates X448 function of the scalar and the u-coordinate.
+pub fn x448(scalar: [u8; 56], u: [u8; 56]) -> [u8; 56] {
+    let curve = load_curve448().curve;
+
+    let mut scalar = scalar;
+    scalar[0] &= 252;
+    scalar[55] |= 128;
+    let k = bigi_from_le_bytes::<7>(&scalar);
+
+    let u = bigi_from_le_bytes::<7>(&u) % &curve.m;
+
+    bigi_to_array(&curve.ladder(&k, &u, 448))
+}
+
+
+/// Gets the public key for the private key: X448 of the base point.
+pub fn get_public_key(private_key: [u8; 56]) -> [u8; 56] {
+    x448(private_key, BASEPOINT)
+}
+
+
+/// Generates a random private key and the public key.
+pub fn generate_pair<R: Rng + ?Sized>(rng: &mut R) -> ([u8; 56], [u8; 56]) {
+    let mut private_key = [0u8; 56];
+    rng.fill(&mut private_key[..]);
+    (private_key, get_public_key(private_key))
+}
+
+
+/// Checks whether the shared secret is all zeros, that happens for points
+/// of small order.
+pub fn is_zero_secret(shared: &[u8; 56]) -> bool {
+    shared.iter().all(|&b| b == 0)
+}
+
+
+fn bigi_to_array(x: &Bigi<7>) -> [u8; 56] {
+    let mut bytes = [0u8; 56];
+    bytes.copy_from_slice(&bigi_to_le_bytes(x, 56));
+    bytes
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use test::Bencher;
+    use crate::utils::{bytes_from_hex, bytes_to_hex};
+
+    fn from_hex(hex: &str) -> [u8; 56] {
+        let mut bytes = [0u8; 56];
+        bytes.copy_from_slice(&bytes_from_hex(hex).unwrap());
+        bytes
+    }
+
+    #[test]
+    fn test_x448() {
+        assert_eq!(
+            bytes_to_hex(&x448(
+                from_hex("3d262fddf9ec8e88495266fea19a34d28882acef045104d0d1aae121700a779c984c24f8cdd78fbff44943eba368f54b29259a4f1c600ad3"),
+                from_hex("06fce640fa3487bfda5f6cf2d5263f8aad88334cbd07437f020f08f9814dc031ddbdc38c19c6da2583fa5429db94ada18aa7a7fb4ef8a086")
+            )),
+            "ce3e4ff95a60dc6697da1db1d85e6afbdf79b50a2412d7546d5f239fe14fbaadeb445fc66a01b0779d98223961111e21766282f73dd96b6f"
+        );
+    }
+
+    #[test]
+    fn test_x448_diffie_hellman() {
+        let private_key_a = from_hex(
+            "9a8f4925d1519f5775cf46b04b5800d4ee9ee8bae8bc5565d498c28dd9c9baf574a9419744897391006382a6f127ab1d9ac2d8c0a598726b"
+        );
+        let private_key_b = from_hex(
+            "1c306a7ac2a0e2e0990b294470cba339e6453772b075811d8fad0d1d6927c120bb5ee8972b0d3e21374c9c921b09d1b0366f10b65173992d"
+        );
+        let public_key_a = get_public_key(private_key_a);
+        let public_key_b = get_public_key(private_key_b);
+        assert_eq!(
+            bytes_to_hex(&public_key_a),
+            "9b08f7cc31b7e3e67d22d5aea121074a273bd2b83de09c63faa73d2c22c5d9bbc836647241d953d40c5b12da88120d53177f80e532c41fa0"
+        );
+        assert_eq!(
+            bytes_to_hex(&public_key_b),
+            "3eb7a829b0cd20f5bcfc0b599b6feccf6da4627107bdb0d4f345b43027d8b972fc3e34fb4232a13ca706dcb57aec3dae07bdc1c67bf33609"
+        );
+
+        let shared = x448(private_key_a, public_key_b);
+        assert_eq!(shared, x448(private_key_b, public_key_a));
+        assert_eq!(
+            bytes_to_hex(&shared),
+            "07fff4181ac6cc95ec1c16a94a0f74d12da232ce40a77552281d282bb60c0b56fd2464c335543936521c24403085d59a449a5037514a879d"
+        );
+        assert!(!is_zero_secret(&shared));
+    }
+
+    #[test]
+    fn test_x448_iterated() {
+        let mut k = BASEPOINT;
+        let mut u = BASEPOINT;
+        for i in 1..=1000 {
+            let res = x448(k, u);
+            u = k;
+            k = res;
+            if i == 1 {
+                assert_eq!(
+                    bytes_to_hex(&k),
+                    "3f482c8a9f19b01e6c46ee9711d9dc14fd4bf67af30765c2ae2b846a4d23a8cd0db897086239492caf350b51f833868b9bc2b3bca9cf4113"
+                );
+            }
+        }
+        assert_eq!(
+            bytes_to_hex(&k),
+            "aa3b4749d55b9daf1e5b00288826c467274ce3ebbdd5c17b975e09d4af6c67cf10d087202db88286e2b79fceea3ec353ef54faa26e219f38"
+        );
+    }
+
+    #[bench]
+    fn bench_x448(b: &mut Bencher) {
+        let mut rng = rand::thread_rng();
+        let (private_key, _public_key) = generate_pair(&mut rng);
+        let (_private_key, public_key) = generate_pair(&mut rng);
+        b.iter(|| x448(private_key, public_key));
+    }
+}